@@ -1,28 +1,87 @@
 use std::collections::{HashMap, HashSet};
 
+use serde::{Deserialize, Serialize};
+
+use crate::credit::CreditTotals;
+use crate::transport::VesselLocation;
 use crate::types::{GoodId, Quantity, SettlementId};
-use crate::world::World;
+use crate::world::{ConstructionTotals, World};
+
+/// Goods flows tallied at one settlement during a tick.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SettlementFlows {
+    /// Recipe outputs.
+    pub produced: HashMap<GoodId, Quantity>,
+    /// Recipe inputs consumed and tools worn.
+    pub used_in_production: HashMap<GoodId, Quantity>,
+    /// Grain handed to unemployed pops.
+    pub subsistence: HashMap<GoodId, Quantity>,
+    /// Consumed by pops.
+    pub consumed: HashMap<GoodId, Quantity>,
+    /// Currency passed from pops who died to their heirs.
+    pub inherited_currency: f64,
+    /// Goods passed from pops who died to their heirs.
+    pub inherited_goods: HashMap<GoodId, Quantity>,
+    /// Goods held by pops who died with no one left to inherit them.
+    pub lost_with_dead: HashMap<GoodId, Quantity>,
+    /// Currency held by pops who died with no one left to inherit it.
+    #[serde(default)]
+    pub currency_lost_with_dead: f64,
+}
+
+/// One settlement's stocks at a tick boundary, by holder class.
+#[derive(Debug, Clone, Default)]
+pub struct SettlementFlowSnapshot {
+    pub pop_currency: f64,
+    /// Cash in the treasuries of facilities here. Merchant purses are not
+    /// tied to a settlement and only appear world-wide.
+    pub facility_currency: f64,
+    pub pop_goods: HashMap<GoodId, Quantity>,
+    pub merchant_goods: HashMap<GoodId, Quantity>,
+    /// Cargo aboard vessels docked here.
+    pub vessel_goods: HashMap<GoodId, Quantity>,
+    pub imports_qty: HashMap<GoodId, Quantity>,
+    pub exports_qty: HashMap<GoodId, Quantity>,
+    pub imports_value: f64,
+    pub exports_value: f64,
+    /// Flows tallied during the tick ending at this boundary.
+    pub flows: SettlementFlows,
+}
 
 /// World-wide stock snapshot captured at a tick boundary.
 #[derive(Debug, Clone, Default)]
 pub struct WorldFlowSnapshot {
     pub pop_currency: f64,
     pub merchant_currency: f64,
+    /// Cash held in facility treasuries.
+    pub facility_currency: f64,
     pub goods: HashMap<GoodId, Quantity>,
     pub imports_qty: HashMap<GoodId, Quantity>,
     pub exports_qty: HashMap<GoodId, Quantity>,
     pub imports_value: HashMap<GoodId, f64>,
     pub exports_value: HashMap<GoodId, f64>,
+    pub transport_paid: f64,
+    pub cargo_lost: HashMap<GoodId, Quantity>,
+    pub cargo_spoiled: HashMap<GoodId, Quantity>,
+    /// Balance owed on all open loans.
+    pub loans: f64,
+    pub credit: CreditTotals,
+    pub construction: ConstructionTotals,
+    pub settlements: HashMap<SettlementId, SettlementFlowSnapshot>,
 }
 
 /// Per-tick stock-flow decomposition output.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TickStockFlow {
     pub tick: u64,
     pub pop_currency_before: f64,
     pub pop_currency_after: f64,
     pub merchant_currency_before: f64,
     pub merchant_currency_after: f64,
+    #[serde(default)]
+    pub facility_currency_before: f64,
+    #[serde(default)]
+    pub facility_currency_after: f64,
     pub currency_before: f64,
     pub currency_after: f64,
     pub currency_delta: f64,
@@ -30,11 +89,177 @@ pub struct TickStockFlow {
     pub currency_residual: f64,
     pub imports_value_delta: f64,
     pub exports_value_delta: f64,
+    /// Freight and upkeep paid this tick (currency leaving the economy).
+    #[serde(default)]
+    pub transport_paid_delta: f64,
+    /// Construction costs paid this tick (currency leaving the economy).
+    #[serde(default)]
+    pub construction_spent_delta: f64,
+    /// Salvage refunded on demolition this tick (currency entering it).
+    #[serde(default)]
+    pub salvage_delta: f64,
     pub goods_before: HashMap<GoodId, Quantity>,
     pub goods_after: HashMap<GoodId, Quantity>,
     pub goods_delta: HashMap<GoodId, Quantity>,
     pub imports_qty_delta: HashMap<GoodId, Quantity>,
     pub exports_qty_delta: HashMap<GoodId, Quantity>,
+    /// Goods destroyed by route incidents this tick.
+    #[serde(default)]
+    pub goods_destroyed: HashMap<GoodId, Quantity>,
+    /// Recipe outputs.
+    #[serde(default)]
+    pub goods_produced: HashMap<GoodId, Quantity>,
+    /// Recipe inputs consumed and tools worn.
+    #[serde(default)]
+    pub goods_used_in_production: HashMap<GoodId, Quantity>,
+    /// Subsistence yield handed to unemployed pops.
+    #[serde(default)]
+    pub goods_subsistence: HashMap<GoodId, Quantity>,
+    /// Consumed by pops.
+    #[serde(default)]
+    pub goods_consumed: HashMap<GoodId, Quantity>,
+    /// The part of `goods_destroyed` that spoiled in transit.
+    #[serde(default)]
+    pub goods_spoiled: HashMap<GoodId, Quantity>,
+    /// The part of `goods_destroyed` lost with wrecked vessels.
+    #[serde(default)]
+    pub goods_lost_in_transit: HashMap<GoodId, Quantity>,
+    /// Held by pops who died without heirs.
+    #[serde(default)]
+    pub goods_lost_with_dead: HashMap<GoodId, Quantity>,
+    /// Currency held by pops who died without heirs (leaving the economy).
+    #[serde(default)]
+    pub currency_lost_with_dead: f64,
+    /// `goods_delta` less every source and sink above; should be ~0.
+    #[serde(default)]
+    pub goods_residual: HashMap<GoodId, Quantity>,
+    /// Balance owed on open loans between merchants. Loans only move
+    /// currency between merchants, so they leave `currency_residual` alone.
+    #[serde(default)]
+    pub loans_before: f64,
+    #[serde(default)]
+    pub loans_after: f64,
+    #[serde(default)]
+    pub loans_issued_delta: f64,
+    #[serde(default)]
+    pub interest_accrued_delta: f64,
+    #[serde(default)]
+    pub loans_repaid_delta: f64,
+    /// Change in loan balances less issuance and interest plus repayments;
+    /// should be ~0.
+    #[serde(default)]
+    pub loan_residual: f64,
+    /// The same tick broken down by settlement, in settlement id order.
+    #[serde(default)]
+    pub settlements: Vec<SettlementStockFlow>,
+}
+
+/// One settlement's share of a tick's stock-flow decomposition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettlementStockFlow {
+    pub settlement: SettlementId,
+    pub pop_currency_before: f64,
+    pub pop_currency_after: f64,
+    pub facility_currency_before: f64,
+    pub facility_currency_after: f64,
+    pub pop_goods_delta: HashMap<GoodId, Quantity>,
+    pub merchant_goods_delta: HashMap<GoodId, Quantity>,
+    pub vessel_goods_delta: HashMap<GoodId, Quantity>,
+    pub imports_qty_delta: HashMap<GoodId, Quantity>,
+    pub exports_qty_delta: HashMap<GoodId, Quantity>,
+    pub imports_value_delta: f64,
+    pub exports_value_delta: f64,
+    pub flows: SettlementFlows,
+}
+
+impl SettlementStockFlow {
+    /// Value of goods exported to the outside market less value imported.
+    pub fn trade_balance(&self) -> f64 {
+        self.exports_value_delta - self.imports_value_delta
+    }
+
+    /// Change in all holders' stock of one good.
+    pub fn goods_delta(&self, good: GoodId) -> Quantity {
+        [
+            &self.pop_goods_delta,
+            &self.merchant_goods_delta,
+            &self.vessel_goods_delta,
+        ]
+        .iter()
+        .map(|deltas| deltas.get(&good).copied().unwrap_or(0.0))
+        .sum()
+    }
+}
+
+fn delta_by_good(
+    before: &HashMap<GoodId, Quantity>,
+    after: &HashMap<GoodId, Quantity>,
+) -> HashMap<GoodId, Quantity> {
+    let keys: HashSet<GoodId> = before.keys().chain(after.keys()).copied().collect();
+    keys.into_iter()
+        .map(|good| {
+            let after_qty = after.get(&good).copied().unwrap_or(0.0);
+            let before_qty = before.get(&good).copied().unwrap_or(0.0);
+            (good, after_qty - before_qty)
+        })
+        .collect()
+}
+
+fn add_goods(totals: &mut HashMap<GoodId, Quantity>, goods: &HashMap<GoodId, Quantity>) {
+    for (good, qty) in goods {
+        *totals.entry(*good).or_insert(0.0) += *qty;
+    }
+}
+
+/// A merchant's holdings at one settlement, valued at the local `price_ema`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SettlementPosition {
+    /// Cash in the treasuries of the merchant's facilities here.
+    pub facility_cash: f64,
+    /// Stockpile and docked cargo at market value.
+    pub inventory: f64,
+    /// Construction cost of the merchant's facilities here, net of
+    /// amortization to date.
+    pub facility_book_value: f64,
+}
+
+impl SettlementPosition {
+    pub fn total(&self) -> f64 {
+        self.facility_cash + self.inventory + self.facility_book_value
+    }
+}
+
+/// A merchant's assets and liabilities at one point in time.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BalanceSheet {
+    /// The merchant's own purse, held at no particular settlement.
+    pub cash: f64,
+    /// Holdings by settlement, in settlement id order.
+    pub settlements: Vec<(SettlementId, SettlementPosition)>,
+    /// Cargo aboard vessels under way, valued at the destination.
+    pub in_transit: f64,
+    pub receivables: f64,
+    pub debts: f64,
+}
+
+impl BalanceSheet {
+    pub fn total_assets(&self) -> f64 {
+        self.cash
+            + self.settlements.iter().map(|(_, p)| p.total()).sum::<f64>()
+            + self.in_transit
+            + self.receivables
+    }
+
+    pub fn net_worth(&self) -> f64 {
+        self.total_assets() - self.debts
+    }
+
+    pub fn position(&self, settlement: SettlementId) -> Option<&SettlementPosition> {
+        self.settlements
+            .iter()
+            .find(|(id, _)| *id == settlement)
+            .map(|(_, p)| p)
+    }
 }
 
 fn rollup_by_good<T: Copy + Default + std::ops::AddAssign>(
@@ -54,8 +279,15 @@ pub fn capture_world_flow_snapshot(world: &World) -> WorldFlowSnapshot {
         .values()
         .flat_map(|s| s.pops.values())
         .map(|p| p.currency)
-        .sum();
+        .sum::<f64>()
+        + world.migrants.iter().map(|m| m.pop.currency).sum::<f64>();
     let merchant_currency: f64 = world.merchants.values().map(|m| m.currency).sum();
+    let facility_currency: f64 = world
+        .settlements
+        .values()
+        .flat_map(|s| s.facilities.values())
+        .map(|f| f.currency)
+        .sum();
 
     let mut goods: HashMap<GoodId, Quantity> = HashMap::new();
     for settlement in world.settlements.values() {
@@ -65,6 +297,11 @@ pub fn capture_world_flow_snapshot(world: &World) -> WorldFlowSnapshot {
             }
         }
     }
+    for migrant in &world.migrants {
+        for (good, qty) in &migrant.pop.stocks {
+            *goods.entry(*good).or_insert(0.0) += *qty;
+        }
+    }
     for merchant in world.merchants.values() {
         for stockpile in merchant.stockpiles.values() {
             for (good, qty) in &stockpile.goods {
@@ -72,15 +309,111 @@ pub fn capture_world_flow_snapshot(world: &World) -> WorldFlowSnapshot {
             }
         }
     }
+    // Cargo aboard vessels, docked or in transit
+    for vessel in world.vessels.values() {
+        for (good, qty) in &vessel.cargo.goods {
+            *goods.entry(*good).or_insert(0.0) += *qty;
+        }
+    }
+
+    let mut settlements: HashMap<SettlementId, SettlementFlowSnapshot> = world
+        .settlements
+        .iter()
+        .map(|(id, settlement)| {
+            let mut snapshot = SettlementFlowSnapshot {
+                pop_currency: settlement.pops.values().map(|p| p.currency).sum(),
+                facility_currency: settlement.facilities.values().map(|f| f.currency).sum(),
+                flows: settlement.flows.clone(),
+                ..Default::default()
+            };
+            for pop in settlement.pops.values() {
+                add_goods(&mut snapshot.pop_goods, &pop.stocks);
+            }
+            (*id, snapshot)
+        })
+        .collect();
+    for merchant in world.merchants.values() {
+        for (id, stockpile) in &merchant.stockpiles {
+            if let Some(snapshot) = settlements.get_mut(id) {
+                add_goods(&mut snapshot.merchant_goods, &stockpile.goods);
+            }
+        }
+    }
+    for vessel in world.vessels.values() {
+        if let VesselLocation::Docked(at) = vessel.location
+            && let Some(snapshot) = settlements.get_mut(&at)
+        {
+            add_goods(&mut snapshot.vessel_goods, &vessel.cargo.goods);
+        }
+    }
+    let outside = &world.outside_flow_totals;
+    for ((id, good), qty) in &outside.imports_qty {
+        if let Some(snapshot) = settlements.get_mut(id) {
+            *snapshot.imports_qty.entry(*good).or_insert(0.0) += *qty;
+        }
+    }
+    for ((id, good), qty) in &outside.exports_qty {
+        if let Some(snapshot) = settlements.get_mut(id) {
+            *snapshot.exports_qty.entry(*good).or_insert(0.0) += *qty;
+        }
+    }
+    for (id, snapshot) in settlements.iter_mut() {
+        let value_at = |values: &HashMap<(SettlementId, GoodId), f64>| {
+            let mut goods: Vec<(GoodId, f64)> = values
+                .iter()
+                .filter(|((s, _), _)| s == id)
+                .map(|((_, g), v)| (*g, *v))
+                .collect();
+            goods.sort_by_key(|(g, _)| *g);
+            goods.iter().map(|(_, v)| v).sum::<f64>()
+        };
+        snapshot.imports_value = value_at(&outside.imports_value);
+        snapshot.exports_value = value_at(&outside.exports_value);
+    }
 
     WorldFlowSnapshot {
         pop_currency,
         merchant_currency,
+        facility_currency,
         goods,
         imports_qty: rollup_by_good(&world.outside_flow_totals.imports_qty),
         exports_qty: rollup_by_good(&world.outside_flow_totals.exports_qty),
         imports_value: rollup_by_good(&world.outside_flow_totals.imports_value),
         exports_value: rollup_by_good(&world.outside_flow_totals.exports_value),
+        transport_paid: world.transport_totals.total_paid(),
+        cargo_lost: world.transport_totals.cargo_lost.clone(),
+        cargo_spoiled: world.transport_totals.cargo_spoiled.clone(),
+        loans: crate::determinism::sorted_loan_ids(world.loans.keys().copied())
+            .iter()
+            .map(|id| world.loans[id].balance())
+            .sum(),
+        credit: world.credit_totals.clone(),
+        construction: world.construction_totals.clone(),
+        settlements,
+    }
+}
+
+/// Decompose one settlement's stock changes. Flows come from `after`,
+/// which closes the tick they were tallied in.
+fn decompose_settlement_flow(
+    settlement: SettlementId,
+    before: &SettlementFlowSnapshot,
+    after: &SettlementFlowSnapshot,
+) -> SettlementStockFlow {
+    SettlementStockFlow {
+        settlement,
+        pop_currency_before: before.pop_currency,
+        pop_currency_after: after.pop_currency,
+        facility_currency_before: before.facility_currency,
+        facility_currency_after: after.facility_currency,
+        pop_goods_delta: delta_by_good(&before.pop_goods, &after.pop_goods),
+        merchant_goods_delta: delta_by_good(&before.merchant_goods, &after.merchant_goods),
+        vessel_goods_delta: delta_by_good(&before.vessel_goods, &after.vessel_goods),
+        imports_qty_delta: delta_by_good(&before.imports_qty, &after.imports_qty),
+        exports_qty_delta: delta_by_good(&before.exports_qty, &after.exports_qty),
+        imports_value_delta: after.imports_value - before.imports_value,
+        exports_value_delta: after.exports_value - before.exports_value,
+        flows: after.flows.clone(),
     }
 }
 
@@ -94,8 +427,10 @@ pub fn decompose_tick_flow(
     let pop_currency_after = after.pop_currency;
     let merchant_currency_before = before.merchant_currency;
     let merchant_currency_after = after.merchant_currency;
-    let currency_before = pop_currency_before + merchant_currency_before;
-    let currency_after = pop_currency_after + merchant_currency_after;
+    let facility_currency_before = before.facility_currency;
+    let facility_currency_after = after.facility_currency;
+    let currency_before = pop_currency_before + merchant_currency_before + facility_currency_before;
+    let currency_after = pop_currency_after + merchant_currency_after + facility_currency_after;
     let currency_delta = currency_after - currency_before;
 
     let imports_value_delta: f64 = after
@@ -109,7 +444,20 @@ pub fn decompose_tick_flow(
         .map(|(good, qty_after)| qty_after - before.exports_value.get(good).copied().unwrap_or(0.0))
         .sum();
     let expected_currency_delta_from_external = exports_value_delta - imports_value_delta;
-    let currency_residual = currency_delta - expected_currency_delta_from_external;
+    let transport_paid_delta = after.transport_paid - before.transport_paid;
+    let construction_spent_delta = after.construction.spent - before.construction.spent;
+    let salvage_delta = after.construction.salvaged - before.construction.salvaged;
+    let mut settlement_ids: Vec<SettlementId> = after.settlements.keys().copied().collect();
+    settlement_ids.sort_by_key(|id| id.0);
+    let currency_lost_with_dead: f64 = settlement_ids
+        .iter()
+        .map(|id| after.settlements[id].flows.currency_lost_with_dead)
+        .sum();
+    let currency_residual = currency_delta - expected_currency_delta_from_external
+        + transport_paid_delta
+        + construction_spent_delta
+        - salvage_delta
+        + currency_lost_with_dead;
 
     let mut goods_keys: HashSet<GoodId> = HashSet::new();
     goods_keys.extend(before.goods.keys().copied());
@@ -147,12 +495,91 @@ pub fn decompose_tick_flow(
         })
         .collect();
 
+    // Cargo losses only ever accumulate, so keys present after cover both.
+    let goods_destroyed: HashMap<GoodId, Quantity> = after
+        .cargo_lost
+        .iter()
+        .map(|(good, lost_after)| {
+            let lost_before = before.cargo_lost.get(good).copied().unwrap_or(0.0);
+            (*good, lost_after - lost_before)
+        })
+        .filter(|(_, qty)| *qty > 0.0)
+        .collect();
+    let goods_spoiled: HashMap<GoodId, Quantity> =
+        delta_by_good(&before.cargo_spoiled, &after.cargo_spoiled)
+            .into_iter()
+            .filter(|(_, qty)| *qty > 0.0)
+            .collect();
+    let goods_lost_in_transit: HashMap<GoodId, Quantity> = goods_destroyed
+        .iter()
+        .map(|(good, qty)| (*good, qty - goods_spoiled.get(good).copied().unwrap_or(0.0)))
+        .filter(|(_, qty)| *qty > 0.0)
+        .collect();
+
+    let empty = SettlementFlowSnapshot::default();
+    let settlements: Vec<SettlementStockFlow> = settlement_ids
+        .into_iter()
+        .map(|id| {
+            let before = before.settlements.get(&id).unwrap_or(&empty);
+            decompose_settlement_flow(id, before, &after.settlements[&id])
+        })
+        .collect();
+
+    let mut goods_produced = HashMap::new();
+    let mut goods_used_in_production = HashMap::new();
+    let mut goods_subsistence = HashMap::new();
+    let mut goods_consumed = HashMap::new();
+    let mut goods_lost_with_dead = HashMap::new();
+    for settlement in &settlements {
+        let flows = &settlement.flows;
+        add_goods(&mut goods_produced, &flows.produced);
+        add_goods(&mut goods_used_in_production, &flows.used_in_production);
+        add_goods(&mut goods_subsistence, &flows.subsistence);
+        add_goods(&mut goods_consumed, &flows.consumed);
+        add_goods(&mut goods_lost_with_dead, &flows.lost_with_dead);
+    }
+
+    let sources = [&goods_produced, &goods_subsistence, &imports_qty_delta];
+    let sinks = [
+        &goods_used_in_production,
+        &goods_consumed,
+        &exports_qty_delta,
+        &goods_destroyed,
+        &goods_lost_with_dead,
+    ];
+    let mut residual_keys: HashSet<GoodId> = goods_delta.keys().copied().collect();
+    for term in sources.iter().chain(&sinks) {
+        residual_keys.extend(term.keys().copied());
+    }
+    let term_total = |terms: &[&HashMap<GoodId, Quantity>], good: &GoodId| -> Quantity {
+        terms
+            .iter()
+            .map(|term| term.get(good).copied().unwrap_or(0.0))
+            .sum()
+    };
+    let goods_residual: HashMap<GoodId, Quantity> = residual_keys
+        .into_iter()
+        .map(|good| {
+            let delta = goods_delta.get(&good).copied().unwrap_or(0.0);
+            let explained = term_total(&sources, &good) - term_total(&sinks, &good);
+            (good, delta - explained)
+        })
+        .collect();
+
+    let loans_issued_delta = after.credit.issued - before.credit.issued;
+    let interest_accrued_delta = after.credit.interest_accrued - before.credit.interest_accrued;
+    let loans_repaid_delta = after.credit.total_repaid() - before.credit.total_repaid();
+    let loan_residual = (after.loans - before.loans)
+        - (loans_issued_delta + interest_accrued_delta - loans_repaid_delta);
+
     TickStockFlow {
         tick,
         pop_currency_before,
         pop_currency_after,
         merchant_currency_before,
         merchant_currency_after,
+        facility_currency_before,
+        facility_currency_after,
         currency_before,
         currency_after,
         currency_delta,
@@ -160,10 +587,30 @@ pub fn decompose_tick_flow(
         currency_residual,
         imports_value_delta,
         exports_value_delta,
+        transport_paid_delta,
+        construction_spent_delta,
+        salvage_delta,
         goods_before: before.goods.clone(),
         goods_after: after.goods.clone(),
         goods_delta,
         imports_qty_delta,
         exports_qty_delta,
+        goods_destroyed,
+        goods_produced,
+        goods_used_in_production,
+        goods_subsistence,
+        goods_consumed,
+        goods_spoiled,
+        goods_lost_in_transit,
+        goods_lost_with_dead,
+        currency_lost_with_dead,
+        goods_residual,
+        loans_before: before.loans,
+        loans_after: after.loans,
+        loans_issued_delta,
+        interest_accrued_delta,
+        loans_repaid_delta,
+        loan_residual,
+        settlements,
     }
 }
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::agents::{MarketSnapshot, MerchantAgent};
+use crate::labor::SkillId;
+use crate::market::Order;
+use crate::production::{Facility, FacilityType, Recipe, RecipeId};
+use crate::transport::Vessel;
+use crate::types::{FacilityHandle, GoodId, LoanId, Price, Quantity, SettlementId, VesselId};
+
+// === OBSERVATION ===
+
+/// What a merchant can see at one settlement where it has presence. Market
+/// figures come from the merchant's own snapshot, refreshed this tick.
+#[derive(Debug)]
+pub struct SettlementObservation<'a> {
+    pub id: SettlementId,
+    pub name: &'a str,
+    pub pop_count: usize,
+    pub price_ema: &'a HashMap<GoodId, Price>,
+    pub wage_ema: &'a HashMap<SkillId, Price>,
+    /// Facilities this merchant owns here, sorted by key.
+    pub facilities: Vec<(FacilityHandle, &'a Facility)>,
+}
+
+/// Read-only view handed to a [`MerchantController`] once per tick, after
+/// production and before the market phase.
+#[derive(Debug)]
+pub struct MerchantObservation<'a> {
+    pub tick: u64,
+    pub merchant: &'a MerchantAgent,
+    /// Settlements where the merchant owns a facility, sorted by id.
+    pub settlements: Vec<SettlementObservation<'a>>,
+    /// Vessels this merchant owns, sorted by id.
+    pub vessels: Vec<&'a Vessel>,
+    /// Recipes the world is running this tick.
+    pub recipes: &'a [Recipe],
+}
+
+impl MerchantObservation<'_> {
+    /// The merchant's latest snapshot of any settlement it has heard about.
+    /// This is the only view of remote markets a controller gets.
+    pub fn market(&self, settlement: SettlementId) -> Option<&MarketSnapshot> {
+        self.merchant.knowledge.get(settlement)
+    }
+
+    /// How many ticks old the merchant's snapshot of a settlement is.
+    pub fn market_age(&self, settlement: SettlementId) -> Option<u64> {
+        self.merchant.knowledge.age(settlement, self.tick)
+    }
+}
+
+// === ACTIONS ===
+
+/// A decision returned by a [`MerchantController`].
+///
+/// `Trade` orders go to this tick's market at that settlement and are
+/// dropped where the merchant has no presence. The structural actions are
+/// applied at the end of the tick, before its closing stock-flow snapshot,
+/// so their costs show up in that tick's `TickStockFlow`.
+#[derive(Debug, Clone)]
+pub enum MerchantAction {
+    Trade {
+        settlement: SettlementId,
+        orders: Vec<Order>,
+    },
+    SetRecipePriorities {
+        facility: FacilityHandle,
+        priorities: Vec<RecipeId>,
+    },
+    Build {
+        facility_type: FacilityType,
+        settlement: SettlementId,
+    },
+    Demolish {
+        facility: FacilityHandle,
+    },
+    /// Move currency from the merchant into a facility's treasury.
+    Deposit {
+        facility: FacilityHandle,
+        amount: f64,
+    },
+    /// Move currency from a facility's treasury back to the merchant.
+    Withdraw {
+        facility: FacilityHandle,
+        amount: f64,
+    },
+    /// Load `cargo` from the stockpile where the vessel is docked and send
+    /// it to `to`. Nothing is loaded if the vessel cannot depart.
+    Dispatch {
+        vessel: VesselId,
+        to: SettlementId,
+        cargo: Vec<(GoodId, Quantity)>,
+    },
+    /// Have a report on `from`'s market sent over land to `to`; it joins
+    /// the merchant's knowledge when it arrives.
+    SendCourier {
+        from: SettlementId,
+        to: SettlementId,
+    },
+    /// Pay down a loan this merchant owes ahead of schedule.
+    RepayLoan {
+        loan: LoanId,
+        amount: f64,
+    },
+}
+
+// === CONTROLLER ===
+
+/// Decision-making for one merchant. Register per merchant with
+/// [`World::set_merchant_controller`](crate::World::set_merchant_controller);
+/// merchants without one use [`SupplyCurveController`].
+pub trait MerchantController: fmt::Debug + Send + Sync {
+    fn decide(&self, observation: &MerchantObservation<'_>) -> Vec<MerchantAction>;
+}
+
+/// The built-in heuristic: at every settlement, buy inputs for the runs its
+/// facilities' crews allow and sell the rest of its stock along a supply
+/// curve (see [`MerchantAgent::generate_production_orders`]). Never builds
+/// or changes recipes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SupplyCurveController;
+
+impl MerchantController for SupplyCurveController {
+    fn decide(&self, observation: &MerchantObservation<'_>) -> Vec<MerchantAction> {
+        observation
+            .settlements
+            .iter()
+            .filter_map(|s| {
+                let facilities: Vec<&Facility> = s.facilities.iter().map(|(_, f)| *f).collect();
+                let orders = observation.merchant.generate_production_orders(
+                    s.id,
+                    s.price_ema,
+                    &facilities,
+                    observation.recipes,
+                );
+                (!orders.is_empty()).then_some(MerchantAction::Trade {
+                    settlement: s.id,
+                    orders,
+                })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,107 @@
+// What a merchant knows about markets it cannot see directly
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::labor::SkillId;
+use crate::types::{GoodId, MerchantId, Price, Quantity, SettlementId};
+
+/// One settlement's market as observed at `observed_tick`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MarketSnapshot {
+    pub settlement: SettlementId,
+    pub observed_tick: u64,
+    pub pop_count: usize,
+    pub prices: HashMap<GoodId, Price>,
+    pub wages: HashMap<SkillId, Price>,
+    /// Quantity that changed hands per good in the last market clearing.
+    pub depth: HashMap<GoodId, Quantity>,
+    /// Goods held by pops and merchant stockpiles at the settlement.
+    pub stocks: HashMap<GoodId, Quantity>,
+}
+
+impl MarketSnapshot {
+    /// Ticks since the observation was made.
+    pub fn age(&self, now: u64) -> u64 {
+        now.saturating_sub(self.observed_tick)
+    }
+}
+
+/// A merchant's dated view of every market it has heard about. Only
+/// updated by presence, vessel arrivals and couriers, so remote entries
+/// go stale.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MarketKnowledge {
+    pub snapshots: HashMap<SettlementId, MarketSnapshot>,
+}
+
+impl MarketKnowledge {
+    pub fn get(&self, settlement: SettlementId) -> Option<&MarketSnapshot> {
+        self.snapshots.get(&settlement)
+    }
+
+    /// Age of the latest snapshot for a settlement, if the merchant has one.
+    pub fn age(&self, settlement: SettlementId, now: u64) -> Option<u64> {
+        self.get(settlement).map(|s| s.age(now))
+    }
+
+    /// Keep a snapshot unless a newer one for the same settlement is
+    /// already known. Returns whether it was kept.
+    pub fn record(&mut self, snapshot: MarketSnapshot) -> bool {
+        match self.snapshots.get(&snapshot.settlement) {
+            Some(known) if known.observed_tick > snapshot.observed_tick => false,
+            _ => {
+                self.snapshots.insert(snapshot.settlement, snapshot);
+                true
+            }
+        }
+    }
+
+    /// Known settlements, sorted by id.
+    pub fn settlements(&self) -> Vec<SettlementId> {
+        crate::determinism::sorted_settlement_ids(self.snapshots.keys().copied())
+    }
+}
+
+/// A market report on its way to a merchant. Travels one route distance
+/// unit per tick.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Courier {
+    pub merchant: MerchantId,
+    pub from: SettlementId,
+    pub to: SettlementId,
+    pub ticks_remaining: u32,
+    pub report: MarketSnapshot,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(settlement: u32, tick: u64, price: Price) -> MarketSnapshot {
+        MarketSnapshot {
+            settlement: SettlementId::new(settlement),
+            observed_tick: tick,
+            pop_count: 0,
+            prices: HashMap::from([(1, price)]),
+            wages: HashMap::new(),
+            depth: HashMap::new(),
+            stocks: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn older_news_never_overwrites_newer() {
+        let mut knowledge = MarketKnowledge::default();
+        assert!(knowledge.record(snapshot(0, 5, 10.0)));
+        assert!(!knowledge.record(snapshot(0, 3, 99.0)));
+        assert_eq!(
+            knowledge.get(SettlementId::new(0)).unwrap().prices[&1],
+            10.0
+        );
+        assert!(knowledge.record(snapshot(0, 7, 12.0)));
+        assert_eq!(knowledge.age(SettlementId::new(0), 10), Some(3));
+        assert_eq!(knowledge.age(SettlementId::new(1), 10), None);
+    }
+}
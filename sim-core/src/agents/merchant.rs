@@ -1,8 +1,13 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::agents::Stockpile;
+use serde::{Deserialize, Serialize};
+
+use crate::agents::{MarketKnowledge, Stockpile};
+use crate::labor::SkillId;
 use crate::market::{Order, Side};
-use crate::types::{AgentId, FacilityHandle, GoodId, MerchantId, Price, SettlementId};
+use crate::production::{Facility, Recipe};
+use crate::tick::qty_norm;
+use crate::types::{AgentId, FacilityHandle, GoodId, MerchantId, Price, Quantity, SettlementId};
 
 // === PRODUCTION EMA CONSTANTS ===
 
@@ -16,6 +21,7 @@ const PRICE_SWEEP_MIN: f64 = 0.6;
 const PRICE_SWEEP_MAX: f64 = 1.4;
 const PRICE_SWEEP_POINTS: usize = 9;
 const TARGET_STOCK_BUFFER: f64 = 2.0; // ticks of production to hold as buffer
+const INPUT_STOCK_BUFFER: f64 = 2.0; // ticks of planned runs' inputs to hold
 
 /// Quantity supplied as fraction of excess above target.
 ///
@@ -34,9 +40,59 @@ fn qty_supply(norm_p: f64, norm_c: f64) -> f64 {
     (excess_ratio * (0.5 + 0.5 * price_factor) + 0.1 * price_factor.max(0.0)).clamp(0.0, 1.0)
 }
 
+/// Runs per recipe a facility's current crew and capacity allow, in
+/// priority order, ignoring inputs.
+fn planned_runs<'a>(facility: &Facility, recipes: &'a [Recipe]) -> Vec<(&'a Recipe, u32)> {
+    let mut capacity = facility.capacity;
+    let mut workers: HashMap<SkillId, u32> = facility.workers.clone();
+    let mut runs = Vec::new();
+    for recipe_id in &facility.recipe_priorities {
+        let Some(recipe) = recipes.iter().find(|r| r.id == *recipe_id) else {
+            continue;
+        };
+        if !recipe.can_run_at(facility.facility_type) {
+            continue;
+        }
+        let mut instances = capacity / recipe.capacity_cost.max(1);
+        for (skill, needed) in &recipe.workers {
+            if *needed > 0 {
+                instances = instances.min(workers.get(skill).copied().unwrap_or(0) / needed);
+            }
+        }
+        if instances == 0 {
+            continue;
+        }
+        capacity -= instances * recipe.capacity_cost;
+        for (skill, needed) in &recipe.workers {
+            if let Some(count) = workers.get_mut(skill) {
+                *count -= instances * needed;
+            }
+        }
+        runs.push((recipe, instances));
+    }
+    runs
+}
+
+/// Highest price, as a multiple of EMA, worth paying for a recipe's inputs:
+/// output value over input and tool-wear cost at EMA prices.
+fn input_price_ceiling(recipe: &Recipe, price_ema: &HashMap<GoodId, Price>) -> f64 {
+    let price = |good: &GoodId| price_ema.get(good).copied().unwrap_or(1.0);
+    let output_value: f64 = recipe.outputs.iter().map(|(g, q)| q * price(g)).sum();
+    let input_cost: f64 = recipe.inputs.iter().map(|(g, q)| q * price(g)).sum::<f64>()
+        + recipe
+            .tools
+            .iter()
+            .map(|t| t.qty * t.wear * price(&t.good))
+            .sum::<f64>();
+    if input_cost <= 0.0 {
+        return PRICE_SWEEP_MAX;
+    }
+    output_value / input_cost
+}
+
 /// A merchant entity that can trade across settlements.
 /// Has agency - controlled by player or AI bot.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MerchantAgent {
     pub id: MerchantId,
     pub currency: f64,
@@ -46,6 +102,9 @@ pub struct MerchantAgent {
     pub stockpiles: HashMap<SettlementId, Stockpile>,
     /// EMA of production rate per good per settlement (for buffer target calculation)
     pub production_ema: HashMap<SettlementId, HashMap<GoodId, f64>>,
+    /// Dated market snapshots; the only market view controllers get
+    #[serde(default)]
+    pub knowledge: MarketKnowledge,
 }
 
 impl MerchantAgent {
@@ -56,6 +115,7 @@ impl MerchantAgent {
             owned_facilities: HashSet::new(),
             stockpiles: HashMap::new(),
             production_ema: HashMap::new(),
+            knowledge: MarketKnowledge::default(),
         }
     }
 
@@ -98,6 +158,94 @@ impl MerchantAgent {
             .unwrap_or(0.0)
     }
 
+    /// Inputs and tools this merchant's facilities at a settlement want on
+    /// hand, with the price ceiling (as a multiple of EMA) for each.
+    ///
+    /// Consumed inputs are sized by `INPUT_STOCK_BUFFER` ticks of the runs
+    /// the current crews allow; tools by the quantity those runs hold.
+    pub fn input_targets(
+        &self,
+        facilities: &[&Facility],
+        recipes: &[Recipe],
+        price_ema: &HashMap<GoodId, Price>,
+    ) -> HashMap<GoodId, (Quantity, f64)> {
+        let mut targets: HashMap<GoodId, (Quantity, f64)> = HashMap::new();
+        for facility in facilities {
+            for (recipe, runs) in planned_runs(facility, recipes) {
+                let ceiling = input_price_ceiling(recipe, price_ema);
+                let needs = recipe
+                    .inputs
+                    .iter()
+                    .map(|(good, qty)| (*good, qty * INPUT_STOCK_BUFFER))
+                    .chain(recipe.tools.iter().map(|t| (t.good, t.qty)));
+                for (good, per_run) in needs {
+                    let target = targets.entry(good).or_insert((0.0, 0.0));
+                    target.0 += per_run * runs as f64;
+                    target.1 = target.1.max(ceiling);
+                }
+            }
+        }
+        targets
+    }
+
+    /// Orders for a settlement where this merchant runs facilities: buy
+    /// curves for their inputs up to each input's price ceiling, and the
+    /// supply curve for whatever stock is not held back as input.
+    pub fn generate_production_orders(
+        &self,
+        settlement: SettlementId,
+        price_ema: &HashMap<GoodId, Price>,
+        facilities: &[&Facility],
+        recipes: &[Recipe],
+    ) -> Vec<Order> {
+        let targets = self.input_targets(facilities, recipes, price_ema);
+        let reserve: HashMap<GoodId, Quantity> = targets
+            .iter()
+            .map(|(good, (qty, _))| (*good, *qty))
+            .collect();
+        let mut orders = self.supply_orders(settlement, price_ema, &reserve);
+
+        let mut goods: Vec<GoodId> = targets.keys().copied().collect();
+        goods.sort_unstable();
+        for good in goods {
+            let (target, ceiling) = targets[&good];
+            if target <= 0.0 {
+                continue;
+            }
+            let stock = self
+                .stockpiles
+                .get(&settlement)
+                .map_or(0.0, |s| s.get(good));
+            if stock >= target {
+                continue;
+            }
+            let ema_price = price_ema.get(&good).copied().unwrap_or(1.0);
+            let norm_c = stock / target;
+
+            for i in 0..PRICE_SWEEP_POINTS {
+                let norm_p = PRICE_SWEEP_MIN
+                    + (PRICE_SWEEP_MAX - PRICE_SWEEP_MIN) * (i as f64)
+                        / ((PRICE_SWEEP_POINTS - 1) as f64);
+                if norm_p > ceiling {
+                    break;
+                }
+                let buy_qty = qty_norm(norm_p, norm_c) * target;
+                if buy_qty > 0.001 {
+                    orders.push(Order {
+                        id: 0, // assigned later
+                        agent_id: AgentId::Merchant(self.id),
+                        good,
+                        side: Side::Buy,
+                        quantity: buy_qty,
+                        limit_price: norm_p * ema_price,
+                    });
+                }
+            }
+        }
+
+        orders
+    }
+
     /// Generate market orders for a settlement.
     ///
     /// Supply curve with two forces:
@@ -109,6 +257,16 @@ impl MerchantAgent {
         &self,
         settlement: SettlementId,
         price_ema: &HashMap<GoodId, Price>,
+    ) -> Vec<Order> {
+        self.supply_orders(settlement, price_ema, &HashMap::new())
+    }
+
+    /// Supply curve over stock in excess of `reserve`.
+    fn supply_orders(
+        &self,
+        settlement: SettlementId,
+        price_ema: &HashMap<GoodId, Price>,
+        reserve: &HashMap<GoodId, Quantity>,
     ) -> Vec<Order> {
         let mut orders = Vec::new();
 
@@ -117,7 +275,8 @@ impl MerchantAgent {
             return orders;
         };
 
-        for (&good, &qty) in &stockpile.goods {
+        for (&good, &held) in &stockpile.goods {
+            let qty = held - reserve.get(&good).copied().unwrap_or(0.0);
             if qty < 0.01 {
                 continue;
             }
@@ -158,6 +317,7 @@ impl MerchantAgent {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::production::{FacilityType, RecipeId};
     use crate::types::{MerchantId, SettlementId, facility_key_from_u64};
 
     #[test]
@@ -204,4 +364,44 @@ mod tests {
             "EMA should have decayed to near zero after 50 empty ticks, got {ema_after}"
         );
     }
+
+    #[test]
+    fn production_orders_buy_inputs_for_crewed_runs() {
+        let grain: GoodId = 1;
+        let bread: GoodId = 2;
+        let baker = SkillId(2);
+        let s = SettlementId::new(1);
+        let recipes = vec![
+            Recipe::new(RecipeId::new(1), "Bake Bread", vec![FacilityType::Bakery])
+                .with_worker(baker, 1)
+                .with_input(grain, 2.0)
+                .with_output(bread, 3.0),
+        ];
+        let mut bakery = Facility::new(FacilityType::Bakery, MerchantId::new(1));
+        bakery.capacity = 10;
+        bakery.recipe_priorities = vec![RecipeId::new(1)];
+        bakery.workers = [(baker, 2)].into();
+
+        let mut merchant = MerchantAgent::new(MerchantId::new(1));
+        merchant.stockpile_at(s).add(grain, 3.0);
+
+        // 2 runs * 2 grain * 2 ticks of buffer, worth up to 1.5x EMA
+        let prices: HashMap<GoodId, Price> = [(grain, 1.0), (bread, 1.0)].into();
+        let targets = merchant.input_targets(&[&bakery], &recipes, &prices);
+        assert_eq!(targets[&grain], (8.0, 1.5));
+
+        let orders = merchant.generate_production_orders(s, &prices, &[&bakery], &recipes);
+        assert!(
+            orders
+                .iter()
+                .all(|o| o.good == grain && matches!(o.side, Side::Buy))
+        );
+        assert!(!orders.is_empty());
+        assert!(orders.iter().all(|o| o.limit_price <= 1.5));
+
+        // Grain too dear to bake with: no bids, and the stock stays held
+        let prices: HashMap<GoodId, Price> = [(grain, 3.0), (bread, 1.0)].into();
+        let orders = merchant.generate_production_orders(s, &prices, &[&bakery], &recipes);
+        assert!(orders.is_empty());
+    }
 }
@@ -1,7 +1,11 @@
+pub mod controller;
+pub mod knowledge;
 pub mod merchant;
 pub mod pop;
 pub mod stockpile;
 
+pub use controller::*;
+pub use knowledge::*;
 pub use merchant::*;
 pub use pop::*;
 pub use stockpile::*;
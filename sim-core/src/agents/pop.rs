@@ -1,6 +1,9 @@
 use std::collections::{HashMap, HashSet};
 
+use serde::{Deserialize, Serialize};
+
 use crate::labor::SkillId;
+use crate::mortality::FoodIntake;
 use crate::types::{FacilityKey, GoodId, Price, Quantity};
 
 // === CONSUMPTION ===
@@ -14,18 +17,28 @@ pub struct ConsumptionResult {
 
 /// A population unit (~100 workers + dependents) bound to a settlement.
 /// Makes consumption decisions, participates in labor markets as 1 worker.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Pop {
     pub currency: f64,
     pub stocks: HashMap<GoodId, Quantity>,
     pub desired_consumption_ema: HashMap<GoodId, Quantity>,
     pub need_satisfaction: HashMap<String, f64>,
+    /// Goods consumed in the most recent tick.
+    #[serde(default)]
+    pub consumed: HashMap<GoodId, Quantity>,
+    /// Rolling food intake driving death and growth hazards.
+    #[serde(default)]
+    pub food_intake: FoodIntake,
     /// Smoothed income used as budget for desire discovery and market purchases.
     pub income_ema: f64,
 
     // Labor market participation
     /// Skills this pop can work as (includes inherited skills)
     pub skills: HashSet<SkillId>,
+    /// Experience per skill: progress towards skills not yet learned, and
+    /// how fresh learned ones are. Learned skills without an entry are fresh.
+    #[serde(default)]
+    pub skill_experience: HashMap<SkillId, f64>,
     /// Minimum acceptable wage (reservation wage)
     pub min_wage: Price,
     /// Current employment: facility this pop works at (if any)
@@ -41,8 +54,11 @@ impl Pop {
             stocks: HashMap::new(),
             desired_consumption_ema: HashMap::new(),
             need_satisfaction: HashMap::new(),
+            consumed: HashMap::new(),
+            food_intake: FoodIntake::default(),
             income_ema: 100.0,
             skills: HashSet::new(),
+            skill_experience: HashMap::new(),
             min_wage: 1.0, // Default reservation wage
             employed_at: None,
             employed_skill: None,
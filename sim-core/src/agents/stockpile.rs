@@ -2,10 +2,12 @@
 
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
+
 use crate::types::{GoodId, Quantity};
 
 /// Inventory of goods
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Stockpile {
     pub goods: HashMap<GoodId, Quantity>,
 }
@@ -0,0 +1,109 @@
+//! Headless scenario runner.
+//!
+//! ```text
+//! manifest-sim run <scenario.json> [--ticks N] [--seed S] [--out DIR]
+//! ```
+//!
+//! Builds the world described by the scenario, runs `N` ticks and writes
+//! every instrumentation table to `DIR/<table>.parquet`.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use sim_core::instrument;
+use sim_core::scenario::Scenario;
+
+const USAGE: &str = "usage: manifest-sim run <scenario.json> [--ticks N] [--seed S] [--out DIR]";
+const DEFAULT_TICKS: u64 = 100;
+
+struct RunArgs {
+    scenario: PathBuf,
+    ticks: Option<u64>,
+    seed: Option<u64>,
+    out: PathBuf,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<RunArgs, String> {
+    match args.next().as_deref() {
+        Some("run") => {}
+        Some(other) => return Err(format!("unknown command {other:?}")),
+        None => return Err("missing command".to_string()),
+    }
+
+    let mut scenario = None;
+    let mut ticks = None;
+    let mut seed = None;
+    let mut out = PathBuf::from("out");
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| {
+            args.next()
+                .ok_or_else(|| format!("{flag} requires a value"))
+        };
+        match arg.as_str() {
+            "--ticks" => {
+                let v = value("--ticks")?;
+                ticks = Some(v.parse().map_err(|_| format!("invalid --ticks {v:?}"))?);
+            }
+            "--seed" => {
+                let v = value("--seed")?;
+                seed = Some(v.parse().map_err(|_| format!("invalid --seed {v:?}"))?);
+            }
+            "--out" => out = PathBuf::from(value("--out")?),
+            flag if flag.starts_with("--") => return Err(format!("unknown flag {flag:?}")),
+            path if scenario.is_none() => scenario = Some(PathBuf::from(path)),
+            extra => return Err(format!("unexpected argument {extra:?}")),
+        }
+    }
+
+    Ok(RunArgs {
+        scenario: scenario.ok_or("missing scenario path")?,
+        ticks,
+        seed,
+        out,
+    })
+}
+
+fn run(args: RunArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let scenario = Scenario::load(&args.scenario)?;
+    let ticks = args.ticks.or(scenario.ticks).unwrap_or(DEFAULT_TICKS);
+    let mut sim = scenario.build(args.seed)?;
+
+    instrument::install_subscriber();
+    for _ in 0..ticks {
+        sim.run_tick();
+    }
+
+    let mut tables = instrument::drain_to_dataframes();
+    instrument::save_parquet(&mut tables, &args.out)?;
+
+    let mut names: Vec<_> = tables.keys().collect();
+    names.sort();
+    eprintln!(
+        "ran {ticks} ticks; wrote {} tables to {}: {}",
+        names.len(),
+        args.out.display(),
+        names
+            .iter()
+            .map(|n| n.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(msg) => {
+            eprintln!("error: {msg}\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
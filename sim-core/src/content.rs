@@ -0,0 +1,704 @@
+//! Data-driven content loading.
+//!
+//! A content pack is a JSON document listing needs, goods, skills, recipes
+//! and facility definitions by name. Loading it validates cross references
+//! and resolves good and skill names to IDs, producing a [`ContentRegistry`]
+//! that holds the runtime types `World::run_tick` and the labor market
+//! expect.
+//!
+//! IDs are assigned in file order starting at 1 unless an entry pins an
+//! explicit `id`.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::geography::ResourceType;
+use crate::labor::{SkillDef, SkillId};
+use crate::needs::{Need, UtilityCurve};
+use crate::production::{FacilityDef, FacilityType, Recipe, RecipeId, get_facility_defs};
+use crate::types::{GoodId, GoodProfile, NeedContribution};
+
+// === PACK FORMAT ===
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ContentPack {
+    #[serde(default)]
+    pub needs: Vec<NeedEntry>,
+    #[serde(default)]
+    pub goods: Vec<GoodEntry>,
+    #[serde(default)]
+    pub skills: Vec<SkillEntry>,
+    #[serde(default)]
+    pub recipes: Vec<RecipeEntry>,
+    /// Overrides for the built-in facility definitions. Types not listed
+    /// keep their defaults from [`get_facility_defs`].
+    #[serde(default)]
+    pub facilities: Vec<FacilityEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NeedEntry {
+    pub id: String,
+    pub utility_curve: UtilityCurveEntry,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", deny_unknown_fields)]
+pub enum UtilityCurveEntry {
+    Subsistence { requirement: f64, steepness: f64 },
+    LogDiminishing { scale: f64 },
+    LuxuryThreshold { threshold: f64, scale: f64 },
+    Positional { reference: f64, sensitivity: f64 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GoodEntry {
+    pub name: String,
+    #[serde(default)]
+    pub id: Option<GoodId>,
+    /// Need id → units of satisfaction per unit of good.
+    #[serde(default)]
+    pub satisfies: HashMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SkillEntry {
+    pub name: String,
+    #[serde(default)]
+    pub id: Option<u32>,
+    /// Name of the parent skill (`None` for roots such as laborer).
+    #[serde(default)]
+    pub parent: Option<String>,
+    /// Lowest wage accepted for a slot at this skill level.
+    #[serde(default)]
+    pub reservation_wage: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RecipeEntry {
+    pub name: String,
+    #[serde(default)]
+    pub id: Option<u32>,
+    pub facility_types: Vec<FacilityType>,
+    #[serde(default = "default_capacity_cost")]
+    pub capacity_cost: u32,
+    /// Skill name → workers per instance.
+    #[serde(default)]
+    pub workers: HashMap<String, u32>,
+    /// Good name → quantity per instance.
+    #[serde(default)]
+    pub inputs: HashMap<String, f64>,
+    /// Good name → quantity per instance.
+    #[serde(default)]
+    pub outputs: HashMap<String, f64>,
+    /// Good name → durable tool held per instance.
+    #[serde(default)]
+    pub tools: HashMap<String, ToolEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ToolEntry {
+    /// Quantity held per instance.
+    pub qty: f64,
+    /// Fraction of `qty` worn away per run.
+    pub wear: f64,
+    /// Yield of a run without the tool. Absent means the tool is required.
+    #[serde(default)]
+    pub unequipped_yield: Option<f64>,
+}
+
+fn default_capacity_cost() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FacilityEntry {
+    pub facility_type: FacilityType,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub required_resource: Option<ResourceType>,
+    #[serde(default)]
+    pub base_capacity: Option<u32>,
+    #[serde(default)]
+    pub construction_cost: Option<f64>,
+    #[serde(default)]
+    pub salvage_fraction: Option<f64>,
+    #[serde(default)]
+    pub useful_life: Option<u64>,
+}
+
+// === ERRORS ===
+
+#[derive(Debug)]
+pub enum ContentError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Duplicate {
+        kind: &'static str,
+        name: String,
+    },
+    UnknownReference {
+        context: String,
+        kind: &'static str,
+        name: String,
+    },
+    SkillCycle(String),
+    Invalid {
+        context: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for ContentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContentError::Io(e) => write!(f, "content io error: {e}"),
+            ContentError::Json(e) => write!(f, "content parse error: {e}"),
+            ContentError::Duplicate { kind, name } => write!(f, "duplicate {kind} {name:?}"),
+            ContentError::UnknownReference {
+                context,
+                kind,
+                name,
+            } => write!(f, "{context}: unknown {kind} {name:?}"),
+            ContentError::SkillCycle(name) => {
+                write!(f, "skill {name:?} is its own ancestor")
+            }
+            ContentError::Invalid { context, message } => write!(f, "{context}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ContentError {}
+
+impl From<std::io::Error> for ContentError {
+    fn from(e: std::io::Error) -> Self {
+        ContentError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ContentError {
+    fn from(e: serde_json::Error) -> Self {
+        ContentError::Json(e)
+    }
+}
+
+// === REGISTRY ===
+
+#[derive(Debug, Clone)]
+pub struct GoodInfo {
+    pub id: GoodId,
+    pub name: String,
+}
+
+/// Validated content with names resolved to IDs.
+pub struct ContentRegistry {
+    pub goods: Vec<GoodInfo>,
+    pub good_profiles: Vec<GoodProfile>,
+    pub needs: HashMap<String, Need>,
+    pub skills: Vec<SkillDef>,
+    pub recipes: Vec<Recipe>,
+    pub facility_defs: Vec<FacilityDef>,
+    good_ids: HashMap<String, GoodId>,
+    skill_ids: HashMap<String, SkillId>,
+}
+
+impl ContentRegistry {
+    pub fn from_json(json: &str) -> Result<Self, ContentError> {
+        let pack: ContentPack = serde_json::from_str(json)?;
+        Self::from_pack(pack)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ContentError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn from_pack(pack: ContentPack) -> Result<Self, ContentError> {
+        let needs = build_needs(&pack.needs)?;
+        let (goods, good_ids) = assign_good_ids(&pack.goods)?;
+        let good_profiles = build_good_profiles(&pack.goods, &good_ids, &needs)?;
+        let (skills, skill_ids) = build_skills(&pack.skills)?;
+        let recipes = build_recipes(&pack.recipes, &good_ids, &skill_ids)?;
+        let facility_defs = build_facility_defs(&pack.facilities)?;
+
+        Ok(Self {
+            goods,
+            good_profiles,
+            needs,
+            skills,
+            recipes,
+            facility_defs,
+            good_ids,
+            skill_ids,
+        })
+    }
+
+    pub fn good_id(&self, name: &str) -> Option<GoodId> {
+        self.good_ids.get(name).copied()
+    }
+
+    pub fn skill_id(&self, name: &str) -> Option<SkillId> {
+        self.skill_ids.get(name).copied()
+    }
+
+    pub fn recipe(&self, name: &str) -> Option<&Recipe> {
+        self.recipes.iter().find(|r| r.name == name)
+    }
+
+    pub fn facility_def(&self, facility_type: FacilityType) -> Option<&FacilityDef> {
+        self.facility_defs
+            .iter()
+            .find(|def| def.facility_type == facility_type)
+    }
+}
+
+fn check_non_negative(context: &str, field: &str, value: f64) -> Result<(), ContentError> {
+    if value.is_finite() && value >= 0.0 {
+        Ok(())
+    } else {
+        Err(ContentError::Invalid {
+            context: context.to_string(),
+            message: format!("{field} must be a finite non-negative number, got {value}"),
+        })
+    }
+}
+
+fn build_needs(entries: &[NeedEntry]) -> Result<HashMap<String, Need>, ContentError> {
+    let mut needs = HashMap::new();
+    for entry in entries {
+        let context = format!("need {:?}", entry.id);
+        let utility_curve = match entry.utility_curve {
+            UtilityCurveEntry::Subsistence {
+                requirement,
+                steepness,
+            } => {
+                check_non_negative(&context, "requirement", requirement)?;
+                check_non_negative(&context, "steepness", steepness)?;
+                UtilityCurve::Subsistence {
+                    requirement,
+                    steepness,
+                }
+            }
+            UtilityCurveEntry::LogDiminishing { scale } => {
+                check_non_negative(&context, "scale", scale)?;
+                UtilityCurve::LogDiminishing { scale }
+            }
+            UtilityCurveEntry::LuxuryThreshold { threshold, scale } => {
+                check_non_negative(&context, "threshold", threshold)?;
+                check_non_negative(&context, "scale", scale)?;
+                UtilityCurve::LuxuryThreshold { threshold, scale }
+            }
+            UtilityCurveEntry::Positional {
+                reference,
+                sensitivity,
+            } => UtilityCurve::Positional {
+                reference,
+                sensitivity,
+            },
+        };
+        let need = Need {
+            id: entry.id.clone(),
+            utility_curve,
+        };
+        if needs.insert(entry.id.clone(), need).is_some() {
+            return Err(ContentError::Duplicate {
+                kind: "need",
+                name: entry.id.clone(),
+            });
+        }
+    }
+    Ok(needs)
+}
+
+/// Resolve explicit IDs first, then hand out the lowest unused IDs (from 1)
+/// in file order.
+fn assign_ids(
+    kind: &'static str,
+    entries: impl Iterator<Item = (String, Option<u32>)>,
+) -> Result<Vec<(String, u32)>, ContentError> {
+    let entries: Vec<(String, Option<u32>)> = entries.collect();
+    let mut names = HashSet::new();
+    let mut used = HashSet::new();
+    for (name, id) in &entries {
+        if !names.insert(name.clone()) {
+            return Err(ContentError::Duplicate {
+                kind,
+                name: name.clone(),
+            });
+        }
+        if let Some(id) = id
+            && !used.insert(*id)
+        {
+            return Err(ContentError::Duplicate {
+                kind,
+                name: format!("{name} (id {id})"),
+            });
+        }
+    }
+
+    let mut next = 1;
+    let mut assigned = Vec::with_capacity(entries.len());
+    for (name, id) in entries {
+        let id = match id {
+            Some(id) => id,
+            None => {
+                while used.contains(&next) {
+                    next += 1;
+                }
+                used.insert(next);
+                next
+            }
+        };
+        assigned.push((name, id));
+    }
+    Ok(assigned)
+}
+
+fn assign_good_ids(
+    entries: &[GoodEntry],
+) -> Result<(Vec<GoodInfo>, HashMap<String, GoodId>), ContentError> {
+    let assigned = assign_ids("good", entries.iter().map(|g| (g.name.clone(), g.id)))?;
+    let goods: Vec<GoodInfo> = assigned
+        .iter()
+        .map(|(name, id)| GoodInfo {
+            id: *id,
+            name: name.clone(),
+        })
+        .collect();
+    let ids = assigned.into_iter().collect();
+    Ok((goods, ids))
+}
+
+fn build_good_profiles(
+    entries: &[GoodEntry],
+    good_ids: &HashMap<String, GoodId>,
+    needs: &HashMap<String, Need>,
+) -> Result<Vec<GoodProfile>, ContentError> {
+    let mut profiles = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let context = format!("good {:?}", entry.name);
+        let mut need_ids: Vec<&String> = entry.satisfies.keys().collect();
+        need_ids.sort();
+        let mut contributions = Vec::with_capacity(need_ids.len());
+        for need_id in need_ids {
+            if !needs.contains_key(need_id) {
+                return Err(ContentError::UnknownReference {
+                    context,
+                    kind: "need",
+                    name: need_id.clone(),
+                });
+            }
+            let efficiency = entry.satisfies[need_id];
+            check_non_negative(&context, "efficiency", efficiency)?;
+            contributions.push(NeedContribution {
+                need_id: need_id.clone(),
+                efficiency,
+            });
+        }
+        if !contributions.is_empty() {
+            profiles.push(GoodProfile {
+                good: good_ids[&entry.name],
+                contributions,
+            });
+        }
+    }
+    Ok(profiles)
+}
+
+fn build_skills(
+    entries: &[SkillEntry],
+) -> Result<(Vec<SkillDef>, HashMap<String, SkillId>), ContentError> {
+    let assigned = assign_ids("skill", entries.iter().map(|s| (s.name.clone(), s.id)))?;
+    let skill_ids: HashMap<String, SkillId> = assigned
+        .into_iter()
+        .map(|(name, id)| (name, SkillId(id)))
+        .collect();
+
+    let mut parents: HashMap<&str, &str> = HashMap::new();
+    let mut skills = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let parent = match &entry.parent {
+            Some(parent) => {
+                let Some(&parent_id) = skill_ids.get(parent) else {
+                    return Err(ContentError::UnknownReference {
+                        context: format!("skill {:?}", entry.name),
+                        kind: "skill",
+                        name: parent.clone(),
+                    });
+                };
+                parents.insert(&entry.name, parent);
+                Some(parent_id)
+            }
+            None => None,
+        };
+        skills.push(SkillDef {
+            id: skill_ids[&entry.name],
+            name: entry.name.clone(),
+            parent,
+            reservation_wage: entry.reservation_wage,
+        });
+    }
+
+    for entry in entries {
+        let mut current = entry.name.as_str();
+        for _ in 0..entries.len() {
+            match parents.get(current) {
+                Some(parent) => current = parent,
+                None => break,
+            }
+            if current == entry.name {
+                return Err(ContentError::SkillCycle(entry.name.clone()));
+            }
+        }
+    }
+
+    Ok((skills, skill_ids))
+}
+
+fn build_recipes(
+    entries: &[RecipeEntry],
+    good_ids: &HashMap<String, GoodId>,
+    skill_ids: &HashMap<String, SkillId>,
+) -> Result<Vec<Recipe>, ContentError> {
+    let assigned = assign_ids("recipe", entries.iter().map(|r| (r.name.clone(), r.id)))?;
+
+    let resolve_goods =
+        |context: &str, goods: &HashMap<String, f64>| -> Result<Vec<(GoodId, f64)>, ContentError> {
+            let mut resolved = Vec::with_capacity(goods.len());
+            for (name, &qty) in goods {
+                let Some(&good) = good_ids.get(name) else {
+                    return Err(ContentError::UnknownReference {
+                        context: context.to_string(),
+                        kind: "good",
+                        name: name.clone(),
+                    });
+                };
+                check_non_negative(context, name, qty)?;
+                resolved.push((good, qty));
+            }
+            resolved.sort_by_key(|(good, _)| *good);
+            Ok(resolved)
+        };
+
+    let mut recipes = Vec::with_capacity(entries.len());
+    for (entry, (_, id)) in entries.iter().zip(assigned) {
+        let context = format!("recipe {:?}", entry.name);
+        if entry.facility_types.is_empty() {
+            return Err(ContentError::Invalid {
+                context,
+                message: "recipe must list at least one facility type".to_string(),
+            });
+        }
+        if entry.outputs.is_empty() {
+            return Err(ContentError::Invalid {
+                context,
+                message: "recipe must produce at least one output".to_string(),
+            });
+        }
+
+        let mut recipe = Recipe::new(
+            RecipeId::new(id),
+            entry.name.clone(),
+            entry.facility_types.clone(),
+        )
+        .with_capacity_cost(entry.capacity_cost);
+
+        let mut skill_names: Vec<&String> = entry.workers.keys().collect();
+        skill_names.sort();
+        for name in skill_names {
+            let Some(&skill) = skill_ids.get(name) else {
+                return Err(ContentError::UnknownReference {
+                    context,
+                    kind: "skill",
+                    name: name.clone(),
+                });
+            };
+            recipe = recipe.with_worker(skill, entry.workers[name]);
+        }
+        for (good, qty) in resolve_goods(&context, &entry.inputs)? {
+            recipe = recipe.with_input(good, qty);
+        }
+        for (good, qty) in resolve_goods(&context, &entry.outputs)? {
+            recipe = recipe.with_output(good, qty);
+        }
+        let mut tool_names: Vec<&String> = entry.tools.keys().collect();
+        tool_names.sort();
+        for name in tool_names {
+            let Some(&good) = good_ids.get(name) else {
+                return Err(ContentError::UnknownReference {
+                    context,
+                    kind: "good",
+                    name: name.clone(),
+                });
+            };
+            let tool = &entry.tools[name];
+            check_non_negative(&context, name, tool.qty)?;
+            let mut fractions = std::iter::once(tool.wear).chain(tool.unequipped_yield);
+            if fractions.any(|f| !(0.0..=1.0).contains(&f)) {
+                return Err(ContentError::Invalid {
+                    context,
+                    message: format!("tool {name:?} wear and yield must be within 0..=1"),
+                });
+            }
+            recipe = match tool.unequipped_yield {
+                Some(unequipped_yield) => {
+                    recipe.with_booster(good, tool.qty, tool.wear, unequipped_yield)
+                }
+                None => recipe.with_tool(good, tool.qty, tool.wear),
+            };
+        }
+        recipes.push(recipe);
+    }
+    Ok(recipes)
+}
+
+fn build_facility_defs(entries: &[FacilityEntry]) -> Result<Vec<FacilityDef>, ContentError> {
+    let mut defs = get_facility_defs();
+    let mut seen = HashSet::new();
+    for entry in entries {
+        if !seen.insert(entry.facility_type) {
+            return Err(ContentError::Duplicate {
+                kind: "facility",
+                name: format!("{:?}", entry.facility_type),
+            });
+        }
+        let context = format!("facility {:?}", entry.facility_type);
+        let def = defs
+            .iter_mut()
+            .find(|d| d.facility_type == entry.facility_type)
+            .expect("every facility type has a default definition");
+        if let Some(name) = &entry.name {
+            def.name = name.clone();
+        }
+        if entry.required_resource.is_some() {
+            def.required_resource = entry.required_resource;
+        }
+        if let Some(capacity) = entry.base_capacity {
+            def.base_capacity = capacity;
+        }
+        if let Some(cost) = entry.construction_cost {
+            check_non_negative(&context, "construction_cost", cost)?;
+            def.construction_cost = cost;
+        }
+        if let Some(fraction) = entry.salvage_fraction {
+            if !(0.0..=1.0).contains(&fraction) {
+                return Err(ContentError::Invalid {
+                    context,
+                    message: format!("salvage_fraction must be in [0, 1], got {fraction}"),
+                });
+            }
+            def.salvage_fraction = fraction;
+        }
+        if let Some(ticks) = entry.useful_life {
+            if ticks == 0 {
+                return Err(ContentError::Invalid {
+                    context,
+                    message: "useful_life must be positive".to_string(),
+                });
+            }
+            def.useful_life = ticks;
+        }
+    }
+    Ok(defs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PACK: &str = r#"{
+        "needs": [
+            { "id": "food", "utility_curve": { "kind": "Subsistence", "requirement": 1.0, "steepness": 5.0 } }
+        ],
+        "goods": [
+            { "name": "grain", "satisfies": { "food": 1.0 } },
+            { "name": "bread", "satisfies": { "food": 1.6 } },
+            { "name": "iron", "id": 7 }
+        ],
+        "skills": [
+            { "name": "laborer" },
+            { "name": "baker", "parent": "laborer" }
+        ],
+        "recipes": [
+            {
+                "name": "Bake Bread",
+                "facility_types": ["Bakery"],
+                "workers": { "baker": 1 },
+                "inputs": { "grain": 2.0 },
+                "outputs": { "bread": 1.5 },
+                "tools": { "iron": { "qty": 1.0, "wear": 0.1, "unequipped_yield": 0.5 } }
+            }
+        ],
+        "facilities": [
+            { "facility_type": "Bakery", "base_capacity": 12 }
+        ]
+    }"#;
+
+    #[test]
+    fn resolves_names_to_ids() {
+        let registry = ContentRegistry::from_json(PACK).unwrap();
+
+        assert_eq!(registry.good_id("grain"), Some(1));
+        assert_eq!(registry.good_id("bread"), Some(2));
+        assert_eq!(registry.good_id("iron"), Some(7));
+        assert_eq!(registry.good_profiles.len(), 2, "iron satisfies no need");
+
+        let baker = registry.skill_id("baker").unwrap();
+        let laborer = registry.skill_id("laborer").unwrap();
+        let baker_def = registry.skills.iter().find(|s| s.id == baker).unwrap();
+        assert_eq!(baker_def.parent, Some(laborer));
+
+        let recipe = registry.recipe("Bake Bread").unwrap();
+        assert_eq!(recipe.workers.get(&baker), Some(&1));
+        assert_eq!(recipe.inputs, vec![(1, 2.0)]);
+        assert_eq!(recipe.outputs, vec![(2, 1.5)]);
+        assert_eq!(recipe.tools.len(), 1);
+        assert_eq!(recipe.tools[0].good, 7);
+        assert_eq!(recipe.tools[0].unequipped_yield, Some(0.5));
+
+        let bakery = registry.facility_def(FacilityType::Bakery).unwrap();
+        assert_eq!(bakery.base_capacity, 12);
+        assert_eq!(registry.facility_defs.len(), get_facility_defs().len());
+    }
+
+    #[test]
+    fn rejects_unknown_references() {
+        let pack = PACK.replace(r#""grain": 2.0"#, r#""rye": 2.0"#);
+        let err = ContentRegistry::from_json(&pack).err().unwrap();
+        assert!(matches!(
+            err,
+            ContentError::UnknownReference { kind: "good", ref name, .. } if name == "rye"
+        ));
+
+        let pack = PACK.replace(r#""food": 1.6"#, r#""warmth": 1.6"#);
+        let err = ContentRegistry::from_json(&pack).err().unwrap();
+        assert!(matches!(
+            err,
+            ContentError::UnknownReference { kind: "need", .. }
+        ));
+    }
+
+    #[test]
+    fn rejects_duplicates_and_cycles() {
+        let pack = PACK.replace(r#""name": "bread""#, r#""name": "grain""#);
+        let err = ContentRegistry::from_json(&pack).err().unwrap();
+        assert!(matches!(err, ContentError::Duplicate { kind: "good", .. }));
+
+        let pack = PACK.replace(
+            r#"{ "name": "laborer" }"#,
+            r#"{ "name": "laborer", "parent": "baker" }"#,
+        );
+        let err = ContentRegistry::from_json(&pack).err().unwrap();
+        assert!(matches!(err, ContentError::SkillCycle(_)));
+    }
+}
@@ -0,0 +1,200 @@
+// Loans between merchants
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{LoanId, MerchantId};
+
+// === LOAN ===
+
+/// Currency lent by one merchant to another, repaid in equal principal
+/// installments over `term` ticks with interest on the outstanding balance.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Loan {
+    pub id: LoanId,
+    pub lender: MerchantId,
+    pub borrower: MerchantId,
+    /// Amount originally lent.
+    pub principal: f64,
+    /// Principal not yet repaid.
+    pub outstanding: f64,
+    /// Interest accrued and not yet paid.
+    pub accrued_interest: f64,
+    /// Interest per tick, as a fraction of the outstanding principal.
+    pub rate: f64,
+    /// Number of installments; the last one falls due at `maturity()`.
+    pub term: u64,
+    pub issued_at: u64,
+}
+
+impl Loan {
+    pub fn new(
+        id: LoanId,
+        lender: MerchantId,
+        borrower: MerchantId,
+        principal: f64,
+        rate: f64,
+        term: u64,
+        issued_at: u64,
+    ) -> Self {
+        Self {
+            id,
+            lender,
+            borrower,
+            principal,
+            outstanding: principal,
+            accrued_interest: 0.0,
+            rate,
+            term,
+            issued_at,
+        }
+    }
+
+    /// What the borrower owes right now.
+    pub fn balance(&self) -> f64 {
+        self.outstanding + self.accrued_interest
+    }
+
+    pub fn maturity(&self) -> u64 {
+        self.issued_at + self.term
+    }
+
+    /// Accrue one tick of interest, returning the amount added.
+    pub fn accrue(&mut self) -> f64 {
+        let interest = self.outstanding * self.rate;
+        self.accrued_interest += interest;
+        interest
+    }
+
+    /// Installment due at `tick`: all accrued interest plus an equal share
+    /// of the outstanding principal over the installments left. Anything
+    /// unpaid at maturity falls due in full every tick after.
+    pub fn installment_due(&self, tick: u64) -> f64 {
+        let remaining = self.maturity().saturating_sub(tick) + 1;
+        self.accrued_interest + self.outstanding / remaining as f64
+    }
+
+    /// Apply a payment, interest first. Returns the (interest, principal)
+    /// split; any excess over the balance is not taken.
+    pub fn apply_payment(&mut self, amount: f64) -> (f64, f64) {
+        let interest = amount.clamp(0.0, self.accrued_interest);
+        let principal = (amount - interest).clamp(0.0, self.outstanding);
+        self.accrued_interest -= interest;
+        self.outstanding -= principal;
+        (interest, principal)
+    }
+
+    pub fn is_repaid(&self) -> bool {
+        self.balance() <= 1e-9
+    }
+}
+
+// === TOTALS ===
+
+/// Cumulative credit flows. Loans move currency between merchants, so they
+/// never change the money stock; these totals explain the change in loan
+/// balances instead.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreditTotals {
+    pub issued: f64,
+    pub interest_accrued: f64,
+    pub interest_paid: f64,
+    pub principal_repaid: f64,
+}
+
+impl CreditTotals {
+    pub fn total_repaid(&self) -> f64 {
+        self.interest_paid + self.principal_repaid
+    }
+}
+
+// === ERRORS ===
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CreditError {
+    UnknownMerchant(MerchantId),
+    UnknownLoan(LoanId),
+    /// Lender and borrower are the same merchant.
+    SelfLoan(MerchantId),
+    /// Principal, rate or term out of range.
+    InvalidTerms,
+    InvalidAmount(f64),
+    InsufficientFunds {
+        required: f64,
+        available: f64,
+    },
+}
+
+impl fmt::Display for CreditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CreditError::UnknownMerchant(id) => write!(f, "unknown merchant {}", id.0),
+            CreditError::UnknownLoan(id) => write!(f, "unknown loan {}", id.0),
+            CreditError::SelfLoan(id) => write!(f, "merchant {} cannot lend to itself", id.0),
+            CreditError::InvalidTerms => {
+                write!(
+                    f,
+                    "loans need a positive principal and term and a rate >= 0"
+                )
+            }
+            CreditError::InvalidAmount(amount) => write!(f, "invalid amount {amount}"),
+            CreditError::InsufficientFunds {
+                required,
+                available,
+            } => write!(f, "lending {required} but lender has {available}"),
+        }
+    }
+}
+
+impl std::error::Error for CreditError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn loan(rate: f64) -> Loan {
+        Loan::new(
+            LoanId::new(0),
+            MerchantId::new(0),
+            MerchantId::new(1),
+            100.0,
+            rate,
+            4,
+            10,
+        )
+    }
+
+    #[test]
+    fn installments_repay_principal_evenly_by_maturity() {
+        let mut interest_free = loan(0.0);
+        for tick in 11..=14 {
+            let due = interest_free.installment_due(tick);
+            assert!((due - 25.0).abs() < 1e-12, "tick {tick} due {due}");
+            interest_free.apply_payment(due);
+        }
+        assert!(interest_free.is_repaid());
+
+        let mut with_interest = loan(0.01);
+        let mut paid = 0.0;
+        for tick in 11..=14 {
+            with_interest.accrue();
+            let due = with_interest.installment_due(tick);
+            let (interest, _) = with_interest.apply_payment(due);
+            assert!(interest > 0.0);
+            paid += due;
+        }
+        assert!(with_interest.is_repaid());
+        // 1% on 100, 75, 50 and 25
+        assert!((paid - 102.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn overdue_balance_falls_due_in_full() {
+        let mut overdue = loan(0.0);
+        overdue.apply_payment(10.0);
+        assert_eq!(overdue.installment_due(20), 90.0);
+        assert_eq!(overdue.apply_payment(500.0), (0.0, 90.0));
+        assert!(overdue.is_repaid());
+    }
+}
@@ -1,4 +1,7 @@
-use crate::types::{FacilityKey, MerchantId, PopKey, SettlementId, facility_key_u64, pop_key_u64};
+use crate::types::{
+    AgentId, FacilityKey, LoanId, MerchantId, PopKey, SettlementId, VesselId, facility_key_u64,
+    pop_key_u64,
+};
 
 pub(crate) fn sorted_settlement_ids<I>(iter: I) -> Vec<SettlementId>
 where
@@ -35,3 +38,30 @@ where
     keys.sort_by_key(|k| facility_key_u64(*k));
     keys
 }
+
+pub(crate) fn sorted_agent_ids<I>(iter: I) -> Vec<AgentId>
+where
+    I: IntoIterator<Item = AgentId>,
+{
+    let mut ids: Vec<AgentId> = iter.into_iter().collect();
+    ids.sort_by_key(|id| id.stable_u64());
+    ids
+}
+
+pub(crate) fn sorted_vessel_ids<I>(iter: I) -> Vec<VesselId>
+where
+    I: IntoIterator<Item = VesselId>,
+{
+    let mut ids: Vec<VesselId> = iter.into_iter().collect();
+    ids.sort_by_key(|id| id.0);
+    ids
+}
+
+pub(crate) fn sorted_loan_ids<I>(iter: I) -> Vec<LoanId>
+where
+    I: IntoIterator<Item = LoanId>,
+{
+    let mut ids: Vec<LoanId> = iter.into_iter().collect();
+    ids.sort_by_key(|id| id.0);
+    ids
+}
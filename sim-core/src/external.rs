@@ -1,5 +1,8 @@
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
+
+use crate::geography::SettlementRole;
 use crate::market::{Order, Side};
 use crate::types::{AgentId, GoodId, Price, Quantity, SettlementId};
 
@@ -13,7 +16,8 @@ pub const DEPTH_RESPONSE_ELASTICITY: f64 = 0.5;
 pub const DEPTH_RESPONSE_MAX_MULT: f64 = 10.0;
 
 /// Config for an anchored good in the outside market.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AnchoredGoodConfig {
     /// Exogenous reference price for this good in the outside market.
     pub world_price: Price,
@@ -43,7 +47,8 @@ impl Default for AnchoredGoodConfig {
 }
 
 /// Per-settlement friction and enablement controls for outside trade.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SettlementFriction {
     pub enabled: bool,
     pub transport_bps: f64,
@@ -62,12 +67,17 @@ impl Default for SettlementFriction {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ExternalMarketConfig {
     /// Anchored goods and their outside market parameters.
     pub anchors: HashMap<GoodId, AnchoredGoodConfig>,
     /// Settlement-specific friction / enablement toggles.
     pub frictions: HashMap<SettlementId, SettlementFriction>,
+    /// Attach outside ladders only at settlements whose role has outside
+    /// access; inland settlements then see outside prices only through
+    /// goods merchants carry in.
+    #[serde(default)]
+    pub port_only: bool,
 }
 
 impl ExternalMarketConfig {
@@ -75,14 +85,23 @@ impl ExternalMarketConfig {
     pub fn friction_for(&self, settlement: SettlementId) -> SettlementFriction {
         self.frictions.get(&settlement).cloned().unwrap_or_default()
     }
+
+    /// Whether the outside market reaches a settlement with this role.
+    pub fn reaches(&self, role: SettlementRole) -> bool {
+        !self.port_only || role.has_outside_access()
+    }
 }
 
 /// Aggregate outside flow accounting over simulation runtime.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct OutsideFlowTotals {
+    #[serde(with = "crate::snapshot::entries")]
     pub imports_qty: HashMap<(SettlementId, GoodId), Quantity>,
+    #[serde(with = "crate::snapshot::entries")]
     pub exports_qty: HashMap<(SettlementId, GoodId), Quantity>,
+    #[serde(with = "crate::snapshot::entries")]
     pub imports_value: HashMap<(SettlementId, GoodId), f64>,
+    #[serde(with = "crate::snapshot::entries")]
     pub exports_value: HashMap<(SettlementId, GoodId), f64>,
 }
 
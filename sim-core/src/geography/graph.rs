@@ -0,0 +1,397 @@
+// Route network queries: shortest paths, reachability, chokepoints
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+use crate::geography::{Route, RouteKind};
+use crate::types::SettlementId;
+
+/// Edge weight used for path searches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathMetric {
+    /// Travel distance (ticks at speed 1)
+    Distance,
+    /// Sum of per-unit `transport_cost`
+    TransportCost,
+    /// Per-unit cost of goods that actually arrive: `transport_cost / (1 - risk)`
+    RiskAdjusted,
+}
+
+impl PathMetric {
+    fn weight(&self, route: &Route) -> f64 {
+        match self {
+            Self::Distance => f64::from(route.distance),
+            Self::TransportCost => route.transport_cost,
+            Self::RiskAdjusted => route.transport_cost / (1.0 - route.risk.clamp(0.0, 0.99)),
+        }
+    }
+}
+
+/// A multi-hop path through the network.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoutePath {
+    /// Settlements visited, including both endpoints
+    pub settlements: Vec<SettlementId>,
+    /// Total weight under the metric used for the search
+    pub cost: f64,
+    /// Total distance regardless of metric
+    pub distance: u32,
+}
+
+impl RoutePath {
+    pub fn hops(&self) -> usize {
+        self.settlements.len().saturating_sub(1)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    to: SettlementId,
+    route: usize,
+}
+
+/// Undirected adjacency view over a set of routes. Build one per query
+/// batch with [`World::route_graph`](crate::World::route_graph); it holds
+/// its own copy of the routes, so it goes stale once the world's routes
+/// change.
+#[derive(Debug, Clone, Default)]
+pub struct RouteGraph {
+    routes: Vec<Route>,
+    adjacency: HashMap<SettlementId, Vec<Edge>>,
+}
+
+impl RouteGraph {
+    pub fn new(routes: &[Route]) -> Self {
+        Self::build(routes.iter())
+    }
+
+    /// Only routes a vessel of this kind can travel.
+    pub fn of_kind(routes: &[Route], kind: RouteKind) -> Self {
+        Self::build(routes.iter().filter(|r| r.kind == kind))
+    }
+
+    fn build<'a>(routes: impl Iterator<Item = &'a Route>) -> Self {
+        let routes: Vec<Route> = routes.cloned().collect();
+        let mut adjacency: HashMap<SettlementId, Vec<Edge>> = HashMap::new();
+        for (idx, route) in routes.iter().enumerate() {
+            adjacency.entry(route.from).or_default().push(Edge {
+                to: route.to,
+                route: idx,
+            });
+            adjacency.entry(route.to).or_default().push(Edge {
+                to: route.from,
+                route: idx,
+            });
+        }
+        // Neighbour order drives tie-breaking, so keep it stable.
+        for edges in adjacency.values_mut() {
+            edges.sort_by_key(|e| (e.to.0, e.route));
+        }
+        Self { routes, adjacency }
+    }
+
+    /// Settlements with at least one route, sorted by id.
+    pub fn settlements(&self) -> Vec<SettlementId> {
+        crate::determinism::sorted_settlement_ids(self.adjacency.keys().copied())
+    }
+
+    /// Direct neighbours, sorted by id (a neighbour joined by several routes
+    /// appears once).
+    pub fn neighbors(&self, id: SettlementId) -> Vec<SettlementId> {
+        let mut out: Vec<SettlementId> = self
+            .adjacency
+            .get(&id)
+            .map(|edges| edges.iter().map(|e| e.to).collect())
+            .unwrap_or_default();
+        out.dedup();
+        out
+    }
+
+    /// Cheapest path from `from` to `to` under `metric`. Ties are broken
+    /// towards lower settlement ids so results are deterministic.
+    pub fn shortest_path(
+        &self,
+        from: SettlementId,
+        to: SettlementId,
+        metric: PathMetric,
+    ) -> Option<RoutePath> {
+        let search = self.dijkstra(from, metric);
+        let cost = *search.cost.get(&to)?;
+
+        let mut settlements = vec![to];
+        let mut distance = 0;
+        let mut at = to;
+        while let Some(&(prev, route)) = search.prev.get(&at) {
+            distance += self.routes[route].distance;
+            settlements.push(prev);
+            at = prev;
+        }
+        settlements.reverse();
+        Some(RoutePath {
+            settlements,
+            cost,
+            distance,
+        })
+    }
+
+    /// Every settlement reachable from `from` within `ticks` of travel at
+    /// speed 1, with its distance, sorted by distance then id. Includes
+    /// `from` itself at distance 0.
+    pub fn reachable_within(&self, from: SettlementId, ticks: u32) -> Vec<(SettlementId, u32)> {
+        let search = self.dijkstra(from, PathMetric::Distance);
+        let mut out: Vec<(SettlementId, u32)> = search
+            .cost
+            .iter()
+            .map(|(id, cost)| (*id, *cost as u32))
+            .filter(|(_, d)| *d <= ticks)
+            .collect();
+        out.sort_by_key(|(id, d)| (*d, id.0));
+        out
+    }
+
+    /// Betweenness centrality by distance (Brandes), normalised so a node
+    /// on every shortest path between all other pairs scores 1.0. Sorted
+    /// by id.
+    pub fn betweenness(&self) -> Vec<(SettlementId, f64)> {
+        let nodes = self.settlements();
+        let mut score: HashMap<SettlementId, f64> = nodes.iter().map(|&n| (n, 0.0)).collect();
+
+        for &source in &nodes {
+            let search = self.dijkstra_all_preds(source);
+            let mut delta: HashMap<SettlementId, f64> = HashMap::new();
+            for &w in search.order.iter().rev() {
+                let coeff = (1.0 + delta.get(&w).copied().unwrap_or(0.0)) / search.sigma[&w];
+                for &v in &search.preds[&w] {
+                    *delta.entry(v).or_insert(0.0) += search.sigma[&v] * coeff;
+                }
+                if w != source {
+                    *score.get_mut(&w).expect("node in graph") +=
+                        delta.get(&w).copied().unwrap_or(0.0);
+                }
+            }
+        }
+
+        // Undirected: each pair was counted from both ends.
+        let n = nodes.len() as f64;
+        let pairs = if n > 2.0 { (n - 1.0) * (n - 2.0) } else { 1.0 };
+        nodes
+            .into_iter()
+            .map(|id| (id, score[&id] / pairs))
+            .collect()
+    }
+
+    /// Settlements that lie on some other pair's shortest path, highest
+    /// betweenness first.
+    pub fn chokepoints(&self) -> Vec<(SettlementId, f64)> {
+        let mut out: Vec<(SettlementId, f64)> = self
+            .betweenness()
+            .into_iter()
+            .filter(|(_, b)| *b > 0.0)
+            .collect();
+        out.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.0.cmp(&b.0.0)));
+        out
+    }
+
+    fn dijkstra(&self, from: SettlementId, metric: PathMetric) -> Search {
+        let mut search = Search::default();
+        if !self.adjacency.contains_key(&from) {
+            return search;
+        }
+        let mut heap = BinaryHeap::new();
+        search.cost.insert(from, 0.0);
+        heap.push(Frontier {
+            cost: 0.0,
+            id: from,
+        });
+
+        while let Some(Frontier { cost, id }) = heap.pop() {
+            if cost > search.cost[&id] {
+                continue;
+            }
+            for edge in &self.adjacency[&id] {
+                let next = cost + metric.weight(&self.routes[edge.route]);
+                let better = match search.cost.get(&edge.to) {
+                    None => true,
+                    Some(&old) => next < old,
+                };
+                if better {
+                    search.cost.insert(edge.to, next);
+                    search.prev.insert(edge.to, (id, edge.route));
+                    heap.push(Frontier {
+                        cost: next,
+                        id: edge.to,
+                    });
+                }
+            }
+        }
+        search
+    }
+
+    /// Distance-metric Dijkstra keeping every shortest-path predecessor and
+    /// path counts, as Brandes' algorithm needs.
+    fn dijkstra_all_preds(&self, source: SettlementId) -> BrandesSearch {
+        let mut s = BrandesSearch::default();
+        let mut dist: HashMap<SettlementId, f64> = HashMap::new();
+        let mut settled: HashSet<SettlementId> = HashSet::new();
+        let mut heap = BinaryHeap::new();
+        dist.insert(source, 0.0);
+        s.sigma.insert(source, 1.0);
+        s.preds.insert(source, Vec::new());
+        heap.push(Frontier {
+            cost: 0.0,
+            id: source,
+        });
+
+        while let Some(Frontier { cost, id }) = heap.pop() {
+            if cost > dist[&id] || !settled.insert(id) {
+                continue;
+            }
+            s.order.push(id);
+            for edge in &self.adjacency[&id] {
+                let next = cost + f64::from(self.routes[edge.route].distance);
+                match dist.get(&edge.to).copied() {
+                    Some(old) if next > old => {}
+                    Some(old) if next == old => {
+                        let sigma = s.sigma[&id];
+                        *s.sigma.get_mut(&edge.to).expect("seen") += sigma;
+                        s.preds.get_mut(&edge.to).expect("seen").push(id);
+                    }
+                    _ => {
+                        dist.insert(edge.to, next);
+                        s.sigma.insert(edge.to, s.sigma[&id]);
+                        s.preds.insert(edge.to, vec![id]);
+                        heap.push(Frontier {
+                            cost: next,
+                            id: edge.to,
+                        });
+                    }
+                }
+            }
+        }
+        s
+    }
+}
+
+#[derive(Default)]
+struct Search {
+    cost: HashMap<SettlementId, f64>,
+    prev: HashMap<SettlementId, (SettlementId, usize)>,
+}
+
+#[derive(Default)]
+struct BrandesSearch {
+    /// Settlements in non-decreasing distance order
+    order: Vec<SettlementId>,
+    sigma: HashMap<SettlementId, f64>,
+    preds: HashMap<SettlementId, Vec<SettlementId>>,
+}
+
+/// Min-heap entry: lowest cost first, then lowest id.
+#[derive(Debug, Clone, Copy)]
+struct Frontier {
+    cost: f64,
+    id: SettlementId,
+}
+
+impl PartialEq for Frontier {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Frontier {}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Frontier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.id.0.cmp(&self.id.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn s(id: u32) -> SettlementId {
+        SettlementId::new(id)
+    }
+
+    /// 0 - 1 - 2 - 3 line plus an expensive 0 - 3 shortcut.
+    fn line() -> Vec<Route> {
+        vec![
+            Route::new(s(0), s(1), 2),
+            Route::new(s(1), s(2), 2),
+            Route::new(s(2), s(3), 2),
+            Route::new(s(0), s(3), 5).with_cost(10.0),
+        ]
+    }
+
+    #[test]
+    fn shortest_path_depends_on_metric() {
+        let graph = RouteGraph::new(&line());
+
+        let by_distance = graph
+            .shortest_path(s(0), s(3), PathMetric::Distance)
+            .unwrap();
+        assert_eq!(by_distance.settlements, vec![s(0), s(3)]);
+        assert_eq!(by_distance.distance, 5);
+
+        let by_cost = graph
+            .shortest_path(s(0), s(3), PathMetric::TransportCost)
+            .unwrap();
+        assert_eq!(by_cost.settlements, vec![s(0), s(1), s(2), s(3)]);
+        assert_eq!(by_cost.cost, 3.0);
+        assert_eq!(by_cost.distance, 6);
+        assert_eq!(by_cost.hops(), 3);
+
+        assert!(
+            graph
+                .shortest_path(s(0), s(9), PathMetric::Distance)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn risk_adjusted_avoids_dangerous_edges() {
+        let mut routes = line();
+        routes[1].risk = 0.9;
+        let graph = RouteGraph::new(&routes);
+        let path = graph
+            .shortest_path(s(0), s(2), PathMetric::RiskAdjusted)
+            .unwrap();
+        assert_eq!(path.settlements, vec![s(0), s(3), s(2)]);
+    }
+
+    #[test]
+    fn reachability_within_ticks() {
+        let graph = RouteGraph::new(&line());
+        assert_eq!(
+            graph.reachable_within(s(0), 4),
+            vec![(s(0), 0), (s(1), 2), (s(2), 4)]
+        );
+    }
+
+    #[test]
+    fn chokepoints_rank_hub_first() {
+        // Star around 0, with 4 hanging off 1.
+        let routes = vec![
+            Route::new(s(0), s(1), 1),
+            Route::new(s(0), s(2), 1),
+            Route::new(s(0), s(3), 1),
+            Route::new(s(1), s(4), 1),
+        ];
+        let graph = RouteGraph::new(&routes);
+        let chokepoints = graph.chokepoints();
+        assert_eq!(chokepoints[0].0, s(0));
+        assert_eq!(chokepoints[1].0, s(1));
+        assert_eq!(chokepoints.len(), 2, "leaves are never chokepoints");
+    }
+}
@@ -1,7 +1,9 @@
+pub mod graph;
 pub mod resources;
 pub mod route;
 pub mod settlement;
 
+pub use graph::*;
 pub use resources::*;
 pub use route::*;
 pub use settlement::*;
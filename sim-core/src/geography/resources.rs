@@ -1,9 +1,11 @@
 // Resource types for primary production
 
+use serde::{Deserialize, Serialize};
+
 use crate::types::FacilityKey;
 
 /// Broad categories of natural resources
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ResourceType {
     /// Arable land - farms, orchards, vineyards, ranches
     Land,
@@ -16,7 +18,7 @@ pub enum ResourceType {
 }
 
 /// Quality affects output multiplier
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ResourceQuality {
     Poor,   // 0.5x output
     Normal, // 1.0x output
@@ -34,7 +36,7 @@ impl ResourceQuality {
 }
 
 /// A claimable resource slot at a settlement
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourceSlot {
     pub resource_type: ResourceType,
     pub quality: ResourceQuality,
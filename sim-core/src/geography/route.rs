@@ -1,15 +1,29 @@
 // Route type connecting settlements
 
+use serde::{Deserialize, Serialize};
+
 use crate::types::SettlementId;
 
+/// Which vessels can use a route
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum RouteKind {
+    /// Roads and trails - caravans
+    #[default]
+    Land,
+    /// Sea lanes - ships
+    Sea,
+}
+
 /// An edge connecting two settlements
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Route {
     pub from: SettlementId,
     pub to: SettlementId,
     pub distance: u32,       // Travel time in ticks
     pub transport_cost: f64, // Cost per unit of cargo
     pub risk: f64,           // 0.0 - 1.0, chance of incident per trip
+    #[serde(default)]
+    pub kind: RouteKind,
 }
 
 impl Route {
@@ -20,6 +34,7 @@ impl Route {
             distance,
             transport_cost: 1.0,
             risk: 0.0,
+            kind: RouteKind::Land,
         }
     }
 
@@ -33,6 +48,11 @@ impl Route {
         self
     }
 
+    pub fn with_kind(mut self, kind: RouteKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
     /// Check if this route connects the given settlements (in either direction)
     pub fn connects(&self, a: SettlementId, b: SettlementId) -> bool {
         (self.from == a && self.to == b) || (self.from == b && self.to == a)
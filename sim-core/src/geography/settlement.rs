@@ -1,16 +1,39 @@
 // Settlement type for multi-location economy
 
+use serde::{Deserialize, Serialize};
+
 use crate::types::{FacilityKey, SettlementId};
 
 use super::resources::{ResourceSlot, ResourceType};
 
+/// How a settlement connects to the world beyond the map
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SettlementRole {
+    /// On the coast; trades directly with the outside market
+    Port,
+    /// Navigable water to the sea; trades with the outside market
+    RiverPort,
+    /// Landlocked; outside goods arrive only by merchant transport
+    Inland,
+}
+
+impl SettlementRole {
+    /// Whether outside market ladders can attach here.
+    pub fn has_outside_access(&self) -> bool {
+        matches!(self, Self::Port | Self::RiverPort)
+    }
+}
+
 /// A node in the trade network
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settlement {
     pub id: SettlementId,
     pub name: String,
     pub position: (f64, f64),
     pub resource_slots: Vec<ResourceSlot>,
+    /// Explicit role; when unset the role is derived from resource slots.
+    #[serde(default)]
+    pub explicit_role: Option<SettlementRole>,
 }
 
 impl Settlement {
@@ -20,6 +43,7 @@ impl Settlement {
             name: name.into(),
             position,
             resource_slots: Vec::new(),
+            explicit_role: None,
         }
     }
 
@@ -28,6 +52,27 @@ impl Settlement {
         self
     }
 
+    pub fn with_role(mut self, role: SettlementRole) -> Self {
+        self.explicit_role = Some(role);
+        self
+    }
+
+    /// The explicit role if set, otherwise `Port` for settlements with a
+    /// coastal slot and `Inland` for the rest.
+    pub fn role(&self) -> SettlementRole {
+        self.explicit_role.unwrap_or_else(|| {
+            if self
+                .resource_slots
+                .iter()
+                .any(|s| s.resource_type == ResourceType::Coastal)
+            {
+                SettlementRole::Port
+            } else {
+                SettlementRole::Inland
+            }
+        })
+    }
+
     /// Find an available slot of the given resource type
     pub fn find_available_slot(&self, resource_type: ResourceType) -> Option<usize> {
         self.resource_slots
@@ -18,6 +18,7 @@
 use std::collections::HashMap;
 
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use super::skills::SkillId;
 use crate::types::Price;
@@ -29,7 +30,7 @@ pub const FILLED_LOWER_PROB: f64 = 0.3;
 
 /// Outcome of a skill's labor market participation for one tick.
 /// Used to inform bid adjustment for the next tick.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SkillOutcome {
     /// Number of slots that were filled
     pub filled: u32,
@@ -45,7 +46,7 @@ pub struct SkillOutcome {
 
 /// Tracks a facility's current wage bid for each skill.
 /// Bids adjust over time based on fill rate.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FacilityBidState {
     /// Current adaptive bid per skill (our estimate of market rate)
     pub bids: HashMap<SkillId, Price>,
@@ -128,7 +128,12 @@ fn clear_discrete_skill_market<'a>(
     Some((wage, matched_bids, matched_asks))
 }
 
-/// Clear labor markets sequentially by wage EMA (highest first)
+/// Clear labor markets sequentially by wage EMA (highest first).
+///
+/// Skill parents in `skills` let a worker with a specialist skill fill a
+/// slot for any ancestor skill (a smith can work as a laborer), at no less
+/// than that level's `reservation_wage`. Specialist markets clear first
+/// when their wage EMA is higher, so the premium comes from the bids.
 pub fn clear_labor_markets(
     skills: &[SkillDef],
     bids: &[LaborBid],
@@ -155,11 +160,50 @@ pub fn clear_labor_markets(
     // Track which bids have been used (one hire per bid)
     let mut used_bids: HashSet<u64> = HashSet::new();
 
+    // A worker can fill a slot for their own skill or any ancestor of it
+    let skill_defs: HashMap<SkillId, SkillDef> = skills.iter().map(|s| (s.id, s.clone())).collect();
+    let fillable: HashMap<SkillId, Vec<SkillId>> = asks
+        .iter()
+        .map(|a| a.skill)
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .map(|skill| {
+            let chain = match skill_defs.get(&skill) {
+                Some(def) => def.skill_chain(&skill_defs).iter().map(|s| s.id).collect(),
+                None => vec![skill],
+            };
+            (skill, chain)
+        })
+        .collect();
+
     // 2. Clear each skill market in order
     for skill in skill_order {
         // Track bids removed due to budget constraints (for this skill market only)
         let mut removed_bids: HashSet<u64> = HashSet::new();
 
+        // One ask per unhired worker who qualifies, at no less than this
+        // level's reservation wage. Of several qualifying asks the cheapest
+        // (then lowest id) stands.
+        let reservation = skill_defs.get(&skill).map_or(0.0, |d| d.reservation_wage);
+        let mut level_asks: HashMap<u64, LaborAsk> = HashMap::new();
+        for ask in asks
+            .iter()
+            .filter(|a| fillable[&a.skill].contains(&skill))
+            .filter(|a| !filled_workers.contains(&a.worker_id))
+        {
+            let candidate = LaborAsk {
+                skill,
+                min_wage: ask.min_wage.max(reservation),
+                ..ask.clone()
+            };
+            match level_asks.get(&ask.worker_id) {
+                Some(best) if (best.min_wage, best.id) <= (candidate.min_wage, candidate.id) => {}
+                _ => {
+                    level_asks.insert(ask.worker_id, candidate);
+                }
+            }
+        }
+
         // Iterative clearing with budget relaxation
         loop {
             // Filter bids: this skill, unused, not removed
@@ -170,12 +214,7 @@ pub fn clear_labor_markets(
                 .filter(|b| !removed_bids.contains(&b.id))
                 .collect();
 
-            // Filter asks: this skill, worker not already hired
-            let skill_asks: Vec<_> = asks
-                .iter()
-                .filter(|a| a.skill == skill)
-                .filter(|a| !filled_workers.contains(&a.worker_id))
-                .collect();
+            let skill_asks: Vec<_> = level_asks.values().collect();
 
             if skill_bids.is_empty() || skill_asks.is_empty() {
                 break;
@@ -230,7 +269,7 @@ pub fn clear_labor_markets(
             clearing_wages.insert(skill, wage);
 
             // Pair according to explicit deterministic policy.
-            for (bid, ask) in matched_bids.into_iter().zip(matched_asks.into_iter()) {
+            for (bid, ask) in matched_bids.into_iter().zip(matched_asks) {
                 // Deduct from facility budget
                 if let Some(budget) = remaining_budgets.get_mut(&bid.facility_id) {
                     *budget -= wage;
@@ -286,6 +325,7 @@ mod tests {
             id: SkillId(id),
             name: name.to_string(),
             parent: parent.map(SkillId),
+            reservation_wage: 0.0,
         }
     }
 
@@ -541,6 +581,41 @@ mod tests {
         assert_eq!(result.assignments[0].facility_id, fk(1));
     }
 
+    #[test]
+    fn specialist_fills_parent_slot_above_reservation() {
+        let mut laborer = skill_def(1, "Laborer", None);
+        laborer.reservation_wage = 10.0;
+        let skills = vec![laborer, skill_def(2, "Smith", Some(1))];
+
+        // Only a smith is looking for work; the laborer slot pays too little
+        // at first, then clears the reservation wage
+        let asks = vec![ask(1, 1, 2, 0.0)];
+        let wage_emas = emas(&[(1, 20.0), (2, 60.0)]);
+        let facility_budgets = budgets(&[(1, 200.0)]);
+
+        let low = vec![bid(1, 1, 1, 8.0)];
+        let result = clear_labor_markets(&skills, &low, &asks, &wage_emas, &facility_budgets);
+        assert!(result.assignments.is_empty());
+
+        let high = vec![bid(1, 1, 1, 12.0)];
+        let result = clear_labor_markets(&skills, &high, &asks, &wage_emas, &facility_budgets);
+        assert_eq!(result.assignments.len(), 1);
+        assert_eq!(result.assignments[0].skill, skill(1));
+        assert_eq!(result.assignments[0].wage, 12.0);
+
+        // A laborer cannot fill a smith slot
+        let smith_bid = vec![bid(1, 1, 2, 50.0)];
+        let laborer_ask = vec![ask(1, 1, 1, 0.0)];
+        let result = clear_labor_markets(
+            &skills,
+            &smith_bid,
+            &laborer_ask,
+            &wage_emas,
+            &facility_budgets,
+        );
+        assert!(result.assignments.is_empty());
+    }
+
     #[test]
     fn complementarity_increases_output() {
         use super::super::production_fn::ComplementaryProductionFn;
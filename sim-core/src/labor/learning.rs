@@ -0,0 +1,218 @@
+//! Skill acquisition, apprenticeship and decay.
+//!
+//! Known skills carry experience at `learn_threshold`. Working under a skill
+//! keeps it (and its ancestors) fresh and builds experience towards its child
+//! skills; unemployed pops can pay employed masters to train them. Skills
+//! left unused lose experience and are forgotten at zero. Base skills, those
+//! without a registered parent, are never forgotten and are all a child
+//! inherits.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::agents::Pop;
+use crate::types::Price;
+
+use super::skills::{SkillDef, SkillId};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SkillLearningConfig {
+    /// Experience per tick towards each child of the skill a pop works under.
+    pub apprentice_rate: f64,
+    /// Experience per tick of paid training.
+    pub training_rate: f64,
+    /// Fee per tick of training, shared by the skill's employed masters.
+    pub training_fee: Price,
+    /// Experience at which a skill is learned.
+    pub learn_threshold: f64,
+    /// Experience lost per tick a learned skill goes unused.
+    pub decay_rate: f64,
+}
+
+impl Default for SkillLearningConfig {
+    fn default() -> Self {
+        Self {
+            apprentice_rate: 0.02,
+            training_rate: 0.1,
+            training_fee: 5.0,
+            learn_threshold: 1.0,
+            decay_rate: 0.01,
+        }
+    }
+}
+
+/// Skills gained and lost by one pop in one tick, in id order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SkillChanges {
+    pub learned: Vec<SkillId>,
+    pub forgotten: Vec<SkillId>,
+}
+
+/// A skill with no registered parent.
+pub fn is_base_skill(skill: SkillId, defs: &HashMap<SkillId, SkillDef>) -> bool {
+    defs.get(&skill).is_none_or(|d| d.parent.is_none())
+}
+
+/// The skills a child inherits from its parent.
+pub fn inherited_skills(
+    skills: &HashSet<SkillId>,
+    defs: &HashMap<SkillId, SkillDef>,
+) -> HashSet<SkillId> {
+    skills
+        .iter()
+        .copied()
+        .filter(|s| is_base_skill(*s, defs))
+        .collect()
+}
+
+/// The skill an unemployed pop would pay to train towards: the best-paid
+/// registered skill it lacks whose parent it knows, that pays more than the
+/// pop currently earns and has a master to teach it.
+pub fn training_target(
+    pop: &Pop,
+    defs: &HashMap<SkillId, SkillDef>,
+    wage_ema: &HashMap<SkillId, Price>,
+    has_master: impl Fn(SkillId) -> bool,
+) -> Option<SkillId> {
+    let mut candidates: Vec<(SkillId, Price)> = defs
+        .values()
+        .filter(|d| !pop.skills.contains(&d.id))
+        .filter(|d| d.parent.is_none_or(|p| pop.skills.contains(&p)))
+        .filter_map(|d| wage_ema.get(&d.id).map(|w| (d.id, *w)))
+        .filter(|(_, wage)| *wage > pop.income_ema)
+        .filter(|(id, _)| has_master(*id))
+        .collect();
+    candidates.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.0.cmp(&b.0.0)));
+    candidates.first().map(|(id, _)| *id)
+}
+
+/// Advance one pop's skills by one tick of work, training and disuse.
+pub fn advance_skills(
+    pop: &mut Pop,
+    defs: &HashMap<SkillId, SkillDef>,
+    training: Option<SkillId>,
+    config: &SkillLearningConfig,
+) -> SkillChanges {
+    let threshold = config.learn_threshold;
+    let mut changes = SkillChanges::default();
+
+    let used: HashSet<SkillId> = match pop.employed_skill {
+        Some(skill) => match defs.get(&skill) {
+            Some(def) => def.skill_chain(defs).iter().map(|s| s.id).collect(),
+            None => HashSet::from([skill]),
+        },
+        None => HashSet::new(),
+    };
+
+    let mut known: Vec<SkillId> = pop.skills.iter().copied().collect();
+    known.sort_by_key(|s| s.0);
+    for skill in known {
+        if used.contains(&skill) {
+            pop.skill_experience.insert(skill, threshold);
+            continue;
+        }
+        if is_base_skill(skill, defs) {
+            continue;
+        }
+        let experience = pop.skill_experience.entry(skill).or_insert(threshold);
+        *experience -= config.decay_rate;
+        if *experience <= 0.0 {
+            pop.skill_experience.remove(&skill);
+            pop.skills.remove(&skill);
+            changes.forgotten.push(skill);
+        }
+    }
+
+    if let Some(employed) = pop.employed_skill {
+        for def in defs.values().filter(|d| d.parent == Some(employed)) {
+            if !pop.skills.contains(&def.id) {
+                *pop.skill_experience.entry(def.id).or_insert(0.0) += config.apprentice_rate;
+            }
+        }
+    }
+    if let Some(target) = training.filter(|t| !pop.skills.contains(t)) {
+        *pop.skill_experience.entry(target).or_insert(0.0) += config.training_rate;
+    }
+
+    let mut progressing: Vec<SkillId> = pop
+        .skill_experience
+        .iter()
+        .filter(|(skill, experience)| !pop.skills.contains(*skill) && **experience >= threshold)
+        .map(|(skill, _)| *skill)
+        .collect();
+    progressing.sort_by_key(|s| s.0);
+    for skill in progressing {
+        pop.skills.insert(skill);
+        pop.skill_experience.insert(skill, threshold);
+        changes.learned.push(skill);
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LABORER: SkillId = SkillId(1);
+    const SMITH: SkillId = SkillId(2);
+
+    fn defs() -> HashMap<SkillId, SkillDef> {
+        [(LABORER, None), (SMITH, Some(LABORER))]
+            .into_iter()
+            .map(|(id, parent)| {
+                (
+                    id,
+                    SkillDef {
+                        id,
+                        name: String::new(),
+                        parent,
+                        reservation_wage: 0.0,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn apprentice_learns_then_forgets_when_idle() {
+        let defs = defs();
+        let config = SkillLearningConfig {
+            apprentice_rate: 0.5,
+            decay_rate: 0.5,
+            ..Default::default()
+        };
+        let mut pop = Pop::new().with_skills([LABORER]);
+        pop.employed_skill = Some(LABORER);
+
+        let changes = advance_skills(&mut pop, &defs, None, &config);
+        assert!(changes.learned.is_empty());
+        let changes = advance_skills(&mut pop, &defs, None, &config);
+        assert_eq!(changes.learned, vec![SMITH]);
+
+        pop.employed_skill = None;
+        let changes = advance_skills(&mut pop, &defs, None, &config);
+        assert!(changes.forgotten.is_empty());
+        let changes = advance_skills(&mut pop, &defs, None, &config);
+        assert_eq!(changes.forgotten, vec![SMITH]);
+        assert!(pop.skills.contains(&LABORER), "base skills never decay");
+    }
+
+    #[test]
+    fn training_targets_best_paid_teachable_skill() {
+        let defs = defs();
+        let mut pop = Pop::new().with_skills([LABORER]);
+        pop.income_ema = 5.0;
+        let wages = HashMap::from([(LABORER, 4.0), (SMITH, 9.0)]);
+
+        assert_eq!(training_target(&pop, &defs, &wages, |_| true), Some(SMITH));
+        assert_eq!(training_target(&pop, &defs, &wages, |_| false), None);
+        pop.income_ema = 10.0;
+        assert_eq!(training_target(&pop, &defs, &wages, |_| true), None);
+
+        let smiths = HashSet::from([LABORER, SMITH]);
+        assert_eq!(inherited_skills(&smiths, &defs), HashSet::from([LABORER]));
+    }
+}
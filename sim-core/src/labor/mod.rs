@@ -1,11 +1,13 @@
 pub mod bidding;
 pub mod clearing;
+pub mod learning;
 pub mod production_fn;
 pub mod skills;
 pub mod subsistence;
 
 pub use bidding::*;
 pub use clearing::*;
+pub use learning::*;
 pub use production_fn::*;
 pub use skills::*;
 pub use subsistence::*;
@@ -11,10 +11,25 @@ pub trait ProductionFn {
 
     /// Skills this production function uses
     fn relevant_skills(&self) -> Vec<SkillId>;
+
+    /// Output added by the last `skill` worker in `workers`, or by the
+    /// first when there is none.
+    fn marginal_product(&self, workers: &HashMap<SkillId, u32>, skill: SkillId) -> f64 {
+        let mut fewer = workers.clone();
+        let mut more = workers.clone();
+        match workers.get(&skill).copied().unwrap_or(0) {
+            0 => *more.entry(skill).or_insert(0) += 1,
+            n => {
+                fewer.insert(skill, n - 1);
+            }
+        }
+        self.compute(&more) - self.compute(&fewer)
+    }
 }
 
 /// Simple production function with complementarity
 /// Output = sum of individual contributions + bonus for combinations
+#[derive(Debug, Clone)]
 pub struct ComplementaryProductionFn {
     /// Base output per worker of each skill type
     pub base_output: HashMap<SkillId, f64>,
@@ -34,10 +49,16 @@ impl ProductionFn for ComplementaryProductionFn {
     fn compute(&self, workers: &HashMap<SkillId, u32>) -> f64 {
         let mut output = 0.0;
 
+        // Sum in skill order so output is deterministic
+        let mut skills: Vec<(SkillId, u32)> = workers.iter().map(|(s, c)| (*s, *c)).collect();
+        skills.sort_by_key(|(s, _)| s.0);
+        let mut bonuses: Vec<_> = self.complementarity_bonus.iter().collect();
+        bonuses.sort_by_key(|((a, b), _)| (a.0, b.0));
+
         // Base output with diminishing returns
-        for (skill, &count) in workers {
-            let base = self.base_output.get(skill).copied().unwrap_or(0.0);
-            let max_cap = self.max_optimal_capacity.get(skill).copied().unwrap_or(10);
+        for &(skill, count) in &skills {
+            let base = self.base_output.get(&skill).copied().unwrap_or(0.0);
+            let max_cap = self.max_optimal_capacity.get(&skill).copied().unwrap_or(10);
 
             for i in 0..count {
                 if i < max_cap {
@@ -52,7 +73,7 @@ impl ProductionFn for ComplementaryProductionFn {
         }
 
         // Complementarity bonuses
-        for ((skill_a, skill_b), bonus) in &self.complementarity_bonus {
+        for ((skill_a, skill_b), bonus) in bonuses {
             let count_a = workers.get(skill_a).copied().unwrap_or(0);
             let count_b = workers.get(skill_b).copied().unwrap_or(0);
             // Bonus applies to each pair
@@ -64,6 +85,12 @@ impl ProductionFn for ComplementaryProductionFn {
     }
 
     fn relevant_skills(&self) -> Vec<SkillId> {
-        self.base_output.keys().copied().collect()
+        let mut skills: Vec<SkillId> = self.base_output.keys().copied().collect();
+        for (a, b) in self.complementarity_bonus.keys() {
+            skills.extend([*a, *b]);
+        }
+        skills.sort_by_key(|s| s.0);
+        skills.dedup();
+        skills
     }
 }
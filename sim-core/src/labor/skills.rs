@@ -1,19 +1,36 @@
 use std::collections::{HashMap, HashSet};
 
+use serde::{Deserialize, Serialize};
+
 use crate::types::Price;
 
 // === SKILL TYPES ===
 
-#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SkillId(pub u32);
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SkillDef {
     pub id: SkillId,
     pub name: String,
     pub parent: Option<SkillId>, // None for root (e.g., Laborer)
+    /// Lowest wage anyone accepts for a slot at this level.
+    #[serde(default)]
+    pub reservation_wage: Price,
 }
 
 impl SkillDef {
+    /// A root skill with no name and no reservation wage, for skills seen in
+    /// play but never registered.
+    pub fn unregistered(id: SkillId) -> Self {
+        Self {
+            id,
+            name: String::new(),
+            parent: None,
+            reservation_wage: 0.0,
+        }
+    }
+
     /// Returns all ancestor skills (including self)
     pub fn skill_chain<'a>(&self, all_skills: &'a HashMap<SkillId, SkillDef>) -> Vec<&'a SkillDef> {
         let mut chain = vec![];
@@ -1,9 +1,11 @@
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
+
 use crate::types::{GoodId, PopKey, Price};
 
 /// Config for converting in-kind subsistence fallback into labor reservation asks.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubsistenceReservationConfig {
     /// Good used to value subsistence output (typically grain).
     pub grain_good: GoodId,
@@ -0,0 +1,177 @@
+//! Optional double-entry journal of transfers.
+//!
+//! Every recorded transfer moves currency or a good from a credit account
+//! to a debit account, so the journal always balances. Accounts outside the
+//! economy (the outside market, transport operators, subsistence land,
+//! construction and heirless estates) are where currency and goods enter or leave; summing the
+//! journal's external flows per phase explains a tick's stock-flow deltas
+//! and points any `currency_residual` at the phase that moved money without
+//! recording it.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{FacilityHandle, GoodId, MerchantId, PopHandle, Quantity, SettlementId};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Account {
+    /// A migrant's balance moves to its new handle when it arrives.
+    Pop(PopHandle),
+    Merchant(MerchantId),
+    Facility(FacilityHandle),
+    /// Clearing counterparty of a settlement's market; nets to zero.
+    Market(SettlementId),
+    /// Import sellers and export buyers beyond the map.
+    Outside(SettlementId),
+    /// Land worked for subsistence.
+    Commons(SettlementId),
+    /// Freight, fares, courier fees and vessel upkeep.
+    Transport,
+    /// Construction costs paid and salvage refunded.
+    Construction,
+    /// Holdings of pops who died with no one left to inherit them.
+    Unclaimed(SettlementId),
+}
+
+impl Account {
+    /// Whether the account lies outside the simulated economy.
+    pub fn is_external(&self) -> bool {
+        matches!(
+            self,
+            Account::Outside(_)
+                | Account::Commons(_)
+                | Account::Transport
+                | Account::Construction
+                | Account::Unclaimed(_)
+        )
+    }
+}
+
+/// The part of the tick a transfer happened in. `Actions` covers merchant
+/// actions applied at the end of the tick and direct calls between ticks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Phase {
+    Transport,
+    Labor,
+    Skills,
+    Market,
+    Mortality,
+    Migration,
+    /// Loan interest and installments.
+    Credit,
+    Actions,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub tick: u64,
+    pub phase: Phase,
+    /// Account receiving the amount.
+    pub debit: Account,
+    /// Account giving the amount.
+    pub credit: Account,
+    /// `None` for currency.
+    pub good: Option<GoodId>,
+    pub amount: Quantity,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Ledger {
+    pub entries: Vec<JournalEntry>,
+}
+
+impl Ledger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record `amount` of `good` (currency when `None`) moving `from` one
+    /// account `to` another. Zero amounts are skipped.
+    pub fn transfer(
+        &mut self,
+        tick: u64,
+        phase: Phase,
+        from: Account,
+        to: Account,
+        good: Option<GoodId>,
+        amount: Quantity,
+    ) {
+        if amount == 0.0 {
+            return;
+        }
+        self.entries.push(JournalEntry {
+            tick,
+            phase,
+            debit: to,
+            credit: from,
+            good,
+            amount,
+        });
+    }
+
+    pub fn entries_at(&self, tick: u64) -> impl Iterator<Item = &JournalEntry> {
+        self.entries.iter().filter(move |e| e.tick == tick)
+    }
+
+    /// Debits less credits of one account over the whole journal.
+    pub fn balance(&self, account: Account, good: Option<GoodId>) -> f64 {
+        self.entries
+            .iter()
+            .filter(|e| e.good == good)
+            .map(|e| {
+                let mut net = 0.0;
+                if e.debit == account {
+                    net += e.amount;
+                }
+                if e.credit == account {
+                    net -= e.amount;
+                }
+                net
+            })
+            .sum()
+    }
+
+    /// Net inflow of `good` into the economy from external accounts during
+    /// `tick`, by phase in phase order.
+    pub fn external_inflow(&self, tick: u64, good: Option<GoodId>) -> Vec<(Phase, f64)> {
+        let mut by_phase: HashMap<Phase, f64> = HashMap::new();
+        for entry in self.entries_at(tick).filter(|e| e.good == good) {
+            let inflow = match (entry.credit.is_external(), entry.debit.is_external()) {
+                (true, false) => entry.amount,
+                (false, true) => -entry.amount,
+                _ => continue,
+            };
+            *by_phase.entry(entry.phase).or_insert(0.0) += inflow;
+        }
+        let mut flows: Vec<(Phase, f64)> = by_phase.into_iter().collect();
+        flows.sort_by_key(|(phase, _)| *phase);
+        flows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn external_inflow_nets_by_phase() {
+        let town = SettlementId(1);
+        let merchant = Account::Merchant(MerchantId(1));
+        let outside = Account::Outside(town);
+        let mut ledger = Ledger::new();
+        ledger.transfer(1, Phase::Market, outside, merchant, None, 10.0);
+        ledger.transfer(1, Phase::Market, merchant, Account::Market(town), None, 4.0);
+        ledger.transfer(1, Phase::Transport, merchant, Account::Transport, None, 3.0);
+        ledger.transfer(1, Phase::Labor, merchant, Account::Transport, None, 0.0);
+        ledger.transfer(2, Phase::Market, outside, merchant, None, 1.0);
+
+        assert_eq!(ledger.entries.len(), 4, "zero transfers are skipped");
+        assert_eq!(
+            ledger.external_inflow(1, None),
+            vec![(Phase::Transport, -3.0), (Phase::Market, 10.0)]
+        );
+        assert_eq!(ledger.balance(merchant, None), 10.0 - 4.0 - 3.0 + 1.0);
+        assert_eq!(ledger.balance(merchant, Some(1)), 0.0);
+    }
+}
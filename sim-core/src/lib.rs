@@ -57,52 +57,76 @@
 //! ## Module Structure
 //!
 //! - `types`       Core type definitions (IDs, goods)
-//! - `geography`   Settlement and route definitions
+//! - `geography`   Settlement, route and route-network definitions
 //! - `agents`      Pop and merchant agent types
 //! - `production`  Recipe and facility definitions
 //! - `labor`       Skill-based labor market
+//! - `ledger`      Optional double-entry journal of transfers
 //! - `consumption` Utility-based consumption model
+//! - `content`     Content pack loading and validation
+//! - `credit`      Loans between merchants
 //! - `market`      Auction-based market clearing
+//! - `migration`   Pops moving between settlements
 //! - `needs`       Need and utility curve definitions
+//! - `scenario`    Declarative scenario files
+//! - `snapshot`    Versioned World save/load
 //! - `tick`        Full simulation tick orchestration
+//! - `transport`   Ships and caravans moving merchant cargo
+//! - `wasm`        JavaScript/WASM facade over World
 //! - `world`       World state container
 
 pub mod accounting;
 pub mod agents;
 pub mod consumption;
+pub mod content;
+pub mod credit;
 mod determinism;
 pub mod external;
 pub mod geography;
 #[cfg(feature = "instrument")]
 pub use instrument;
 pub mod labor;
+pub mod ledger;
 pub mod market;
+pub mod migration;
 pub mod mortality;
 pub mod needs;
 pub mod production;
+pub mod scenario;
+pub mod snapshot;
 pub mod tick;
+pub mod transport;
 pub mod types;
+pub mod wasm;
 pub mod world;
 
 // Re-export commonly used types at the crate root
 
 // Accounting
 pub use accounting::{
-    TickStockFlow, WorldFlowSnapshot, capture_world_flow_snapshot, decompose_tick_flow,
+    BalanceSheet, SettlementPosition, TickStockFlow, WorldFlowSnapshot,
+    capture_world_flow_snapshot, decompose_tick_flow,
 };
 
 // Core types
 pub use types::{
-    AgentId, FacilityHandle, FacilityKey, GoodId, GoodProfile, MerchantId, NeedContribution,
-    PopHandle, PopKey, Price, Quantity, SettlementId, facility_key_from_u64, facility_key_u64,
-    pop_key_from_u64, pop_key_u64,
+    AgentId, FacilityHandle, FacilityKey, GoodId, GoodProfile, LoanId, MerchantId,
+    NeedContribution, PopHandle, PopKey, Price, Quantity, SettlementId, VesselId,
+    facility_key_from_u64, facility_key_u64, pop_key_from_u64, pop_key_u64,
 };
 
 // Agents
-pub use agents::{ConsumptionResult, MerchantAgent, Pop, Stockpile};
+pub use agents::{
+    ConsumptionResult, Courier, MarketKnowledge, MarketSnapshot, MerchantAction, MerchantAgent,
+    MerchantController, MerchantObservation, Pop, SettlementObservation, Stockpile,
+    SupplyCurveController,
+};
 
 // Geography
-pub use geography::{ResourceQuality, ResourceSlot, ResourceType, Route, Settlement};
+pub use geography::{
+    PathMetric, ResourceQuality, ResourceSlot, ResourceType, Route, RouteGraph, RouteKind,
+    RoutePath, Settlement, SettlementRole,
+};
 
 // External market
 pub use external::{
@@ -110,13 +134,37 @@ pub use external::{
     ExternalMarketConfig, OutsideFlowTotals, SettlementFriction, compute_depth_multiplier,
 };
 
+// Credit
+pub use credit::{CreditError, CreditTotals, Loan};
+
+// Ledger
+pub use ledger::{Account, JournalEntry, Ledger, Phase};
+
 // Production
 pub use production::{
-    Facility, FacilityDef, FacilityType, Recipe, RecipeId, get_facility_def, get_facility_defs,
+    Facility, FacilityDef, FacilityType, IncomeStatement, Recipe, RecipeId, Tool, get_facility_def,
+    get_facility_defs,
 };
 
 // World
-pub use world::World;
+pub use world::{ConstructionTotals, FacilityError, World};
+
+// WASM facade
+pub use wasm::WasmWorld;
+
+// Snapshots
+pub use snapshot::{SNAPSHOT_FORMAT_VERSION, SnapshotError};
+
+// Content
+pub use content::{ContentError, ContentPack, ContentRegistry};
+
+// Transport
+pub use transport::{
+    PortDependence, TransportError, TransportTotals, Vessel, VesselKind, VesselLocation,
+};
+
+// Scenarios
+pub use scenario::{Scenario, ScenarioError, ScenarioWorld};
 
 // Consumption
 pub use consumption::{compute_consumption, greedy_consume};
@@ -124,9 +172,10 @@ pub use consumption::{compute_consumption, greedy_consume};
 // Labor
 pub use labor::{
     Assignment, ComplementaryProductionFn, LaborAsk, LaborBid, LaborMarketResult, ProductionFn,
-    SkillDef, SkillId, SubsistenceReservationConfig, Worker, WorkerId,
-    build_subsistence_reservation_ladder, clear_labor_markets, generate_pop_asks_with_min_wage,
-    generate_worker_asks, update_wage_emas,
+    SkillChanges, SkillDef, SkillId, SkillLearningConfig, SubsistenceReservationConfig, Worker,
+    WorkerId, advance_skills, build_subsistence_reservation_ladder, clear_labor_markets,
+    generate_pop_asks_with_min_wage, generate_worker_asks, inherited_skills, training_target,
+    update_wage_emas,
 };
 
 // Market
@@ -144,4 +193,7 @@ pub use tick::{BUFFER_TICKS, PRICE_SWEEP_MAX, PRICE_SWEEP_MIN, PRICE_SWEEP_POINT
 pub use tick::{generate_demand_curve_orders, qty_norm, qty_sell};
 
 // Mortality
-pub use mortality::{MortalityOutcome, check_mortality, death_probability, growth_probability};
+pub use mortality::{DemographyConfig, FoodIntake, MortalityOutcome, check_mortality};
+
+// Migration
+pub use migration::{Migrant, MigrationConfig, Prospects, migration_probability};
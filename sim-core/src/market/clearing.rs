@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use crate::agents::{MerchantAgent, Pop};
+use crate::determinism::sorted_agent_ids;
 use crate::types::{AgentId, GoodId, Price, SettlementId};
 
 use super::orders::{Fill, Order, Side};
@@ -95,11 +96,11 @@ pub fn clear_single_market(
                 *agent_qty.entry(order.agent_id).or_insert(0.0) += order.quantity;
             }
             // Cap each agent's total by their budget
-            agent_qty
+            sorted_agent_ids(agent_qty.keys().copied())
                 .iter()
-                .map(|(agent_id, &total_qty)| {
+                .map(|agent_id| {
                     let budget = budgets.get(agent_id).copied().unwrap_or(f64::MAX);
-                    total_qty.min(budget / price)
+                    agent_qty[agent_id].min(budget / price)
                 })
                 .sum()
         } else {
@@ -116,11 +117,11 @@ pub fn clear_single_market(
                 *agent_qty.entry(order.agent_id).or_insert(0.0) += order.quantity;
             }
             // Cap each agent's total by their inventory
-            agent_qty
+            sorted_agent_ids(agent_qty.keys().copied())
                 .iter()
-                .map(|(agent_id, &total_qty)| {
+                .map(|agent_id| {
                     let inventory = inventories.get(agent_id).copied().unwrap_or(0.0);
-                    total_qty.min(inventory)
+                    agent_qty[agent_id].min(inventory)
                 })
                 .sum()
         } else {
@@ -186,10 +187,16 @@ pub fn clear_single_market(
     }
 
     // Total budget-constrained demand
-    let budget_constrained_demand: f64 = agent_max_fill.values().sum();
+    let budget_constrained_demand: f64 = sorted_agent_ids(agent_max_fill.keys().copied())
+        .iter()
+        .map(|agent_id| agent_max_fill[agent_id])
+        .sum();
 
     // Total inventory-constrained supply
-    let inventory_constrained_supply: f64 = seller_max_fill.values().sum();
+    let inventory_constrained_supply: f64 = sorted_agent_ids(seller_max_fill.keys().copied())
+        .iter()
+        .map(|agent_id| seller_max_fill[agent_id])
+        .sum();
 
     // The actual volume is min of constrained demand and constrained supply
     let actual_volume = budget_constrained_demand.min(inventory_constrained_supply);
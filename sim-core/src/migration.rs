@@ -0,0 +1,123 @@
+//! Pop migration between settlements.
+//!
+//! Pops compare what they earn and eat at home with what settlements within
+//! reach offer, and move when the gain clears a threshold. Moving costs a
+//! fare per unit of route distance and takes one tick per unit; migrants
+//! carry their currency and stocks with them.
+
+use serde::{Deserialize, Serialize};
+
+use crate::agents::Pop;
+use crate::types::{PopKey, SettlementId};
+
+/// Food satisfaction above this adds nothing to a settlement's appeal.
+const FOOD_SATISFACTION_CAP: f64 = 1.25;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MigrationConfig {
+    /// Relative gain in attractiveness below which nobody moves.
+    pub min_gain: f64,
+    /// Chance per tick of leaving for a destination twice as attractive.
+    pub max_rate: f64,
+    /// Currency charged per unit of route distance.
+    pub fare_per_distance: f64,
+    /// Farthest destination considered, in route distance.
+    pub max_distance: u32,
+}
+
+impl Default for MigrationConfig {
+    fn default() -> Self {
+        Self {
+            min_gain: 0.25,
+            max_rate: 0.05,
+            fare_per_distance: 1.0,
+            max_distance: 10,
+        }
+    }
+}
+
+/// What a place offers a pop: income deflated by the cost of its
+/// consumption basket there, and how well fed people are.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Prospects {
+    pub real_income: f64,
+    pub food_satisfaction: f64,
+}
+
+impl Prospects {
+    pub fn new(income: f64, basket_cost: f64, food_satisfaction: f64) -> Self {
+        let real_income = if basket_cost > 0.0 {
+            income / basket_cost
+        } else {
+            income
+        };
+        Self {
+            real_income,
+            food_satisfaction,
+        }
+    }
+
+    /// Real income, discounted by up to half where people go hungry.
+    pub fn attractiveness(&self) -> f64 {
+        let fed = self.food_satisfaction.clamp(0.0, FOOD_SATISFACTION_CAP);
+        self.real_income.max(0.0) * (0.5 + 0.5 * fed)
+    }
+
+    /// Relative gain of moving from `self` to `other`.
+    pub fn gain_to(&self, other: &Prospects) -> f64 {
+        let here = self.attractiveness();
+        let there = other.attractiveness();
+        if here > 0.0 {
+            there / here - 1.0
+        } else if there > 0.0 {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Chance per tick that a pop facing `gain` leaves. Zero below
+/// `min_gain`, rising linearly to `max_rate` at a gain of 1.0.
+pub fn migration_probability(gain: f64, config: &MigrationConfig) -> f64 {
+    if gain < config.min_gain {
+        return 0.0;
+    }
+    config.max_rate * gain.min(1.0)
+}
+
+/// A pop on the road.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Migrant {
+    pub pop: Pop,
+    pub from: SettlementId,
+    /// Key the pop had at `from`; its journal account moves to the new key
+    /// on arrival.
+    #[serde(default)]
+    pub from_key: PopKey,
+    pub to: SettlementId,
+    pub ticks_remaining: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hunger_discounts_income() {
+        let fed = Prospects::new(10.0, 2.0, 1.0);
+        let hungry = Prospects::new(10.0, 2.0, 0.0);
+        assert_eq!(fed.attractiveness(), 5.0);
+        assert_eq!(hungry.attractiveness(), 2.5);
+        assert_eq!(hungry.gain_to(&fed), 1.0);
+    }
+
+    #[test]
+    fn probability_is_zero_below_threshold_and_capped() {
+        let config = MigrationConfig::default();
+        assert_eq!(migration_probability(0.1, &config), 0.0);
+        assert_eq!(migration_probability(0.5, &config), 0.025);
+        assert_eq!(migration_probability(3.0, &config), config.max_rate);
+    }
+}
@@ -1,47 +1,131 @@
 //! Population mortality and growth mechanics.
 //!
-//! Pops die when food satisfaction is low, creating labor scarcity that
+//! Pops die when food intake stays low, creating labor scarcity that
 //! drives wages up. This closes the feedback loop between prices and wages.
+//!
+//! Hazards are driven by each pop's rolling [`FoodIntake`] rather than a
+//! single tick's satisfaction, so one bad market tick does not kill and one
+//! good one does not breed. All parameters live in [`DemographyConfig`].
 
 use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Tunable demography parameters, held on `World`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DemographyConfig {
+    /// Weight of the latest tick in the rolling intake EMA.
+    pub intake_alpha: f64,
+    /// Intake below which a tick counts as deficit and death risk starts.
+    pub deficit_threshold: f64,
+    /// Consecutive deficit ticks before the full death hazard applies.
+    pub deficit_onset_ticks: u32,
+    /// Death hazard at zero rolling intake.
+    pub max_death_hazard: f64,
+    /// Curvature of the death hazard in the deficit (2 = quadratic).
+    pub deficit_exponent: f64,
+    /// Intake above which a tick counts as surplus and growth is possible.
+    pub surplus_threshold: f64,
+    /// Rolling intake at which the growth hazard peaks.
+    pub surplus_cap: f64,
+    /// Consecutive surplus ticks before the full growth hazard applies.
+    pub surplus_onset_ticks: u32,
+    /// Growth hazard at or above `surplus_cap`.
+    pub max_growth_hazard: f64,
+}
+
+impl Default for DemographyConfig {
+    fn default() -> Self {
+        Self {
+            intake_alpha: 0.5,
+            deficit_threshold: 0.9,
+            deficit_onset_ticks: 2,
+            max_death_hazard: 0.99,
+            deficit_exponent: 2.0,
+            surplus_threshold: 1.0,
+            surplus_cap: 1.25,
+            surplus_onset_ticks: 3,
+            max_growth_hazard: 0.02,
+        }
+    }
+}
+
+impl DemographyConfig {
+    /// Death hazard for a pop's intake history.
+    ///
+    /// Zero while rolling intake is at or above `deficit_threshold`. Below
+    /// it, `max_death_hazard * (deficit / threshold)^deficit_exponent`,
+    /// ramped in over `deficit_onset_ticks` of sustained deficit.
+    pub fn death_hazard(&self, intake: &FoodIntake) -> f64 {
+        if intake.ema >= self.deficit_threshold {
+            return 0.0;
+        }
+        let deficit = (self.deficit_threshold - intake.ema.max(0.0)) / self.deficit_threshold;
+        let hazard = self.max_death_hazard * deficit.powf(self.deficit_exponent);
+        hazard.min(self.max_death_hazard) * onset(intake.deficit_ticks, self.deficit_onset_ticks)
+    }
+
+    /// Growth hazard for a pop's intake history.
+    ///
+    /// Zero at or below `surplus_threshold`, rising linearly to
+    /// `max_growth_hazard` at `surplus_cap` and flat above, ramped in over
+    /// `surplus_onset_ticks` of sustained surplus.
+    pub fn growth_hazard(&self, intake: &FoodIntake) -> f64 {
+        if intake.ema <= self.surplus_threshold {
+            return 0.0;
+        }
+        let span = (self.surplus_cap - self.surplus_threshold).max(0.001);
+        let progress = ((intake.ema - self.surplus_threshold) / span).clamp(0.0, 1.0);
+        self.max_growth_hazard * progress * onset(intake.surplus_ticks, self.surplus_onset_ticks)
+    }
+}
+
+/// Fraction of a hazard that applies after `ticks` of a sustained condition.
+fn onset(ticks: u32, onset_ticks: u32) -> f64 {
+    if onset_ticks == 0 {
+        return 1.0;
+    }
+    (f64::from(ticks) / f64::from(onset_ticks)).min(1.0)
+}
 
-const DEATH_FREE_SATISFACTION: f64 = 0.9;
-const SURPLUS_SATISFACTION_CAP: f64 = 1.25;
-const MAX_GROWTH_PROBABILITY: f64 = 0.02;
-
-/// Probability of death given food satisfaction level.
-///
-/// No death above 90% food satisfaction.
-/// Below 90%, death risk scales quadratically with deficit.
-///
-/// Formula below threshold:
-/// `0.99 * ((0.9 - satisfaction) / 0.9)^2`
-pub fn death_probability(food_satisfaction: f64) -> f64 {
-    if food_satisfaction >= DEATH_FREE_SATISFACTION {
-        0.0
-    } else if food_satisfaction <= 0.0 {
-        0.99 // Cap at 99% to allow slim survival chance
-    } else {
-        let deficit = DEATH_FREE_SATISFACTION - food_satisfaction;
-        // Quadratic scaling, normalized so satisfaction=0 gives p=0.99.
-        let p = 0.99 * (deficit / DEATH_FREE_SATISFACTION).powi(2);
-        p.min(0.99)
+/// A pop's rolling food intake. New pops start fed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FoodIntake {
+    /// EMA of per-tick food satisfaction.
+    pub ema: f64,
+    /// Consecutive ticks with intake below the deficit threshold.
+    pub deficit_ticks: u32,
+    /// Consecutive ticks with intake above the surplus threshold.
+    pub surplus_ticks: u32,
+}
+
+impl Default for FoodIntake {
+    fn default() -> Self {
+        Self {
+            ema: 1.0,
+            deficit_ticks: 0,
+            surplus_ticks: 0,
+        }
     }
 }
 
-/// Probability of growth (new pop spawning) given food satisfaction level.
-///
-/// Growth is possible only with food surplus (satisfaction > 1.0), and
-/// increases modestly up to the subsistence-surplus sat cap.
-///
-/// Above the cap, growth probability is held constant.
-pub fn growth_probability(food_satisfaction: f64) -> f64 {
-    if food_satisfaction <= 1.0 {
-        0.0
-    } else {
-        let span = (SURPLUS_SATISFACTION_CAP - 1.0).max(0.001);
-        let progress = ((food_satisfaction - 1.0) / span).clamp(0.0, 1.0);
-        MAX_GROWTH_PROBABILITY * progress
+impl FoodIntake {
+    /// Fold in one tick's food satisfaction.
+    pub fn observe(&mut self, food_satisfaction: f64, config: &DemographyConfig) {
+        let alpha = config.intake_alpha.clamp(0.0, 1.0);
+        self.ema = (1.0 - alpha) * self.ema + alpha * food_satisfaction;
+
+        if food_satisfaction < config.deficit_threshold {
+            self.deficit_ticks += 1;
+        } else {
+            self.deficit_ticks = 0;
+        }
+        if food_satisfaction > config.surplus_threshold {
+            self.surplus_ticks += 1;
+        } else {
+            self.surplus_ticks = 0;
+        }
     }
 }
 
@@ -56,17 +140,21 @@ pub enum MortalityOutcome {
     Grows,
 }
 
-/// Check mortality outcome for a pop given their food satisfaction.
-/// Uses random roll against death/growth probabilities.
-pub fn check_mortality<R: Rng>(rng: &mut R, food_satisfaction: f64) -> MortalityOutcome {
+/// Check mortality outcome for a pop given its intake history.
+/// Uses a single random roll against the death and growth hazards.
+pub fn check_mortality<R: Rng>(
+    rng: &mut R,
+    intake: &FoodIntake,
+    config: &DemographyConfig,
+) -> MortalityOutcome {
     let roll: f64 = rng.random();
 
-    let p_death = death_probability(food_satisfaction);
+    let p_death = config.death_hazard(intake);
     if roll < p_death {
         return MortalityOutcome::Dies;
     }
 
-    let p_growth = growth_probability(food_satisfaction);
+    let p_growth = config.growth_hazard(intake);
     if roll < p_death + p_growth {
         return MortalityOutcome::Grows;
     }
@@ -78,54 +166,70 @@ pub fn check_mortality<R: Rng>(rng: &mut R, food_satisfaction: f64) -> Mortality
 mod tests {
     use super::*;
 
+    /// Intake held at `ema` long enough for the full hazard to apply.
+    fn sustained(ema: f64) -> FoodIntake {
+        FoodIntake {
+            ema,
+            deficit_ticks: 100,
+            surplus_ticks: 100,
+        }
+    }
+
     #[test]
     fn test_death_probability_curve() {
+        let config = DemographyConfig::default();
+        let death = |ema| config.death_hazard(&sustained(ema));
+
         // At >90% satisfaction, no death
-        assert_eq!(death_probability(1.0), 0.0);
-        assert_eq!(death_probability(0.95), 0.0);
-        assert_eq!(death_probability(1.5), 0.0);
+        assert_eq!(death(1.0), 0.0);
+        assert_eq!(death(0.95), 0.0);
+        assert_eq!(death(1.5), 0.0);
 
         // At 99% satisfaction, still no death (new threshold)
-        let p99 = death_probability(0.99);
+        let p99 = death(0.99);
         assert_eq!(p99, 0.0, "p99 = {}", p99);
 
         // At 80% satisfaction, low but non-zero
-        let p80 = death_probability(0.80);
+        let p80 = death(0.80);
         assert!(p80 > 0.005 && p80 < 0.03, "p80 = {}", p80);
 
         // At 50% satisfaction, significant
-        let p50 = death_probability(0.50);
+        let p50 = death(0.50);
         assert!(p50 > 0.15 && p50 < 0.25, "p50 = {}", p50);
 
         // At 5% satisfaction, very high death risk
-        let p05 = death_probability(0.05);
+        let p05 = death(0.05);
         assert!(p05 > 0.85, "p05 = {}", p05);
 
         // At 0% satisfaction, capped at 99%
-        assert_eq!(death_probability(0.0), 0.99);
+        assert_eq!(death(0.0), 0.99);
     }
 
     #[test]
     fn test_growth_probability_curve() {
+        let config = DemographyConfig::default();
+        let growth = |ema| config.growth_hazard(&sustained(ema));
+        let death = |ema| config.death_hazard(&sustained(ema));
+
         // At 100% or below, no growth
-        assert_eq!(growth_probability(1.0), 0.0);
-        assert_eq!(growth_probability(0.5), 0.0);
+        assert_eq!(growth(1.0), 0.0);
+        assert_eq!(growth(0.5), 0.0);
 
         // At 110% satisfaction, small growth chance
-        let p110 = growth_probability(1.10);
+        let p110 = growth(1.10);
         assert!(p110 > 0.005 && p110 < 0.01, "p110 = {}", p110);
 
         // At 200% satisfaction, capped at the small max growth rate
-        let p200 = growth_probability(2.0);
+        let p200 = growth(2.0);
         assert!(
-            p200 > 0.015 && p200 <= MAX_GROWTH_PROBABILITY,
+            p200 > 0.015 && p200 <= config.max_growth_hazard,
             "p200 = {}",
             p200
         );
 
         // Growth is much smaller than death for same deviation
-        let death_at_50 = death_probability(0.50); // deficit = 0.5
-        let growth_at_125 = growth_probability(1.25); // at cap
+        let death_at_50 = death(0.50); // deficit = 0.5
+        let growth_at_125 = growth(1.25); // at cap
         assert!(
             growth_at_125 < death_at_50 * 0.2,
             "growth {} should be much less than death {}",
@@ -138,13 +242,14 @@ mod tests {
     fn test_check_mortality_distribution() {
         use rand::SeedableRng;
         let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+        let config = DemographyConfig::default();
 
         // At 50% satisfaction, should see substantial deaths
         let mut deaths = 0;
         let trials = 1000;
 
         for _ in 0..trials {
-            match check_mortality(&mut rng, 0.5) {
+            match check_mortality(&mut rng, &sustained(0.5), &config) {
                 MortalityOutcome::Dies => deaths += 1,
                 MortalityOutcome::Survives | MortalityOutcome::Grows => {}
             }
@@ -158,4 +263,23 @@ mod tests {
             death_rate
         );
     }
+
+    #[test]
+    fn one_bad_tick_is_not_a_famine() {
+        let config = DemographyConfig::default();
+        let mut intake = FoodIntake::default();
+
+        intake.observe(0.0, &config);
+        assert_eq!(intake.deficit_ticks, 1);
+        let after_one = config.death_hazard(&intake);
+
+        intake.observe(0.0, &config);
+        intake.observe(0.0, &config);
+        assert!(config.death_hazard(&intake) > after_one * 4.0);
+
+        // A fed tick resets the streak
+        intake.observe(1.0, &config);
+        assert_eq!(intake.deficit_ticks, 0);
+        assert_eq!(config.death_hazard(&intake), 0.0);
+    }
 }
@@ -17,6 +17,10 @@ pub struct RecipeAllocation {
     pub facility_id: FacilityKey,
     /// Recipe ID -> number of instances to run
     pub runs: HashMap<RecipeId, u32>,
+    /// Recipe ID -> workers assigned to its runs, by skill
+    pub crews: HashMap<RecipeId, HashMap<SkillId, u32>>,
+    /// Recipe ID -> runs holding each tool good
+    pub equipped: HashMap<RecipeId, HashMap<GoodId, u32>>,
 }
 
 impl RecipeAllocation {
@@ -24,12 +28,30 @@ impl RecipeAllocation {
         Self {
             facility_id,
             runs: HashMap::new(),
+            crews: HashMap::new(),
+            equipped: HashMap::new(),
         }
     }
 
     pub fn total_runs(&self) -> u32 {
         self.runs.values().sum()
     }
+
+    /// Tool stock held by this allocation's runs. Other facilities drawing
+    /// on the same stockpile this tick cannot use it.
+    pub fn tools_held(&self, recipes: &[Recipe]) -> HashMap<GoodId, Quantity> {
+        let mut held: HashMap<GoodId, Quantity> = HashMap::new();
+        for (recipe_id, equipped) in &self.equipped {
+            let Some(recipe) = recipes.iter().find(|r| r.id == *recipe_id) else {
+                continue;
+            };
+            for tool in &recipe.tools {
+                let runs = equipped.get(&tool.good).copied().unwrap_or(0);
+                *held.entry(tool.good).or_insert(0.0) += tool.qty * runs as f64;
+            }
+        }
+        held
+    }
 }
 
 /// Allocate recipes to a single facility using greedy priority-based algorithm.
@@ -39,8 +61,16 @@ impl RecipeAllocation {
 /// 2. Workers: facility.workers[skill] >= recipe.workers[skill] for all skills
 /// 3. Inputs: stockpile[good] >= recipe.inputs[good] for all inputs
 /// 4. Facility match: recipe.facility_types.contains(facility.facility_type)
+/// 5. Tools: stockpile holds every required tool for the instance
 ///
 /// Greedy fills by priority order (first recipe in list = highest priority).
+/// A recipe with a production function runs at most once.
+/// Tools are held, not consumed, but each held tool serves one instance per
+/// tick. Boosters go to as many of a recipe's runs as stock allows. When
+/// several facilities draw on one stockpile, pass it net of the tools the
+/// others already hold (see [`RecipeAllocation::tools_held`]).
+/// Workers left over afterwards join the crew of the first running recipe
+/// whose production function uses their skill.
 pub fn allocate_recipes(
     facility_key: FacilityKey,
     facility: &Facility,
@@ -91,13 +121,33 @@ pub fn allocate_recipes(
                 break;
             }
 
+            // Check required tools (constraint 5), net of this instance's inputs
+            let has_tools = recipe
+                .tools
+                .iter()
+                .filter(|tool| tool.unequipped_yield.is_none())
+                .all(|tool| {
+                    let consumed: Quantity = recipe
+                        .inputs
+                        .iter()
+                        .filter(|(good, _)| *good == tool.good)
+                        .map(|(_, qty)| qty)
+                        .sum();
+                    remaining_inputs.get(&tool.good).copied().unwrap_or(0.0) - consumed >= tool.qty
+                });
+            if !has_tools {
+                break;
+            }
+
             // All constraints satisfied - commit to running this instance
             remaining_capacity -= recipe.capacity_cost;
 
+            let crew = allocation.crews.entry(*recipe_id).or_default();
             for (skill, needed) in &recipe.workers {
                 if let Some(count) = remaining_workers.get_mut(skill) {
                     *count -= needed;
                 }
+                *crew.entry(*skill).or_insert(0) += needed;
             }
 
             for (good, needed) in &recipe.inputs {
@@ -106,11 +156,55 @@ pub fn allocate_recipes(
                 }
             }
 
+            let equipped = allocation.equipped.entry(*recipe_id).or_default();
+            for tool in recipe.tools.iter().filter(|t| t.unequipped_yield.is_none()) {
+                if let Some(qty) = remaining_inputs.get_mut(&tool.good) {
+                    *qty -= tool.qty;
+                }
+                *equipped.entry(tool.good).or_insert(0) += 1;
+            }
+
             instances += 1;
+
+            // A production function scales one run with the whole crew
+            if recipe.production_fn.is_some() {
+                break;
+            }
         }
 
         if instances > 0 {
             allocation.runs.insert(*recipe_id, instances);
+
+            let equipped = allocation.equipped.entry(*recipe_id).or_default();
+            for tool in recipe.tools.iter().filter(|t| t.unequipped_yield.is_some()) {
+                let available = remaining_inputs.get(&tool.good).copied().unwrap_or(0.0);
+                let held = if tool.qty > 0.0 {
+                    ((available / tool.qty).floor().max(0.0) as u32).min(instances)
+                } else {
+                    instances
+                };
+                if let Some(qty) = remaining_inputs.get_mut(&tool.good) {
+                    *qty -= tool.qty * held as f64;
+                }
+                *equipped.entry(tool.good).or_insert(0) += held;
+            }
+        }
+    }
+
+    for recipe_id in &facility.recipe_priorities {
+        let Some(recipe) = recipes.iter().find(|r| r.id == *recipe_id) else {
+            continue;
+        };
+        let (Some(production_fn), Some(crew)) =
+            (&recipe.production_fn, allocation.crews.get_mut(recipe_id))
+        else {
+            continue;
+        };
+        for skill in production_fn.relevant_skills() {
+            if let Some(count) = remaining_workers.get_mut(&skill).filter(|c| **c > 0) {
+                *crew.entry(skill).or_insert(0) += *count;
+                *count = 0;
+            }
         }
     }
 
@@ -127,6 +221,8 @@ pub struct ProductionResult {
     pub inputs_consumed: HashMap<GoodId, Quantity>,
     /// Outputs produced (good -> quantity)
     pub outputs_produced: HashMap<GoodId, Quantity>,
+    /// Tools worn away (good -> quantity)
+    pub tools_worn: HashMap<GoodId, Quantity>,
     /// Total wages paid
     pub wages_paid: f64,
 }
@@ -137,6 +233,7 @@ impl ProductionResult {
             facility_id,
             inputs_consumed: HashMap::new(),
             outputs_produced: HashMap::new(),
+            tools_worn: HashMap::new(),
             wages_paid: 0.0,
         }
     }
@@ -166,9 +263,25 @@ pub fn execute_production(
             *result.inputs_consumed.entry(*good).or_insert(0.0) += total_consumed;
         }
 
-        // Produce outputs (with quality multiplier)
+        // Wear tools held by this recipe's runs
+        let equipped = allocation
+            .equipped
+            .get(recipe_id)
+            .cloned()
+            .unwrap_or_default();
+        for tool in &recipe.tools {
+            let held = equipped.get(&tool.good).copied().unwrap_or(0);
+            let worn = stockpile.remove(tool.good, tool.qty * tool.wear * held as f64);
+            if worn > 0.0 {
+                *result.tools_worn.entry(tool.good).or_insert(0.0) += worn;
+            }
+        }
+
+        // Produce outputs (with quality multiplier and tool yield)
+        let crew = allocation.crews.get(recipe_id).cloned().unwrap_or_default();
+        let units = recipe.output_units(instances, &crew) * recipe.tool_yield(instances, &equipped);
         for (good, qty_per_instance) in &recipe.outputs {
-            let total_produced = qty_per_instance * instances as f64 * quality_multiplier;
+            let total_produced = qty_per_instance * units * quality_multiplier;
             stockpile.add(*good, total_produced);
             *result.outputs_produced.entry(*good).or_insert(0.0) += total_produced;
         }
@@ -352,4 +465,101 @@ mod tests {
         // 2 instances * 3 bread * 1.5 = 9 bread produced
         assert_eq!(result.outputs_produced.get(&BREAD), Some(&9.0));
     }
+
+    #[test]
+    fn test_production_fn_scales_with_crew() {
+        use crate::labor::ComplementaryProductionFn;
+
+        // One instance needs a laborer; extra laborers and a baker add output
+        let recipe = Recipe::new(RecipeId::new(1), "Crewed Bread", vec![FacilityType::Bakery])
+            .with_capacity_cost(1)
+            .with_worker(laborer(), 1)
+            .with_output(BREAD, 2.0)
+            .with_production_fn(ComplementaryProductionFn {
+                base_output: [(laborer(), 1.0), (baker(), 1.0)].into(),
+                complementarity_bonus: [((laborer(), baker()), 0.5)].into(),
+                max_optimal_capacity: [(laborer(), 2)].into(),
+                diminishing_rate: 0.5,
+            });
+        let mut facility = make_facility([(laborer(), 3), (baker(), 1)].into(), 1);
+        facility.recipe_priorities = vec![RecipeId::new(1)];
+        let recipes = vec![recipe];
+
+        let mut stockpile = Stockpile::new();
+        let allocation = allocate_recipes(fk(), &facility, &recipes, &stockpile);
+        assert_eq!(allocation.runs.get(&RecipeId::new(1)), Some(&1));
+        assert_eq!(
+            allocation.crews[&RecipeId::new(1)],
+            [(laborer(), 3), (baker(), 1)].into()
+        );
+
+        // Laborers 1 + 1 + 0.5, baker 1, pair bonus 0.5 = 4 units of 2 bread
+        let result = execute_production(&allocation, &recipes, &mut stockpile, 1.0);
+        assert_eq!(result.outputs_produced.get(&BREAD), Some(&8.0));
+    }
+
+    #[test]
+    fn test_production_fn_runs_once_per_facility() {
+        use crate::labor::ComplementaryProductionFn;
+
+        let recipe = Recipe::new(RecipeId::new(1), "Crewed Bread", vec![FacilityType::Bakery])
+            .with_worker(laborer(), 1)
+            .with_input(GRAIN, 1.0)
+            .with_output(BREAD, 1.0)
+            .with_production_fn(ComplementaryProductionFn {
+                base_output: [(laborer(), 1.0)].into(),
+                complementarity_bonus: HashMap::new(),
+                max_optimal_capacity: [(laborer(), 3)].into(),
+                diminishing_rate: 0.5,
+            });
+        let mut facility = make_facility([(laborer(), 3)].into(), 3);
+        facility.recipe_priorities = vec![RecipeId::new(1)];
+        let recipes = vec![recipe];
+
+        let mut stockpile = Stockpile::new();
+        stockpile.add(GRAIN, 10.0);
+        let allocation = allocate_recipes(fk(), &facility, &recipes, &stockpile);
+        assert_eq!(allocation.runs.get(&RecipeId::new(1)), Some(&1));
+        assert_eq!(allocation.crews[&RecipeId::new(1)], [(laborer(), 3)].into());
+
+        // Three laborers make 3 bread from a single run's grain
+        let result = execute_production(&allocation, &recipes, &mut stockpile, 1.0);
+        assert_eq!(result.inputs_consumed.get(&GRAIN), Some(&1.0));
+        assert_eq!(result.outputs_produced.get(&BREAD), Some(&3.0));
+    }
+
+    #[test]
+    fn test_tools_wear_and_boost_yield() {
+        const TOOLS: GoodId = 3;
+        let recipes = vec![
+            Recipe::new(RecipeId::new(1), "Tooled Bread", vec![FacilityType::Bakery])
+                .with_worker(laborer(), 1)
+                .with_tool(TOOLS, 1.0, 0.1)
+                .with_output(BREAD, 2.0),
+            Recipe::new(
+                RecipeId::new(2),
+                "Boosted Bread",
+                vec![FacilityType::Bakery],
+            )
+            .with_worker(baker(), 1)
+            .with_booster(TOOLS, 1.0, 0.5, 0.5)
+            .with_output(BREAD, 2.0),
+        ];
+        let mut facility = make_facility([(laborer(), 3), (baker(), 2)].into(), 10);
+        facility.recipe_priorities = vec![RecipeId::new(1), RecipeId::new(2)];
+
+        // Two tools: both go to the required recipe, leaving boosters bare
+        let mut stockpile = Stockpile::new();
+        stockpile.add(TOOLS, 2.0);
+        let allocation = allocate_recipes(fk(), &facility, &recipes, &stockpile);
+        assert_eq!(allocation.runs.get(&RecipeId::new(1)), Some(&2));
+        assert_eq!(allocation.runs.get(&RecipeId::new(2)), Some(&2));
+        assert_eq!(allocation.equipped[&RecipeId::new(2)].get(&TOOLS), Some(&0));
+
+        let result = execute_production(&allocation, &recipes, &mut stockpile, 1.0);
+        // 2 tooled runs * 2 bread + 2 bare runs * 2 bread * 0.5
+        assert_eq!(result.outputs_produced.get(&BREAD), Some(&6.0));
+        assert_eq!(result.tools_worn.get(&TOOLS), Some(&0.2));
+        assert_eq!(stockpile.get(TOOLS), 1.8);
+    }
 }
@@ -2,16 +2,18 @@
 
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
+
 use crate::geography::ResourceType;
 use crate::labor::SkillId;
-use crate::types::MerchantId;
+use crate::types::{GoodId, MerchantId, Quantity};
 
 use super::RecipeId;
 
 // === FACILITY TYPE ===
 
 /// What kind of facility this is
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum FacilityType {
     // Primary production (require natural resources)
     Farm,     // Land → grain
@@ -28,7 +30,7 @@ pub enum FacilityType {
 
 /// Immutable definition of a facility type.
 /// This is content/configuration, not game state.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FacilityDef {
     pub facility_type: FacilityType,
     pub name: String,
@@ -40,6 +42,13 @@ pub struct FacilityDef {
     pub construction_cost: f64,
     /// Fraction of construction cost recovered on demolition
     pub salvage_fraction: f64,
+    /// Ticks over which the construction cost is amortized
+    #[serde(default = "default_useful_life")]
+    pub useful_life: u64,
+}
+
+fn default_useful_life() -> u64 {
+    1_000
 }
 
 impl FacilityDef {
@@ -51,6 +60,7 @@ impl FacilityDef {
             base_capacity: 10,
             construction_cost: 100.0,
             salvage_fraction: 0.3,
+            useful_life: default_useful_life(),
         }
     }
 
@@ -74,6 +84,16 @@ impl FacilityDef {
         self
     }
 
+    pub fn with_useful_life(mut self, ticks: u64) -> Self {
+        self.useful_life = ticks;
+        self
+    }
+
+    /// Construction cost charged against each tick of the useful life.
+    pub fn amortization_per_tick(&self) -> f64 {
+        self.construction_cost / self.useful_life.max(1) as f64
+    }
+
     /// Is this a primary production facility (requires natural resource)?
     pub fn is_primary(&self) -> bool {
         self.required_resource.is_some()
@@ -81,7 +101,7 @@ impl FacilityDef {
 }
 
 /// Get the default definitions for all facility types.
-/// In the future this could be loaded from data files.
+/// Content packs can override these (see [`crate::content`]).
 pub fn get_facility_defs() -> Vec<FacilityDef> {
     vec![
         // Primary production
@@ -118,11 +138,45 @@ pub fn get_facility_def(facility_type: FacilityType) -> Option<FacilityDef> {
         .find(|def| def.facility_type == facility_type)
 }
 
+// === INCOME STATEMENT ===
+
+/// A facility's income over some period. Revenue and input costs are the
+/// owner's market fills attributed to the facility's outputs and inputs;
+/// depreciation is tool wear at local prices.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct IncomeStatement {
+    pub revenue: f64,
+    pub wages: f64,
+    pub input_costs: f64,
+    pub depreciation: f64,
+    pub amortization: f64,
+}
+
+impl IncomeStatement {
+    pub fn expenses(&self) -> f64 {
+        self.wages + self.input_costs + self.depreciation + self.amortization
+    }
+
+    pub fn profit(&self) -> f64 {
+        self.revenue - self.expenses()
+    }
+}
+
+impl std::ops::AddAssign for IncomeStatement {
+    fn add_assign(&mut self, other: Self) {
+        self.revenue += other.revenue;
+        self.wages += other.wages;
+        self.input_costs += other.input_costs;
+        self.depreciation += other.depreciation;
+        self.amortization += other.amortization;
+    }
+}
+
 // === FACILITY INSTANCE (GAME STATE) ===
 
 /// A production facility at a settlement.
 /// This is mutable game state.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Facility {
     pub facility_type: FacilityType,
     pub owner: MerchantId,
@@ -133,7 +187,9 @@ pub struct Facility {
     /// Index into settlement.resource_slots (for primary facilities)
     pub resource_slot_index: Option<usize>,
 
-    /// Currency available for paying wages (facility treasury)
+    /// Facility treasury. Wages and the inputs it uses are paid from it
+    /// first, with the owner covering any shortfall; sales of what it
+    /// produced are credited to it.
     pub currency: f64,
 
     /// Current employees by primary skill
@@ -141,6 +197,22 @@ pub struct Facility {
 
     /// Recipe priorities - first recipe has highest priority
     pub recipe_priorities: Vec<RecipeId>,
+
+    /// Income for the current tick, reset when the tick starts
+    #[serde(default)]
+    pub income: IncomeStatement,
+
+    /// Income since construction
+    #[serde(default)]
+    pub lifetime_income: IncomeStatement,
+
+    /// Goods produced this tick, reset when the tick starts
+    #[serde(skip)]
+    pub produced: HashMap<GoodId, Quantity>,
+
+    /// Inputs consumed this tick; tool wear is booked as depreciation
+    #[serde(skip)]
+    pub used: HashMap<GoodId, Quantity>,
 }
 
 impl Facility {
@@ -157,6 +229,10 @@ impl Facility {
             currency: 0.0,
             workers: HashMap::new(),
             recipe_priorities: Vec::new(),
+            income: IncomeStatement::default(),
+            lifetime_income: IncomeStatement::default(),
+            produced: HashMap::new(),
+            used: HashMap::new(),
         }
     }
 
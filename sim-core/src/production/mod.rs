@@ -3,5 +3,7 @@ pub mod facility;
 pub mod recipe;
 
 pub use execute::{ProductionResult, RecipeAllocation, allocate_recipes, execute_production};
-pub use facility::{Facility, FacilityDef, FacilityType, get_facility_def, get_facility_defs};
-pub use recipe::{Recipe, RecipeId};
+pub use facility::{
+    Facility, FacilityDef, FacilityType, IncomeStatement, get_facility_def, get_facility_defs,
+};
+pub use recipe::{Recipe, RecipeId, Tool};
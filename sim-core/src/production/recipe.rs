@@ -1,15 +1,19 @@
 // Recipe definitions for production chains
 
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
 
-use crate::labor::SkillId;
+use serde::{Deserialize, Serialize};
+
+use crate::labor::{ProductionFn, SkillId};
 use crate::types::{GoodId, Quantity};
 
 use super::FacilityType;
 
 // === RECIPE ID ===
 
-#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct RecipeId(pub u32);
 
 impl RecipeId {
@@ -18,6 +22,21 @@ impl RecipeId {
     }
 }
 
+// === TOOLS ===
+
+/// A good a recipe holds rather than consumes. Each run wears away `wear`
+/// of the held quantity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tool {
+    pub good: GoodId,
+    /// Quantity held per instance
+    pub qty: Quantity,
+    /// Fraction of `qty` worn away per run
+    pub wear: f64,
+    /// Yield of a run without the tool; `None` if the tool is required
+    pub unequipped_yield: Option<f64>,
+}
+
 // === RECIPE ===
 
 /// A recipe defines how a facility converts inputs to outputs.
@@ -27,8 +46,10 @@ impl RecipeId {
 /// - Specific workers (by skill)
 /// - Input goods
 ///
-/// And produces output goods.
-#[derive(Debug, Clone)]
+/// And produces output goods. With a production function the recipe runs
+/// once per facility, taking the whole crew, and outputs are per unit of
+/// the function's output instead of per instance.
+#[derive(Clone)]
 pub struct Recipe {
     pub id: RecipeId,
     pub name: String,
@@ -40,8 +61,29 @@ pub struct Recipe {
     pub workers: HashMap<SkillId, u32>,
     /// Input goods consumed per instance
     pub inputs: Vec<(GoodId, Quantity)>,
+    /// Durable goods held per instance, required or yield-boosting
+    pub tools: Vec<Tool>,
     /// Output goods produced per instance
     pub outputs: Vec<(GoodId, Quantity)>,
+    /// Scales output with the crew's actual skill mix when set
+    pub production_fn: Option<Arc<dyn ProductionFn>>,
+}
+
+// Production functions need not be Debug, so only their presence is shown
+impl fmt::Debug for Recipe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Recipe")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("facility_types", &self.facility_types)
+            .field("capacity_cost", &self.capacity_cost)
+            .field("workers", &self.workers)
+            .field("inputs", &self.inputs)
+            .field("tools", &self.tools)
+            .field("outputs", &self.outputs)
+            .field("production_fn", &self.production_fn.is_some())
+            .finish()
+    }
 }
 
 impl Recipe {
@@ -53,7 +95,9 @@ impl Recipe {
             capacity_cost: 1,
             workers: HashMap::new(),
             inputs: Vec::new(),
+            tools: Vec::new(),
             outputs: Vec::new(),
+            production_fn: None,
         }
     }
 
@@ -72,11 +116,75 @@ impl Recipe {
         self
     }
 
+    /// Require `qty` of a durable good per instance, worn by `wear` per run.
+    pub fn with_tool(mut self, good: GoodId, qty: Quantity, wear: f64) -> Self {
+        self.tools.push(Tool {
+            good,
+            qty,
+            wear,
+            unequipped_yield: None,
+        });
+        self
+    }
+
+    /// Boost yield with `qty` of a durable good per instance. Runs without it
+    /// produce `unequipped_yield` of full output.
+    pub fn with_booster(
+        mut self,
+        good: GoodId,
+        qty: Quantity,
+        wear: f64,
+        unequipped_yield: f64,
+    ) -> Self {
+        self.tools.push(Tool {
+            good,
+            qty,
+            wear,
+            unequipped_yield: Some(unequipped_yield),
+        });
+        self
+    }
+
     pub fn with_output(mut self, good: GoodId, qty: Quantity) -> Self {
         self.outputs.push((good, qty));
         self
     }
 
+    pub fn with_production_fn(mut self, production_fn: impl ProductionFn + 'static) -> Self {
+        self.production_fn = Some(Arc::new(production_fn));
+        self
+    }
+
+    /// Output units for `instances` runs with `crew` workers: the
+    /// production function's output if set (such recipes run once),
+    /// otherwise one per instance.
+    pub fn output_units(&self, instances: u32, crew: &HashMap<SkillId, u32>) -> f64 {
+        match &self.production_fn {
+            Some(production_fn) => production_fn.compute(crew),
+            None => instances as f64,
+        }
+    }
+
+    /// Mean yield over `instances` runs, given how many runs held each tool.
+    pub fn tool_yield(&self, instances: u32, equipped: &HashMap<GoodId, u32>) -> f64 {
+        if instances == 0 {
+            return 1.0;
+        }
+        let runs = instances as f64;
+        self.tools
+            .iter()
+            .filter_map(|tool| {
+                let unequipped_yield = tool.unequipped_yield?;
+                let held = equipped
+                    .get(&tool.good)
+                    .copied()
+                    .unwrap_or(0)
+                    .min(instances) as f64;
+                Some((held + (runs - held) * unequipped_yield) / runs)
+            })
+            .product()
+    }
+
     /// Check if this recipe can run on a given facility type
     pub fn can_run_at(&self, facility_type: FacilityType) -> bool {
         self.facility_types.contains(&facility_type)
@@ -129,6 +237,18 @@ mod tests {
         assert_eq!(recipe.outputs.len(), 1);
     }
 
+    #[test]
+    fn test_tool_yield() {
+        const TOOLS: GoodId = 3;
+        let recipe = Recipe::new(RecipeId::new(1), "Farming", vec![FacilityType::Farm])
+            .with_booster(TOOLS, 1.0, 0.1, 0.5)
+            .with_output(GRAIN, 2.0);
+
+        assert_eq!(recipe.tool_yield(4, &HashMap::new()), 0.5);
+        assert_eq!(recipe.tool_yield(4, &[(TOOLS, 2)].into()), 0.75);
+        assert_eq!(recipe.tool_yield(4, &[(TOOLS, 9)].into()), 1.0);
+    }
+
     #[test]
     fn test_has_workers() {
         let recipe = Recipe::new(RecipeId::new(1), "Test", vec![FacilityType::Bakery])
@@ -0,0 +1,535 @@
+//! Declarative scenario files.
+//!
+//! A scenario is a JSON document describing the initial world: the content
+//! pack to use, settlements with their pops and resources, routes,
+//! merchants with stockpiles and facilities, the outside market and the
+//! subsistence reservation. Goods, skills and recipes are referenced by the
+//! names defined in the content pack.
+//!
+//! [`Scenario::build`] turns a scenario into a ready-to-run [`World`] plus
+//! the [`ContentRegistry`] whose definitions `World::run_tick` needs.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::content::{ContentError, ContentPack, ContentRegistry};
+use crate::external::{AnchoredGoodConfig, ExternalMarketConfig, SettlementFriction};
+use crate::geography::{
+    ResourceQuality, ResourceSlot, ResourceType, Route, RouteKind, SettlementRole,
+};
+use crate::labor::{SkillId, SkillLearningConfig, SubsistenceReservationConfig};
+use crate::migration::MigrationConfig;
+use crate::mortality::DemographyConfig;
+use crate::production::FacilityType;
+use crate::transport::{SEA_ROUTE_RISK, VesselKind};
+use crate::types::{GoodId, SettlementId};
+use crate::world::World;
+
+// === FILE FORMAT ===
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// Content pack, either inline or as a path relative to the scenario file.
+    pub content: ContentSource,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub ticks: Option<u64>,
+    #[serde(default)]
+    pub mortality_grace_ticks: u64,
+    /// Death and growth hazard parameters; defaults when absent.
+    #[serde(default)]
+    pub demography: DemographyConfig,
+    #[serde(default)]
+    pub subsistence: Option<SubsistenceSpec>,
+    #[serde(default)]
+    pub external_market: Option<ExternalMarketSpec>,
+    /// Pop migration settings; pops stay put when absent.
+    #[serde(default)]
+    pub migration: Option<MigrationConfig>,
+    /// Skill learning and decay settings; pop skills are fixed when absent.
+    #[serde(default)]
+    pub skill_learning: Option<SkillLearningConfig>,
+    /// Journal every transfer in a double-entry ledger.
+    #[serde(default)]
+    pub ledger: bool,
+    pub settlements: Vec<SettlementSpec>,
+    #[serde(default)]
+    pub routes: Vec<RouteSpec>,
+    #[serde(default)]
+    pub merchants: Vec<MerchantSpec>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ContentSource {
+    Path(PathBuf),
+    Inline(ContentPack),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SubsistenceSpec {
+    pub good: String,
+    pub q_max: f64,
+    pub carrying_capacity: usize,
+    pub default_price: f64,
+    pub risk_premium: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExternalMarketSpec {
+    /// Good name → outside market parameters.
+    pub anchors: HashMap<String, AnchoredGoodConfig>,
+    /// Attach outside ladders only at port and river-port settlements.
+    #[serde(default)]
+    pub port_only: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SettlementSpec {
+    pub name: String,
+    #[serde(default)]
+    pub position: (f64, f64),
+    #[serde(default)]
+    pub resources: Vec<ResourceSpec>,
+    /// Port / river port / inland; derived from coastal resources when absent.
+    #[serde(default)]
+    pub role: Option<SettlementRole>,
+    /// Outside-market access for this settlement (disabled when absent).
+    #[serde(default)]
+    pub friction: Option<SettlementFriction>,
+    /// Good name → initial price EMA.
+    #[serde(default)]
+    pub prices: HashMap<String, f64>,
+    /// Skill name → initial wage EMA.
+    #[serde(default)]
+    pub wages: HashMap<String, f64>,
+    #[serde(default)]
+    pub pops: Vec<PopGroupSpec>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ResourceSpec {
+    pub resource_type: ResourceType,
+    pub quality: ResourceQuality,
+    #[serde(default = "default_count")]
+    pub count: usize,
+}
+
+/// `count` identical pops.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PopGroupSpec {
+    #[serde(default = "default_count")]
+    pub count: usize,
+    #[serde(default)]
+    pub currency: Option<f64>,
+    #[serde(default)]
+    pub income_ema: Option<f64>,
+    #[serde(default)]
+    pub min_wage: Option<f64>,
+    #[serde(default)]
+    pub skills: Vec<String>,
+    /// Good name → quantity.
+    #[serde(default)]
+    pub stocks: HashMap<String, f64>,
+    /// Good name → initial desired consumption EMA.
+    #[serde(default)]
+    pub desired_consumption: HashMap<String, f64>,
+}
+
+fn default_count() -> usize {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RouteSpec {
+    pub from: String,
+    pub to: String,
+    pub distance: u32,
+    #[serde(default)]
+    pub kind: RouteKind,
+    #[serde(default)]
+    pub transport_cost: Option<f64>,
+    /// Per-trip incident chance; sea routes default to [`SEA_ROUTE_RISK`].
+    #[serde(default)]
+    pub risk: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MerchantSpec {
+    #[serde(default)]
+    pub currency: Option<f64>,
+    /// Settlement name → (good name → quantity).
+    #[serde(default)]
+    pub stockpiles: HashMap<String, HashMap<String, f64>>,
+    #[serde(default)]
+    pub facilities: Vec<FacilitySpec>,
+    #[serde(default)]
+    pub vessels: Vec<VesselSpec>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FacilitySpec {
+    pub facility_type: FacilityType,
+    pub settlement: String,
+    #[serde(default = "default_count")]
+    pub count: usize,
+    #[serde(default)]
+    pub capacity: Option<u32>,
+    /// Recipe names, highest priority first.
+    #[serde(default)]
+    pub recipes: Vec<String>,
+    /// Skill name → initial wage bid.
+    #[serde(default)]
+    pub wage_bids: HashMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VesselSpec {
+    pub kind: VesselKind,
+    /// Settlement where the vessel starts docked.
+    pub settlement: String,
+    #[serde(default = "default_count")]
+    pub count: usize,
+    #[serde(default)]
+    pub capacity: Option<f64>,
+    #[serde(default)]
+    pub speed: Option<u32>,
+    #[serde(default)]
+    pub upkeep: Option<f64>,
+}
+
+// === ERRORS ===
+
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Content(ContentError),
+    Unknown { kind: &'static str, name: String },
+    Invalid(String),
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScenarioError::Io(e) => write!(f, "scenario io error: {e}"),
+            ScenarioError::Json(e) => write!(f, "scenario parse error: {e}"),
+            ScenarioError::Content(e) => write!(f, "scenario content error: {e}"),
+            ScenarioError::Unknown { kind, name } => {
+                write!(f, "scenario references unknown {kind} {name:?}")
+            }
+            ScenarioError::Invalid(msg) => write!(f, "invalid scenario: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {}
+
+impl From<std::io::Error> for ScenarioError {
+    fn from(e: std::io::Error) -> Self {
+        ScenarioError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ScenarioError {
+    fn from(e: serde_json::Error) -> Self {
+        ScenarioError::Json(e)
+    }
+}
+
+impl From<ContentError> for ScenarioError {
+    fn from(e: ContentError) -> Self {
+        ScenarioError::Content(e)
+    }
+}
+
+// === BUILD ===
+
+/// A world built from a scenario, with the content it runs on.
+pub struct ScenarioWorld {
+    pub world: World,
+    pub content: ContentRegistry,
+}
+
+impl ScenarioWorld {
+    pub fn run_tick(&mut self) {
+        self.world.run_tick(
+            &self.content.good_profiles,
+            &self.content.needs,
+            &self.content.recipes,
+        );
+    }
+}
+
+impl Scenario {
+    pub fn from_json(json: &str) -> Result<Self, ScenarioError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Load a scenario file. A relative content path is resolved against the
+    /// scenario file's directory.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScenarioError> {
+        let path = path.as_ref();
+        let mut scenario = Self::from_json(&std::fs::read_to_string(path)?)?;
+        if let ContentSource::Path(content_path) = &mut scenario.content
+            && content_path.is_relative()
+            && let Some(dir) = path.parent()
+        {
+            *content_path = dir.join(&*content_path);
+        }
+        Ok(scenario)
+    }
+
+    pub fn load_content(&self) -> Result<ContentRegistry, ScenarioError> {
+        Ok(match &self.content {
+            ContentSource::Path(path) => ContentRegistry::load(path)?,
+            ContentSource::Inline(pack) => ContentRegistry::from_pack(pack.clone())?,
+        })
+    }
+
+    /// Build the initial world. `seed` overrides the scenario's own seed.
+    pub fn build(&self, seed: Option<u64>) -> Result<ScenarioWorld, ScenarioError> {
+        let content = self.load_content()?;
+        let good = |name: &str| -> Result<GoodId, ScenarioError> {
+            content.good_id(name).ok_or_else(|| ScenarioError::Unknown {
+                kind: "good",
+                name: name.to_string(),
+            })
+        };
+        let skill = |name: &str| -> Result<SkillId, ScenarioError> {
+            content
+                .skill_id(name)
+                .ok_or_else(|| ScenarioError::Unknown {
+                    kind: "skill",
+                    name: name.to_string(),
+                })
+        };
+
+        let mut world = match seed.or(self.seed) {
+            Some(seed) => World::with_seed(seed),
+            None => World::new(),
+        };
+        world.mortality_grace_ticks = self.mortality_grace_ticks;
+        world.demography = self.demography.clone();
+        world.migration = self.migration.clone();
+        world.skill_learning = self.skill_learning.clone();
+        world.set_ledger_enabled(self.ledger);
+        world.set_facility_defs(content.facility_defs.clone());
+        world.set_skill_defs(content.skills.clone());
+
+        if let Some(spec) = &self.subsistence {
+            world.set_subsistence_reservation(SubsistenceReservationConfig::new(
+                good(&spec.good)?,
+                spec.q_max,
+                spec.carrying_capacity,
+                spec.default_price,
+                spec.risk_premium,
+            ));
+        }
+
+        let mut settlement_ids: HashMap<&str, SettlementId> = HashMap::new();
+        let mut frictions = HashMap::new();
+        for spec in &self.settlements {
+            let id = world.add_settlement(spec.name.clone(), spec.position);
+            if settlement_ids.insert(&spec.name, id).is_some() {
+                return Err(ScenarioError::Invalid(format!(
+                    "duplicate settlement {:?}",
+                    spec.name
+                )));
+            }
+            if let Some(friction) = &spec.friction {
+                frictions.insert(id, friction.clone());
+            }
+
+            let state = world
+                .settlements
+                .get_mut(&id)
+                .expect("settlement just added");
+            for resource in &spec.resources {
+                for _ in 0..resource.count {
+                    state
+                        .info
+                        .resource_slots
+                        .push(ResourceSlot::new(resource.resource_type, resource.quality));
+                }
+            }
+            state.info.explicit_role = spec.role;
+            for (name, &price) in &spec.prices {
+                state.price_ema.insert(good(name)?, price);
+            }
+            for (name, &wage) in &spec.wages {
+                state.wage_ema.insert(skill(name)?, wage);
+            }
+
+            for group in &spec.pops {
+                let skills = group
+                    .skills
+                    .iter()
+                    .map(|name| skill(name))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let stocks = group
+                    .stocks
+                    .iter()
+                    .map(|(name, &qty)| Ok((good(name)?, qty)))
+                    .collect::<Result<Vec<_>, ScenarioError>>()?;
+                let desired = group
+                    .desired_consumption
+                    .iter()
+                    .map(|(name, &qty)| Ok((good(name)?, qty)))
+                    .collect::<Result<Vec<_>, ScenarioError>>()?;
+                for _ in 0..group.count {
+                    let handle = world.add_pop(id).expect("settlement just added");
+                    let pop = world.pop_mut(handle).expect("pop just added");
+                    if let Some(currency) = group.currency {
+                        pop.currency = currency;
+                    }
+                    if let Some(income) = group.income_ema {
+                        pop.income_ema = income;
+                    }
+                    if let Some(min_wage) = group.min_wage {
+                        pop.min_wage = min_wage;
+                    }
+                    pop.skills.extend(skills.iter().copied());
+                    pop.stocks.extend(stocks.iter().copied());
+                    pop.desired_consumption_ema.extend(desired.iter().copied());
+                }
+            }
+        }
+        let settlement = |name: &str| -> Result<SettlementId, ScenarioError> {
+            settlement_ids
+                .get(name)
+                .copied()
+                .ok_or_else(|| ScenarioError::Unknown {
+                    kind: "settlement",
+                    name: name.to_string(),
+                })
+        };
+
+        if let Some(spec) = &self.external_market {
+            let mut config = ExternalMarketConfig {
+                frictions,
+                port_only: spec.port_only,
+                ..Default::default()
+            };
+            for (name, anchor) in &spec.anchors {
+                config.anchors.insert(good(name)?, anchor.clone());
+            }
+            world.set_external_market(config);
+        } else if !frictions.is_empty() {
+            return Err(ScenarioError::Invalid(
+                "settlement frictions require an external_market section".to_string(),
+            ));
+        }
+
+        for spec in &self.routes {
+            let mut route = Route::new(
+                settlement(&spec.from)?,
+                settlement(&spec.to)?,
+                spec.distance,
+            )
+            .with_kind(spec.kind);
+            if let Some(cost) = spec.transport_cost {
+                route = route.with_cost(cost);
+            }
+            let default_risk = match spec.kind {
+                RouteKind::Land => 0.0,
+                RouteKind::Sea => SEA_ROUTE_RISK,
+            };
+            route = route.with_risk(spec.risk.unwrap_or(default_risk));
+            world.routes.push(route);
+        }
+
+        for spec in &self.merchants {
+            let merchant = world.add_merchant();
+            {
+                let m = world
+                    .get_merchant_mut(merchant)
+                    .expect("merchant just added");
+                if let Some(currency) = spec.currency {
+                    m.currency = currency;
+                }
+                for (settlement_name, goods) in &spec.stockpiles {
+                    let sid = settlement(settlement_name)?;
+                    for (name, &qty) in goods {
+                        m.stockpile_at(sid).add(good(name)?, qty);
+                    }
+                }
+            }
+
+            for facility in &spec.facilities {
+                let sid = settlement(&facility.settlement)?;
+                let recipes = facility
+                    .recipes
+                    .iter()
+                    .map(|name| {
+                        content
+                            .recipe(name)
+                            .map(|r| r.id)
+                            .ok_or_else(|| ScenarioError::Unknown {
+                                kind: "recipe",
+                                name: name.clone(),
+                            })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let bids = facility
+                    .wage_bids
+                    .iter()
+                    .map(|(name, &bid)| Ok((skill(name)?, bid)))
+                    .collect::<Result<Vec<_>, ScenarioError>>()?;
+
+                for _ in 0..facility.count {
+                    let handle = world
+                        .add_facility(facility.facility_type, sid, merchant)
+                        .expect("settlement and merchant exist");
+                    let f = world.facility_mut(handle).expect("facility just added");
+                    if let Some(capacity) = facility.capacity {
+                        f.capacity = capacity;
+                    }
+                    f.recipe_priorities = recipes.clone();
+                    if let Some(bid_state) = world
+                        .settlements
+                        .get_mut(&sid)
+                        .and_then(|s| s.facility_bid_states.get_mut(handle.key))
+                    {
+                        bid_state.bids.extend(bids.iter().copied());
+                    }
+                }
+            }
+
+            for vessel in &spec.vessels {
+                let sid = settlement(&vessel.settlement)?;
+                for _ in 0..vessel.count {
+                    let id = world
+                        .add_vessel(vessel.kind, sid, merchant)
+                        .expect("settlement and merchant exist");
+                    let v = world.vessel_mut(id).expect("vessel just added");
+                    if let Some(capacity) = vessel.capacity {
+                        v.capacity = capacity;
+                    }
+                    if let Some(speed) = vessel.speed {
+                        v.speed = speed;
+                    }
+                    if let Some(upkeep) = vessel.upkeep {
+                        v.upkeep = upkeep;
+                    }
+                }
+            }
+        }
+
+        Ok(ScenarioWorld { world, content })
+    }
+}
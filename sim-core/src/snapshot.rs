@@ -0,0 +1,310 @@
+//! Versioned save/load of full `World` state.
+//!
+//! A snapshot captures everything a resumed run needs to continue
+//! bit-identically: RNG state, id counters, slotmap keys (including the
+//! free-slot order that decides which key the next insert receives) and the
+//! stock-flow history.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use slotmap::{Key, SlotMap};
+
+use crate::agents::Pop;
+use crate::production::{Facility, FacilityType};
+use crate::types::MerchantId;
+use crate::world::World;
+
+/// Format version written into every snapshot. Only snapshots of exactly
+/// this version load.
+///
+/// Fields added with `#[serde(default)]` leave older snapshots loadable with
+/// the same meaning and do not need a bump. Anything else (a renamed,
+/// removed or retyped field, or a changed enum payload) does.
+///
+/// - 1: initial format.
+/// - 2: ledger pop accounts are keyed by pop handle instead of pop key.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 2;
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    UnsupportedVersion { found: u32, expected: u32 },
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Io(e) => write!(f, "snapshot io error: {e}"),
+            SnapshotError::Json(e) => write!(f, "snapshot json error: {e}"),
+            SnapshotError::UnsupportedVersion { found, expected } => write!(
+                f,
+                "unsupported snapshot version {found} (expected {expected})"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(e: std::io::Error) -> Self {
+        SnapshotError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SnapshotError {
+    fn from(e: serde_json::Error) -> Self {
+        SnapshotError::Json(e)
+    }
+}
+
+#[derive(Serialize)]
+struct SnapshotOut<'a> {
+    version: u32,
+    world: &'a World,
+}
+
+#[derive(Deserialize)]
+struct SnapshotHeader {
+    version: u32,
+}
+
+#[derive(Deserialize)]
+struct SnapshotIn {
+    world: World,
+}
+
+impl World {
+    /// Serialize the full world state into a versioned JSON document.
+    pub fn to_snapshot_json(&self) -> Result<String, SnapshotError> {
+        Ok(serde_json::to_string(&SnapshotOut {
+            version: SNAPSHOT_FORMAT_VERSION,
+            world: self,
+        })?)
+    }
+
+    /// Restore a world from a document produced by [`World::to_snapshot_json`].
+    pub fn from_snapshot_json(json: &str) -> Result<World, SnapshotError> {
+        let header: SnapshotHeader = serde_json::from_str(json)?;
+        if header.version != SNAPSHOT_FORMAT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion {
+                found: header.version,
+                expected: SNAPSHOT_FORMAT_VERSION,
+            });
+        }
+        let snapshot: SnapshotIn = serde_json::from_str(json)?;
+        Ok(snapshot.world)
+    }
+
+    pub fn save_snapshot(&self, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+        std::fs::write(path, self.to_snapshot_json()?)?;
+        Ok(())
+    }
+
+    pub fn load_snapshot(path: impl AsRef<Path>) -> Result<World, SnapshotError> {
+        let json = std::fs::read_to_string(path)?;
+        World::from_snapshot_json(&json)
+    }
+}
+
+// === SERDE HELPERS ===
+
+/// Serialize maps with non-string keys (e.g. tuple keys) as a list of
+/// `(key, value)` pairs, since JSON objects only allow string keys.
+pub(crate) mod entries {
+    use super::*;
+
+    pub fn serialize<K, V, S>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        K: Serialize,
+        V: Serialize,
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(map.iter())
+    }
+
+    pub fn deserialize<'de, K, V, D>(deserializer: D) -> Result<HashMap<K, V>, D::Error>
+    where
+        K: Deserialize<'de> + Eq + Hash,
+        V: Deserialize<'de>,
+        D: serde::Deserializer<'de>,
+    {
+        let pairs: Vec<(K, V)> = Vec::deserialize(deserializer)?;
+        Ok(pairs.into_iter().collect())
+    }
+}
+
+/// Value used to fill slots while rebuilding a slotmap's key layout.
+pub(crate) trait SlotPlaceholder {
+    fn placeholder() -> Self;
+}
+
+impl SlotPlaceholder for Pop {
+    fn placeholder() -> Self {
+        Pop::new()
+    }
+}
+
+impl SlotPlaceholder for Facility {
+    fn placeholder() -> Self {
+        Facility::new(FacilityType::Farm, MerchantId(0))
+    }
+}
+
+/// Serialize a `SlotMap` so that both key versions and the free-slot order
+/// survive a round trip.
+///
+/// slotmap's own serde impl rebuilds the free list in index order, so the
+/// next insert after a reload could receive a different key than it would
+/// have in the uninterrupted run. Keys feed deterministic iteration order, so
+/// that divergence would break bit-identical resumes.
+pub(crate) mod slot_map {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct SlotMapRepr<V> {
+        /// Occupied slots as `(key, value)` in index order.
+        entries: Vec<(u64, V)>,
+        /// Vacant slots as `(index, version)`, head of the free list first.
+        free: Vec<(u32, u32)>,
+    }
+
+    /// Split a key's FFI form into `(index, version)`.
+    fn split(ffi: u64) -> (u32, u32) {
+        (ffi as u32, (ffi >> 32) as u32)
+    }
+
+    pub fn serialize<K, V, S>(map: &SlotMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        K: Key,
+        V: Serialize + Clone + SlotPlaceholder,
+        S: serde::Serializer,
+    {
+        // Probe a clone: inserts consume the free list head-first, and a
+        // fresh slot (version 1) means the free list is exhausted.
+        let mut probe = map.clone();
+        let mut free = Vec::new();
+        loop {
+            let (idx, version) = split(probe.insert(V::placeholder()).data().as_ffi());
+            if version == 1 {
+                break;
+            }
+            free.push((idx, version - 1));
+        }
+
+        let entries: Vec<(u64, &V)> = map.iter().map(|(k, v)| (k.data().as_ffi(), v)).collect();
+        SlotMapRepr { entries, free }.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, K, V, D>(deserializer: D) -> Result<SlotMap<K, V>, D::Error>
+    where
+        K: Key,
+        V: Deserialize<'de> + SlotPlaceholder,
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        let repr: SlotMapRepr<V> = SlotMapRepr::deserialize(deserializer)?;
+
+        // Target version per slot index (index 0 is slotmap's sentinel).
+        let mut targets: Vec<Option<u32>> = vec![None];
+        let mut set_target = |idx: u32, version: u32| -> Result<(), D::Error> {
+            let idx = idx as usize;
+            if idx == 0 {
+                return Err(D::Error::custom("slot index 0 is reserved"));
+            }
+            if targets.len() <= idx {
+                targets.resize(idx + 1, None);
+            }
+            if targets[idx].replace(version).is_some() {
+                return Err(D::Error::custom("duplicate slot index"));
+            }
+            Ok(())
+        };
+        for (key, _) in &repr.entries {
+            let (idx, version) = split(*key);
+            if version % 2 == 0 {
+                return Err(D::Error::custom("occupied slot with even version"));
+            }
+            set_target(idx, version)?;
+        }
+        for &(idx, version) in &repr.free {
+            if version == 0 || version % 2 == 1 {
+                return Err(D::Error::custom("vacant slot with invalid version"));
+            }
+            set_target(idx, version)?;
+        }
+        if targets[1..].iter().any(Option::is_none) {
+            return Err(D::Error::custom("slot layout has gaps"));
+        }
+
+        // Fill every slot, then cycle each one (remove + reinsert reuses the
+        // same slot while the free list is empty) until it reaches the
+        // occupied version at or just below its target.
+        let mut map: SlotMap<K, V> = SlotMap::with_key();
+        let mut keys: Vec<K> = vec![K::null()];
+        for _ in 1..targets.len() {
+            keys.push(map.insert(V::placeholder()));
+        }
+        for (idx, target) in targets.iter().enumerate().skip(1) {
+            let occupied_target = target.unwrap() - (1 - target.unwrap() % 2);
+            while split(keys[idx].data().as_ffi()).1 < occupied_target {
+                let value = map.remove(keys[idx]).unwrap();
+                keys[idx] = map.insert(value);
+            }
+        }
+
+        // Vacate free slots tail-first so the recorded head ends up at the
+        // front of the free list.
+        for &(idx, _) in repr.free.iter().rev() {
+            map.remove(keys[idx as usize]);
+        }
+        for (key, value) in repr.entries {
+            let (idx, _) = split(key);
+            map[keys[idx as usize]] = value;
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PopKey;
+
+    #[derive(Serialize, Deserialize)]
+    struct Wrapper {
+        #[serde(with = "slot_map")]
+        pops: SlotMap<PopKey, Pop>,
+    }
+
+    #[test]
+    fn slot_map_round_trip_preserves_versions_and_free_order() {
+        let mut pops: SlotMap<PopKey, Pop> = SlotMap::with_key();
+        let keys: Vec<PopKey> = (0..6)
+            .map(|i| pops.insert(Pop::new().with_currency(i as f64)))
+            .collect();
+        // Churn one slot so versions differ, then free slots out of index order.
+        pops.remove(keys[1]);
+        let reused = pops.insert(Pop::new().with_currency(10.0));
+        pops.remove(keys[4]);
+        pops.remove(keys[2]);
+
+        let json = serde_json::to_string(&Wrapper { pops: pops.clone() }).unwrap();
+        let mut restored = serde_json::from_str::<Wrapper>(&json).unwrap().pops;
+
+        assert_eq!(restored.len(), pops.len());
+        assert_eq!(restored[reused].currency, 10.0);
+        assert!(restored.get(keys[1]).is_none());
+        for _ in 0..3 {
+            let expected = pops.insert(Pop::new());
+            let actual = restored.insert(Pop::new());
+            assert_eq!(actual, expected);
+        }
+    }
+}
@@ -10,7 +10,9 @@ use crate::labor::{
 };
 use crate::market::{self, Order, Side};
 use crate::needs::Need;
-use crate::types::{AgentId, GoodId, GoodProfile, PopKey, Price, SettlementId, pop_key_u64};
+use crate::types::{
+    AgentId, GoodId, GoodProfile, MerchantId, PopKey, Price, SettlementId, pop_key_u64,
+};
 
 // === CONSTANTS ===
 
@@ -230,6 +232,7 @@ pub fn run_settlement_tick(
     subsistence_config: Option<&SubsistenceReservationConfig>,
     depth_multipliers: &HashMap<GoodId, f64>,
     subsistence_queue: Option<&[PopKey]>,
+    merchant_orders: Option<&HashMap<MerchantId, Vec<Order>>>,
 ) -> market::MultiMarketResult {
     pops.sort_by_key(|(k, _)| pop_key_u64(*k));
     merchants.sort_by_key(|m| m.id.0);
@@ -277,6 +280,7 @@ pub fn run_settlement_tick(
     for (pop_key, pop) in pops.iter_mut() {
         // Reset need satisfaction for this tick (it's per-tick, not cumulative)
         pop.need_satisfaction.clear();
+        pop.consumed.clear();
 
         let result = consumption::compute_consumption(
             &pop.stocks,
@@ -292,6 +296,7 @@ pub fn run_settlement_tick(
         for (good, qty) in &result.actual {
             let stock_before = pop.stocks.get(good).copied().unwrap_or(0.0);
             *pop.stocks.entry(*good).or_insert(0.0) -= qty;
+            *pop.consumed.entry(*good).or_insert(0.0) += qty;
             let stock_after = pop.stocks.get(good).copied().unwrap_or(0.0);
 
             #[cfg(feature = "instrument")]
@@ -353,10 +358,15 @@ pub fn run_settlement_tick(
     }
 
     for merchant in merchants.iter() {
-        let mut orders = merchant.generate_orders(settlement, price_ema);
+        // Controller decisions when provided, otherwise the built-in supply curve.
+        let mut orders = match merchant_orders {
+            Some(by_merchant) => by_merchant.get(&merchant.id).cloned().unwrap_or_default(),
+            None => merchant.generate_orders(settlement, price_ema),
+        };
         for o in &mut orders {
             o.id = next_order_id;
             next_order_id += 1;
+            o.agent_id = AgentId::Merchant(merchant.id);
 
             #[cfg(feature = "instrument")]
             {
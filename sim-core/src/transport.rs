@@ -0,0 +1,343 @@
+// Vessels that carry merchant goods between settlements
+
+use std::collections::HashMap;
+use std::fmt;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::agents::{MarketSnapshot, Stockpile};
+use crate::geography::RouteKind;
+use crate::types::{GoodId, MerchantId, Quantity, SettlementId, VesselId};
+
+// === VESSEL KIND ===
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum VesselKind {
+    /// Sails sea lanes; large hold, fast, expensive to keep
+    Ship,
+    /// Travels overland; small, slow, cheap
+    Caravan,
+}
+
+impl VesselKind {
+    /// The kind of route this vessel can travel.
+    pub fn route_kind(&self) -> RouteKind {
+        match self {
+            Self::Ship => RouteKind::Sea,
+            Self::Caravan => RouteKind::Land,
+        }
+    }
+}
+
+// === VESSEL ===
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum VesselLocation {
+    Docked(SettlementId),
+    InTransit {
+        from: SettlementId,
+        to: SettlementId,
+        ticks_remaining: u32,
+    },
+}
+
+/// A ship or caravan owned by a merchant.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Vessel {
+    pub id: VesselId,
+    pub owner: MerchantId,
+    pub kind: VesselKind,
+    /// Maximum total cargo quantity
+    pub capacity: Quantity,
+    /// Route distance covered per tick
+    pub speed: u32,
+    /// Currency charged to the owner every tick
+    pub upkeep: f64,
+    pub location: VesselLocation,
+    pub cargo: Stockpile,
+    /// Market snapshot of the last port of departure, delivered to the
+    /// owner on arrival.
+    #[serde(default)]
+    pub news: Option<MarketSnapshot>,
+}
+
+impl Vessel {
+    pub fn new(id: VesselId, kind: VesselKind, owner: MerchantId, at: SettlementId) -> Self {
+        let (capacity, speed, upkeep) = match kind {
+            VesselKind::Ship => (100.0, 2, 2.0),
+            VesselKind::Caravan => (20.0, 1, 0.5),
+        };
+        Self {
+            id,
+            owner,
+            kind,
+            capacity,
+            speed,
+            upkeep,
+            location: VesselLocation::Docked(at),
+            cargo: Stockpile::new(),
+            news: None,
+        }
+    }
+
+    pub fn with_capacity(mut self, capacity: Quantity) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    pub fn with_speed(mut self, speed: u32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn with_upkeep(mut self, upkeep: f64) -> Self {
+        self.upkeep = upkeep;
+        self
+    }
+
+    pub fn docked_at(&self) -> Option<SettlementId> {
+        match self.location {
+            VesselLocation::Docked(at) => Some(at),
+            VesselLocation::InTransit { .. } => None,
+        }
+    }
+
+    pub fn free_capacity(&self) -> Quantity {
+        (self.capacity - self.cargo.total()).max(0.0)
+    }
+
+    /// Ticks needed to cover `distance` at this vessel's speed (at least one).
+    pub fn transit_ticks(&self, distance: u32) -> u32 {
+        distance.div_ceil(self.speed.max(1)).max(1)
+    }
+}
+
+// === INCIDENTS ===
+
+/// Default per-trip incident chance for sea lanes (see `World::add_sea_route`).
+pub const SEA_ROUTE_RISK: f64 = 0.12;
+
+/// Something that went wrong on the way.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RouteIncident {
+    /// The whole cargo is lost (wreck, bandits)
+    TotalLoss,
+    /// A fraction of every good in the hold is destroyed
+    Spoilage { fraction: f64 },
+    /// Arrival is pushed back
+    Delay { ticks: u32 },
+}
+
+impl RouteIncident {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::TotalLoss => "total_loss",
+            Self::Spoilage { .. } => "spoilage",
+            Self::Delay { .. } => "delay",
+        }
+    }
+}
+
+/// Per-tick incident probability such that a trip of `trip_ticks` ticks has
+/// overall incident probability `trip_risk`.
+pub fn per_tick_risk(trip_risk: f64, trip_ticks: u32) -> f64 {
+    let trip_risk = trip_risk.clamp(0.0, 1.0);
+    if trip_risk >= 1.0 {
+        return 1.0;
+    }
+    1.0 - (1.0 - trip_risk).powf(1.0 / f64::from(trip_ticks.max(1)))
+}
+
+/// Roll one tick of travel. Sea incidents are more often wrecks and worse
+/// spoilage; overland trips mostly suffer delays.
+pub fn roll_incident(
+    rng: &mut impl Rng,
+    route_kind: RouteKind,
+    tick_risk: f64,
+) -> Option<RouteIncident> {
+    if tick_risk <= 0.0 || rng.random::<f64>() >= tick_risk {
+        return None;
+    }
+    // (total loss, spoilage) cumulative weights; remainder is delay
+    let (loss, spoil, max_spoilage) = match route_kind {
+        RouteKind::Sea => (0.35, 0.80, 0.6),
+        RouteKind::Land => (0.10, 0.45, 0.3),
+    };
+    let roll = rng.random::<f64>();
+    Some(if roll < loss {
+        RouteIncident::TotalLoss
+    } else if roll < spoil {
+        RouteIncident::Spoilage {
+            fraction: rng.random_range(0.1..max_spoilage),
+        }
+    } else {
+        RouteIncident::Delay {
+            ticks: rng.random_range(1..=3),
+        }
+    })
+}
+
+// === TOTALS ===
+
+/// Cumulative transport flows. Freight, upkeep and migrant fares leave the
+/// economy, so stock-flow accounting treats them like an outside payment;
+/// `cargo_lost` is goods destroyed by route incidents, of which
+/// `cargo_spoiled` spoiled rather than went down with a wreck. Deliveries are
+/// keyed by destination settlement.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransportTotals {
+    pub upkeep_paid: f64,
+    pub freight_paid: f64,
+    #[serde(default)]
+    pub fares_paid: f64,
+    #[serde(default)]
+    pub cargo_lost: HashMap<GoodId, Quantity>,
+    #[serde(default)]
+    pub cargo_spoiled: HashMap<GoodId, Quantity>,
+    #[serde(default, with = "crate::snapshot::entries")]
+    pub delivered: HashMap<(SettlementId, GoodId), Quantity>,
+    /// The part of `delivered` that sailed from a settlement with outside
+    /// market access.
+    #[serde(default, with = "crate::snapshot::entries")]
+    pub delivered_from_ports: HashMap<(SettlementId, GoodId), Quantity>,
+}
+
+impl TransportTotals {
+    pub fn total_paid(&self) -> f64 {
+        self.upkeep_paid + self.freight_paid + self.fares_paid
+    }
+
+    pub fn record_delivery(
+        &mut self,
+        settlement: SettlementId,
+        good: GoodId,
+        qty: Quantity,
+        from_port: bool,
+    ) {
+        *self.delivered.entry((settlement, good)).or_insert(0.0) += qty;
+        if from_port {
+            *self
+                .delivered_from_ports
+                .entry((settlement, good))
+                .or_insert(0.0) += qty;
+        }
+    }
+}
+
+// === PORT DEPENDENCE ===
+
+/// How much a settlement without outside access relies on goods shipped in
+/// from ports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortDependence {
+    pub settlement: SettlementId,
+    /// Closest settlement with outside access by route distance.
+    pub nearest_port: Option<(SettlementId, u32)>,
+    /// Goods delivered here by vessel, from any origin.
+    pub delivered: HashMap<GoodId, Quantity>,
+    /// Goods delivered here by vessels that sailed from a port.
+    pub from_ports: HashMap<GoodId, Quantity>,
+}
+
+impl PortDependence {
+    /// Share of all vessel deliveries that came straight from a port.
+    pub fn port_share(&self) -> f64 {
+        let total: Quantity = self.delivered.values().sum();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        self.from_ports.values().sum::<Quantity>() / total
+    }
+
+    /// Share of deliveries of one good that came straight from a port.
+    pub fn good_port_share(&self, good: GoodId) -> f64 {
+        let total = self.delivered.get(&good).copied().unwrap_or(0.0);
+        if total <= 0.0 {
+            return 0.0;
+        }
+        self.from_ports.get(&good).copied().unwrap_or(0.0) / total
+    }
+}
+
+// === ERRORS ===
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransportError {
+    UnknownVessel(VesselId),
+    UnknownMerchant(MerchantId),
+    UnknownSettlement(SettlementId),
+    NotDocked(VesselId),
+    NoRoute {
+        from: SettlementId,
+        to: SettlementId,
+        kind: RouteKind,
+    },
+    InsufficientFunds {
+        required: f64,
+        available: f64,
+    },
+}
+
+impl fmt::Display for TransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransportError::UnknownVessel(id) => write!(f, "unknown vessel {}", id.0),
+            TransportError::UnknownMerchant(id) => write!(f, "unknown merchant {}", id.0),
+            TransportError::UnknownSettlement(id) => write!(f, "unknown settlement {}", id.0),
+            TransportError::NotDocked(id) => write!(f, "vessel {} is not docked", id.0),
+            TransportError::NoRoute { from, to, kind } => {
+                write!(f, "no {kind:?} route from {} to {}", from.0, to.0)
+            }
+            TransportError::InsufficientFunds {
+                required,
+                available,
+            } => write!(f, "freight costs {required} but owner has {available}"),
+        }
+    }
+}
+
+impl std::error::Error for TransportError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transit_ticks_round_up_and_never_zero() {
+        let ship = Vessel::new(
+            VesselId::new(0),
+            VesselKind::Ship,
+            MerchantId::new(0),
+            SettlementId::new(0),
+        )
+        .with_speed(2);
+        assert_eq!(ship.transit_ticks(5), 3);
+        assert_eq!(ship.transit_ticks(4), 2);
+        assert_eq!(ship.transit_ticks(0), 1);
+    }
+
+    #[test]
+    fn per_tick_risk_compounds_to_trip_risk() {
+        let p = per_tick_risk(0.2, 4);
+        let trip = 1.0 - (1.0 - p).powi(4);
+        assert!((trip - 0.2).abs() < 1e-12);
+        assert_eq!(per_tick_risk(0.0, 4), 0.0);
+        assert_eq!(per_tick_risk(1.0, 4), 1.0);
+    }
+
+    #[test]
+    fn free_capacity_tracks_cargo() {
+        let mut caravan = Vessel::new(
+            VesselId::new(0),
+            VesselKind::Caravan,
+            MerchantId::new(0),
+            SettlementId::new(0),
+        )
+        .with_capacity(10.0);
+        caravan.cargo.add(1, 4.0);
+        assert_eq!(caravan.free_capacity(), 6.0);
+        caravan.cargo.add(2, 8.0);
+        assert_eq!(caravan.free_capacity(), 0.0);
+    }
+}
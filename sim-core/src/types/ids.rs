@@ -1,4 +1,5 @@
 // Core ID types and type aliases
+use serde::{Deserialize, Serialize};
 use slotmap::KeyData;
 use slotmap::{Key, new_key_type};
 
@@ -10,7 +11,7 @@ pub type Quantity = f64;
 
 // === NEWTYPE IDS ===
 
-#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct SettlementId(pub u32);
 
 impl SettlementId {
@@ -19,7 +20,7 @@ impl SettlementId {
     }
 }
 
-#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct MerchantId(pub u32);
 
 impl MerchantId {
@@ -28,6 +29,24 @@ impl MerchantId {
     }
 }
 
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct VesselId(pub u32);
+
+impl VesselId {
+    pub fn new(id: u32) -> Self {
+        Self(id)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct LoanId(pub u32);
+
+impl LoanId {
+    pub fn new(id: u32) -> Self {
+        Self(id)
+    }
+}
+
 // Canonical runtime identities for settlement-local arenas.
 new_key_type! { pub struct PopKey; }
 new_key_type! { pub struct FacilityKey; }
@@ -49,13 +68,13 @@ impl AgentId {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct PopHandle {
     pub settlement: SettlementId,
     pub key: PopKey,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct FacilityHandle {
     pub settlement: SettlementId,
     pub key: FacilityKey,
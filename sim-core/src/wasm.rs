@@ -0,0 +1,497 @@
+//! JavaScript/WASM facade over [`World`].
+//!
+//! `WasmWorld` owns a world plus the good/need/recipe definitions that
+//! `run_tick` needs, exposes scenario-building calls, and returns typed read
+//! models. Input and output types derive `Tsify`, so the generated bindings
+//! carry TypeScript interfaces for them.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tsify_next::Tsify;
+use wasm_bindgen::prelude::*;
+
+use crate::agents::Pop;
+use crate::content::ContentRegistry;
+use crate::labor::SkillId;
+use crate::market::Side;
+use crate::needs::{Need, UtilityCurve};
+use crate::production::{FacilityType, Recipe, RecipeId};
+use crate::types::{
+    AgentId, GoodId, GoodProfile, MerchantId, NeedContribution, SettlementId, pop_key_u64,
+};
+use crate::world::World;
+
+// === INPUT DEFINITIONS ===
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct NeedContributionDef {
+    pub need_id: String,
+    pub efficiency: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct GoodDef {
+    pub good: GoodId,
+    pub contributions: Vec<NeedContributionDef>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+#[serde(tag = "kind")]
+pub enum UtilityCurveDef {
+    Subsistence { requirement: f64, steepness: f64 },
+    LogDiminishing { scale: f64 },
+    LuxuryThreshold { threshold: f64, scale: f64 },
+    Positional { reference: f64, sensitivity: f64 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct NeedDef {
+    pub id: String,
+    pub utility_curve: UtilityCurveDef,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct GoodQuantity {
+    pub good: GoodId,
+    pub quantity: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WorkerRequirement {
+    pub skill: u32,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct RecipeDef {
+    pub id: u32,
+    pub name: String,
+    /// Facility type names, e.g. `"Farm"` or `"Sawmill"`.
+    pub facility_types: Vec<String>,
+    pub capacity_cost: u32,
+    pub workers: Vec<WorkerRequirement>,
+    pub inputs: Vec<GoodQuantity>,
+    pub outputs: Vec<GoodQuantity>,
+}
+
+/// Template applied to every pop created by [`WasmWorld::add_pops`].
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct PopDef {
+    pub currency: f64,
+    pub skills: Vec<u32>,
+    pub min_wage: f64,
+    pub stocks: Vec<GoodQuantity>,
+}
+
+// === READ MODELS ===
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct GoodPrice {
+    pub good: GoodId,
+    pub price: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct SkillWage {
+    pub skill: u32,
+    pub wage: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct SettlementView {
+    pub id: u32,
+    pub name: String,
+    pub position: (f64, f64),
+    pub pop_count: usize,
+    pub employed_count: usize,
+    pub facility_count: usize,
+    pub prices: Vec<GoodPrice>,
+    pub wages: Vec<SkillWage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct StockpileView {
+    pub settlement: u32,
+    pub goods: Vec<GoodQuantity>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct MerchantView {
+    pub id: u32,
+    pub currency: f64,
+    pub facility_count: usize,
+    pub stockpiles: Vec<StockpileView>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentKind {
+    Pop,
+    Merchant,
+    Outside,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+#[serde(rename_all = "lowercase")]
+pub enum FillSide {
+    Buy,
+    Sell,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct FillView {
+    pub settlement: u32,
+    pub agent_kind: AgentKind,
+    /// Pop key, merchant id or outside-agent id, depending on `agent_kind`.
+    pub agent_id: u64,
+    pub good: GoodId,
+    pub side: FillSide,
+    pub quantity: f64,
+    pub price: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct WorldView {
+    pub tick: u64,
+    pub settlements: Vec<SettlementView>,
+    pub merchants: Vec<MerchantView>,
+    /// Market fills from the most recent tick, across all settlements.
+    pub fills: Vec<FillView>,
+}
+
+// === WORLD WRAPPER ===
+
+#[wasm_bindgen]
+pub struct WasmWorld {
+    world: World,
+    good_profiles: Vec<GoodProfile>,
+    needs: HashMap<String, Need>,
+    recipes: Vec<Recipe>,
+}
+
+#[wasm_bindgen]
+impl WasmWorld {
+    #[wasm_bindgen(constructor)]
+    pub fn new(seed: u32) -> WasmWorld {
+        #[cfg(target_arch = "wasm32")]
+        console_error_panic_hook::set_once();
+
+        WasmWorld {
+            world: World::with_seed(u64::from(seed)),
+            good_profiles: Vec::new(),
+            needs: HashMap::new(),
+            recipes: Vec::new(),
+        }
+    }
+
+    /// A `bigint` on the JavaScript side, so long runs don't wrap.
+    #[wasm_bindgen(getter)]
+    pub fn tick(&self) -> u64 {
+        self.world.tick
+    }
+
+    pub fn add_good(&mut self, def: GoodDef) {
+        self.good_profiles.retain(|p| p.good != def.good);
+        self.good_profiles.push(GoodProfile {
+            good: def.good,
+            contributions: def
+                .contributions
+                .into_iter()
+                .map(|c| NeedContribution {
+                    need_id: c.need_id,
+                    efficiency: c.efficiency,
+                })
+                .collect(),
+        });
+    }
+
+    pub fn add_need(&mut self, def: NeedDef) {
+        let utility_curve = match def.utility_curve {
+            UtilityCurveDef::Subsistence {
+                requirement,
+                steepness,
+            } => UtilityCurve::Subsistence {
+                requirement,
+                steepness,
+            },
+            UtilityCurveDef::LogDiminishing { scale } => UtilityCurve::LogDiminishing { scale },
+            UtilityCurveDef::LuxuryThreshold { threshold, scale } => {
+                UtilityCurve::LuxuryThreshold { threshold, scale }
+            }
+            UtilityCurveDef::Positional {
+                reference,
+                sensitivity,
+            } => UtilityCurve::Positional {
+                reference,
+                sensitivity,
+            },
+        };
+        self.needs.insert(
+            def.id.clone(),
+            Need {
+                id: def.id,
+                utility_curve,
+            },
+        );
+    }
+
+    pub fn add_recipe(&mut self, def: RecipeDef) -> Result<(), JsError> {
+        let facility_types = def
+            .facility_types
+            .iter()
+            .map(|name| parse_facility_type(name))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut recipe = Recipe::new(RecipeId::new(def.id), def.name, facility_types)
+            .with_capacity_cost(def.capacity_cost);
+        for w in def.workers {
+            recipe = recipe.with_worker(SkillId(w.skill), w.count);
+        }
+        for i in def.inputs {
+            recipe = recipe.with_input(i.good, i.quantity);
+        }
+        for o in def.outputs {
+            recipe = recipe.with_output(o.good, o.quantity);
+        }
+        self.recipes.retain(|r| r.id != recipe.id);
+        self.recipes.push(recipe);
+        Ok(())
+    }
+
+    /// Replace goods, needs, recipes and facility definitions with a JSON
+    /// content pack (see [`crate::content`]).
+    pub fn load_content(&mut self, json: &str) -> Result<(), JsError> {
+        let registry =
+            ContentRegistry::from_json(json).map_err(|e| JsError::new(&e.to_string()))?;
+        self.good_profiles = registry.good_profiles;
+        self.needs = registry.needs;
+        self.recipes = registry.recipes;
+        self.world.set_facility_defs(registry.facility_defs);
+        self.world.set_skill_defs(registry.skills);
+        Ok(())
+    }
+
+    pub fn add_settlement(&mut self, name: &str, x: f64, y: f64) -> u32 {
+        self.world.add_settlement(name, (x, y)).0
+    }
+
+    pub fn add_route(&mut self, from: u32, to: u32, distance: u32) {
+        self.world
+            .add_route(SettlementId(from), SettlementId(to), distance);
+    }
+
+    pub fn add_merchant(&mut self, currency: f64) -> u32 {
+        let id = self.world.add_merchant();
+        if let Some(merchant) = self.world.get_merchant_mut(id) {
+            merchant.currency = currency;
+        }
+        id.0
+    }
+
+    pub fn add_pops(
+        &mut self,
+        settlement: u32,
+        count: u32,
+        template: PopDef,
+    ) -> Result<(), JsError> {
+        let mut pop = Pop::new().with_currency(template.currency);
+        pop.min_wage = template.min_wage;
+        pop.skills = template.skills.iter().map(|&s| SkillId(s)).collect();
+        for stock in &template.stocks {
+            pop.stocks.insert(stock.good, stock.quantity);
+        }
+        for _ in 0..count {
+            let handle = self
+                .world
+                .add_pop(SettlementId(settlement))
+                .ok_or_else(|| JsError::new(&format!("unknown settlement {settlement}")))?;
+            *self.world.pop_mut(handle).expect("pop just added") = pop.clone();
+        }
+        Ok(())
+    }
+
+    pub fn add_facility(
+        &mut self,
+        facility_type: &str,
+        settlement: u32,
+        owner: u32,
+        capacity: u32,
+        recipe_priorities: Vec<u32>,
+    ) -> Result<(), JsError> {
+        let facility_type = parse_facility_type(facility_type)?;
+        let handle = self
+            .world
+            .add_facility(facility_type, SettlementId(settlement), MerchantId(owner))
+            .ok_or_else(|| {
+                JsError::new(&format!(
+                    "cannot add facility at settlement {settlement} for merchant {owner}"
+                ))
+            })?;
+        let facility = self
+            .world
+            .facility_mut(handle)
+            .expect("facility just added");
+        facility.capacity = capacity;
+        facility.recipe_priorities = recipe_priorities.into_iter().map(RecipeId::new).collect();
+        Ok(())
+    }
+
+    pub fn run_tick(&mut self) {
+        self.world
+            .run_tick(&self.good_profiles, &self.needs, &self.recipes);
+    }
+
+    pub fn run_ticks(&mut self, count: u32) {
+        for _ in 0..count {
+            self.run_tick();
+        }
+    }
+
+    pub fn view(&self) -> WorldView {
+        let settlement_ids =
+            crate::determinism::sorted_settlement_ids(self.world.settlements.keys().copied());
+
+        let mut settlements = Vec::with_capacity(settlement_ids.len());
+        let mut fills = Vec::new();
+        for id in settlement_ids {
+            let state = &self.world.settlements[&id];
+
+            let mut prices: Vec<GoodPrice> = state
+                .price_ema
+                .iter()
+                .map(|(&good, &price)| GoodPrice { good, price })
+                .collect();
+            prices.sort_by_key(|p| p.good);
+            let mut wages: Vec<SkillWage> = state
+                .wage_ema
+                .iter()
+                .map(|(skill, &wage)| SkillWage {
+                    skill: skill.0,
+                    wage,
+                })
+                .collect();
+            wages.sort_by_key(|w| w.skill);
+
+            settlements.push(SettlementView {
+                id: id.0,
+                name: state.info.name.clone(),
+                position: state.info.position,
+                pop_count: state.pops.len(),
+                employed_count: state
+                    .pops
+                    .values()
+                    .filter(|p| p.employed_at.is_some())
+                    .count(),
+                facility_count: state.facilities.len(),
+                prices,
+                wages,
+            });
+
+            fills.extend(state.last_fills.iter().map(|fill| {
+                let (agent_kind, agent_id) = match fill.agent_id {
+                    AgentId::Pop(key) => (AgentKind::Pop, pop_key_u64(key)),
+                    AgentId::Merchant(m) => (AgentKind::Merchant, u64::from(m.0)),
+                    AgentId::Outside(raw) => (AgentKind::Outside, raw),
+                };
+                FillView {
+                    settlement: id.0,
+                    agent_kind,
+                    agent_id,
+                    good: fill.good,
+                    side: match fill.side {
+                        Side::Buy => FillSide::Buy,
+                        Side::Sell => FillSide::Sell,
+                    },
+                    quantity: fill.quantity,
+                    price: fill.price,
+                }
+            }));
+        }
+
+        let merchants =
+            crate::determinism::sorted_merchant_ids(self.world.merchants.keys().copied())
+                .into_iter()
+                .map(|id| {
+                    let merchant = &self.world.merchants[&id];
+                    let mut stockpiles: Vec<StockpileView> = merchant
+                        .stockpiles
+                        .iter()
+                        .map(|(sid, stockpile)| {
+                            let mut goods: Vec<GoodQuantity> = stockpile
+                                .goods
+                                .iter()
+                                .map(|(&good, &quantity)| GoodQuantity { good, quantity })
+                                .collect();
+                            goods.sort_by_key(|g| g.good);
+                            StockpileView {
+                                settlement: sid.0,
+                                goods,
+                            }
+                        })
+                        .collect();
+                    stockpiles.sort_by_key(|s| s.settlement);
+                    MerchantView {
+                        id: id.0,
+                        currency: merchant.currency,
+                        facility_count: merchant.owned_facilities.len(),
+                        stockpiles,
+                    }
+                })
+                .collect();
+
+        WorldView {
+            tick: self.world.tick,
+            settlements,
+            merchants,
+            fills,
+        }
+    }
+
+    /// Full-state snapshot (see [`World::to_snapshot_json`]).
+    pub fn save_snapshot(&self) -> Result<String, JsError> {
+        self.world
+            .to_snapshot_json()
+            .map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    /// Replace the world with a snapshot; definitions are kept.
+    pub fn load_snapshot(&mut self, json: &str) -> Result<(), JsError> {
+        self.world = World::from_snapshot_json(json).map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(())
+    }
+}
+
+impl WasmWorld {
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+}
+
+fn parse_facility_type(name: &str) -> Result<FacilityType, JsError> {
+    match name {
+        "Farm" => Ok(FacilityType::Farm),
+        "Fishery" => Ok(FacilityType::Fishery),
+        "Sawmill" => Ok(FacilityType::Sawmill),
+        "IronMine" => Ok(FacilityType::IronMine),
+        "Bakery" => Ok(FacilityType::Bakery),
+        "Smithy" => Ok(FacilityType::Smithy),
+        other => Err(JsError::new(&format!("unknown facility type {other:?}"))),
+    }
+}
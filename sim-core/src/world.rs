@@ -1,36 +1,65 @@
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
-use rand::{SeedableRng, rngs::StdRng};
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
 use slotmap::{SecondaryMap, SlotMap};
 
-use crate::accounting::{TickStockFlow, capture_world_flow_snapshot, decompose_tick_flow};
-use crate::agents::{MerchantAgent, Pop, Stockpile};
+use crate::accounting::{
+    SettlementFlows, TickStockFlow, capture_world_flow_snapshot, decompose_tick_flow,
+};
+use crate::agents::{
+    Courier, MarketSnapshot, MerchantAction, MerchantAgent, MerchantController,
+    MerchantObservation, Pop, SettlementObservation, Stockpile, SupplyCurveController,
+};
+use crate::credit::{CreditError, CreditTotals, Loan};
 use crate::external::{ExternalMarketConfig, OutsideFlowTotals};
-use crate::geography::{Route, Settlement};
+use crate::geography::{Route, RouteGraph, RouteKind, Settlement};
 use crate::labor::{
     Assignment, FacilityBidState, LaborBid, LaborMarketResult, SkillDef, SkillId,
-    SubsistenceReservationConfig, build_subsistence_reservation_ladder, clear_labor_markets,
-    generate_pop_asks_with_min_wage, update_wage_emas,
+    SkillLearningConfig, SubsistenceReservationConfig, build_subsistence_reservation_ladder,
+    clear_labor_markets, generate_pop_asks_with_min_wage, update_wage_emas,
+};
+use crate::ledger::{Account, Ledger, Phase};
+use crate::market::{Fill, Order};
+use crate::migration::{Migrant, MigrationConfig};
+use crate::mortality::{DemographyConfig, MortalityOutcome, check_mortality};
+use crate::production::{
+    Facility, FacilityDef, FacilityType, Recipe, allocate_recipes, execute_production,
+    get_facility_defs,
 };
-use crate::mortality::{MortalityOutcome, check_mortality};
-use crate::production::{Facility, FacilityType, Recipe, allocate_recipes, execute_production};
 use crate::tick::run_settlement_tick;
+use crate::transport::{SEA_ROUTE_RISK, TransportTotals, Vessel};
 use crate::types::{
-    FacilityHandle, FacilityKey, GoodId, GoodProfile, MerchantId, PopHandle, PopKey, Price,
-    SettlementId, facility_key_u64, pop_key_u64,
+    FacilityHandle, FacilityKey, GoodId, GoodProfile, LoanId, MerchantId, PopHandle, PopKey, Price,
+    Quantity, SettlementId, VesselId, facility_key_u64, pop_key_u64,
 };
 
+mod balance_sheet;
+mod construction;
+mod credit_phase;
+mod income_phase;
+mod knowledge_phase;
 mod labor_phase;
 mod market_phase;
+mod merchant_phase;
+mod migration_phase;
 mod mortality_phase;
 mod production_phase;
+mod skill_phase;
+mod transport_phase;
 
-#[derive(Debug, Clone)]
+pub use construction::{ConstructionTotals, FacilityError};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SettlementState {
     pub id: SettlementId,
     pub info: Settlement,
 
+    #[serde(with = "crate::snapshot::slot_map")]
     pub pops: SlotMap<PopKey, Pop>,
+    #[serde(with = "crate::snapshot::slot_map")]
     pub facilities: SlotMap<FacilityKey, Facility>,
 
     pub price_ema: HashMap<GoodId, Price>,
@@ -40,6 +69,20 @@ pub struct SettlementState {
     pub depth_multipliers: HashMap<GoodId, f64>,
 
     pub owner_facility_counts: HashMap<MerchantId, u32>,
+
+    /// Market fills from the most recent tick (read model only; not saved in
+    /// snapshots).
+    #[serde(skip)]
+    pub last_fills: Vec<Fill>,
+    /// Quantity bought per good in the most recent market clearing. Unlike
+    /// `last_fills` this is saved, so merchant knowledge resumes identically.
+    #[serde(default)]
+    pub market_depth: HashMap<GoodId, Quantity>,
+
+    /// Goods flows tallied so far this tick (read model only; not saved in
+    /// snapshots).
+    #[serde(skip)]
+    pub flows: SettlementFlows,
 }
 
 impl SettlementState {
@@ -55,9 +98,35 @@ impl SettlementState {
             subsistence_queue: Vec::new(),
             depth_multipliers: HashMap::new(),
             owner_facility_counts: HashMap::new(),
+            last_fills: Vec::new(),
+            market_depth: HashMap::new(),
+            flows: SettlementFlows::default(),
         }
     }
 
+    /// Take a pop out of the settlement, dropping it from the subsistence
+    /// queue and from its facility's worker count.
+    fn remove_pop(&mut self, pop_key: PopKey) -> Option<Pop> {
+        let pop = self.pops.remove(pop_key)?;
+
+        self.subsistence_queue.retain(|k| *k != pop_key);
+
+        if let Some(facility_key) = pop.employed_at {
+            debug_assert!(
+                pop.employed_skill.is_some(),
+                "pop has employed_at but no employed_skill"
+            );
+            if let Some(skill) = pop.employed_skill
+                && let Some(facility) = self.facilities.get_mut(facility_key)
+                && let Some(count) = facility.workers.get_mut(&skill)
+            {
+                *count = count.saturating_sub(1);
+            }
+        }
+
+        Some(pop)
+    }
+
     fn update_subsistence_queue(&mut self) {
         let in_pops: HashSet<PopKey> = self
             .pops
@@ -98,25 +167,76 @@ struct LaborReservationResult {
     clipped_owners: HashSet<MerchantId>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct World {
     pub tick: u64,
     pub settlements: HashMap<SettlementId, SettlementState>,
     pub routes: Vec<Route>,
 
     pub merchants: HashMap<MerchantId, MerchantAgent>,
+    #[serde(default)]
+    pub vessels: HashMap<VesselId, Vessel>,
+    /// Market reports in flight to merchants.
+    #[serde(default)]
+    pub couriers: Vec<Courier>,
+    /// Loans between merchants that are not yet repaid.
+    #[serde(default)]
+    pub loans: HashMap<LoanId, Loan>,
 
     pub external_market: Option<ExternalMarketConfig>,
     pub subsistence_reservation: Option<SubsistenceReservationConfig>,
     pub mortality_grace_ticks: u64,
+    #[serde(default)]
+    pub demography: DemographyConfig,
+    /// Skill learning is off unless configured; pop skills stay fixed.
+    #[serde(default)]
+    pub skill_learning: Option<SkillLearningConfig>,
+    /// Migration is off unless configured.
+    #[serde(default)]
+    pub migration: Option<MigrationConfig>,
+    /// Pops travelling between settlements.
+    #[serde(default)]
+    pub migrants: Vec<Migrant>,
+    /// Cumulative pops departed per (origin, destination).
+    #[serde(default, with = "crate::snapshot::entries")]
+    pub migration_flows: HashMap<(SettlementId, SettlementId), u64>,
 
     pub outside_flow_totals: OutsideFlowTotals,
     pub stock_flow_history: Vec<TickStockFlow>,
+    #[serde(default)]
+    pub transport_totals: TransportTotals,
+    #[serde(default)]
+    pub credit_totals: CreditTotals,
+    #[serde(default)]
+    pub construction_totals: ConstructionTotals,
+    /// Journal of every transfer; off unless enabled.
+    #[serde(default)]
+    pub ledger: Option<Ledger>,
+
+    /// Skill hierarchy used in labor clearing. Skills seen in play but not
+    /// registered clear as unrelated roots.
+    #[serde(default)]
+    pub skill_defs: Vec<SkillDef>,
+
+    /// Definitions used when merchants build or demolish facilities.
+    #[serde(default = "get_facility_defs")]
+    pub facility_defs: Vec<FacilityDef>,
+
+    /// Per-merchant decision makers. Not part of snapshots; re-register
+    /// after loading. Merchants without one use [`SupplyCurveController`].
+    #[serde(skip)]
+    controllers: HashMap<MerchantId, Arc<dyn MerchantController>>,
 
     next_settlement_id: u32,
     next_agent_id: u32,
-
-    rng: StdRng,
+    #[serde(default)]
+    next_vessel_id: u32,
+    #[serde(default)]
+    next_loan_id: u32,
+
+    /// ChaCha12 is the algorithm behind `StdRng`; naming it directly keeps
+    /// the generator state serializable so snapshots resume bit-identically.
+    rng: ChaCha12Rng,
 }
 
 impl Default for World {
@@ -128,36 +248,97 @@ impl Default for World {
 impl World {
     pub fn new() -> Self {
         let mut thread_rng = rand::rng();
+        Self::with_rng(ChaCha12Rng::from_rng(&mut thread_rng))
+    }
+
+    /// Seeded constructor. Does not touch OS entropy, so it also works on
+    /// targets without a thread RNG (e.g. wasm in the browser).
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_rng(ChaCha12Rng::seed_from_u64(seed))
+    }
+
+    fn with_rng(rng: ChaCha12Rng) -> Self {
         Self {
             tick: 0,
             settlements: HashMap::new(),
             routes: Vec::new(),
             merchants: HashMap::new(),
+            vessels: HashMap::new(),
+            couriers: Vec::new(),
+            loans: HashMap::new(),
             external_market: None,
             subsistence_reservation: None,
             mortality_grace_ticks: 0,
+            demography: DemographyConfig::default(),
+            skill_learning: None,
+            migration: None,
+            migrants: Vec::new(),
+            migration_flows: HashMap::new(),
             outside_flow_totals: OutsideFlowTotals::default(),
             stock_flow_history: Vec::new(),
+            transport_totals: TransportTotals::default(),
+            credit_totals: CreditTotals::default(),
+            construction_totals: ConstructionTotals::default(),
+            ledger: None,
+            skill_defs: Vec::new(),
+            facility_defs: get_facility_defs(),
+            controllers: HashMap::new(),
             next_settlement_id: 0,
             next_agent_id: 0,
-            rng: StdRng::from_rng(&mut thread_rng),
+            next_vessel_id: 0,
+            next_loan_id: 0,
+            rng,
         }
     }
 
-    pub fn with_seed(seed: u64) -> Self {
-        let mut world = Self::new();
-        world.rng = StdRng::seed_from_u64(seed);
-        world
+    pub fn set_random_seed(&mut self, seed: u64) {
+        self.rng = ChaCha12Rng::seed_from_u64(seed);
     }
 
-    pub fn set_random_seed(&mut self, seed: u64) {
-        self.rng = StdRng::seed_from_u64(seed);
+    pub fn set_facility_defs(&mut self, defs: Vec<FacilityDef>) {
+        self.facility_defs = defs;
+    }
+
+    pub fn set_skill_defs(&mut self, defs: Vec<SkillDef>) {
+        self.skill_defs = defs;
+    }
+
+    pub fn facility_def(&self, facility_type: FacilityType) -> Option<&FacilityDef> {
+        self.facility_defs
+            .iter()
+            .find(|def| def.facility_type == facility_type)
+    }
+
+    /// Replace the decision maker for a merchant.
+    pub fn set_merchant_controller(
+        &mut self,
+        merchant: MerchantId,
+        controller: Arc<dyn MerchantController>,
+    ) {
+        self.controllers.insert(merchant, controller);
     }
 
     pub fn set_external_market(&mut self, config: ExternalMarketConfig) {
         self.external_market = Some(config);
     }
 
+    pub fn set_skill_learning(&mut self, config: SkillLearningConfig) {
+        self.skill_learning = Some(config);
+    }
+
+    /// Start journaling transfers, or stop and drop the journal.
+    pub fn set_ledger_enabled(&mut self, enabled: bool) {
+        if !enabled {
+            self.ledger = None;
+        } else if self.ledger.is_none() {
+            self.ledger = Some(Ledger::new());
+        }
+    }
+
+    pub fn set_migration(&mut self, config: MigrationConfig) {
+        self.migration = Some(config);
+    }
+
     pub fn set_subsistence_reservation(&mut self, config: SubsistenceReservationConfig) {
         self.subsistence_reservation = Some(config);
     }
@@ -187,10 +368,23 @@ impl World {
         self.routes.push(Route::new(from, to, distance));
     }
 
+    pub fn add_sea_route(&mut self, from: SettlementId, to: SettlementId, distance: u32) {
+        self.routes.push(
+            Route::new(from, to, distance)
+                .with_kind(RouteKind::Sea)
+                .with_risk(SEA_ROUTE_RISK),
+        );
+    }
+
     pub fn find_route(&self, from: SettlementId, to: SettlementId) -> Option<&Route> {
         self.routes.iter().find(|r| r.connects(from, to))
     }
 
+    /// Snapshot of the route network for multi-hop queries.
+    pub fn route_graph(&self) -> RouteGraph {
+        RouteGraph::new(&self.routes)
+    }
+
     pub fn connected_settlements(&self, settlement_id: SettlementId) -> Vec<SettlementId> {
         self.routes
             .iter()
@@ -335,11 +529,17 @@ impl World {
     ) {
         self.tick += 1;
         let pre_tick_snapshot = capture_world_flow_snapshot(self);
+        for settlement in self.settlements.values_mut() {
+            settlement.flows = SettlementFlows::default();
+        }
+        self.open_facility_books();
 
         let mut merchants = std::mem::take(&mut self.merchants);
         let settlement_ids =
             crate::determinism::sorted_settlement_ids(self.settlements.keys().copied());
 
+        self.run_transport_phase(&mut merchants);
+
         self.run_labor_phase_all_settlements(&settlement_ids, recipes, &mut merchants);
 
         for &settlement_id in &settlement_ids {
@@ -347,17 +547,43 @@ impl World {
         }
 
         for &settlement_id in &settlement_ids {
-            self.run_market_phase_settlement(settlement_id, good_profiles, needs, &mut merchants);
+            self.run_skill_phase_settlement(settlement_id);
+        }
+
+        self.refresh_merchant_knowledge(&mut merchants);
+        let mut decisions = self.run_merchant_decisions(&merchants, recipes);
+
+        for &settlement_id in &settlement_ids {
+            let orders = decisions.orders.remove(&settlement_id).unwrap_or_default();
+            self.run_market_phase_settlement(
+                settlement_id,
+                good_profiles,
+                needs,
+                &mut merchants,
+                &orders,
+            );
         }
 
+        self.close_facility_books();
+
         for &settlement_id in &settlement_ids {
             self.run_mortality_phase_settlement(settlement_id);
         }
 
+        self.run_migration_phase();
+
         self.merchants = merchants;
 
+        self.accrue_loan_interest();
+        self.collect_loan_installments();
+
+        self.apply_merchant_actions(decisions.deferred);
+
         let post_tick_snapshot = capture_world_flow_snapshot(self);
         let tick_flow = decompose_tick_flow(self.tick, &pre_tick_snapshot, &post_tick_snapshot);
         self.stock_flow_history.push(tick_flow);
+
+        #[cfg(feature = "instrument")]
+        self.record_balance_sheets();
     }
 }
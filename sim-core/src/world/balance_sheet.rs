@@ -0,0 +1,101 @@
+use super::*;
+use crate::accounting::{BalanceSheet, SettlementPosition};
+use crate::transport::VesselLocation;
+
+impl World {
+    /// Mark-to-market balance sheet for a merchant. Goods are valued at the
+    /// local `price_ema` of the settlement they sit at (or are bound for),
+    /// falling back to 1.0 like [`World::get_price`].
+    pub fn balance_sheet(&self, merchant_id: MerchantId) -> Option<BalanceSheet> {
+        let merchant = self.merchants.get(&merchant_id)?;
+        let mut positions: HashMap<SettlementId, SettlementPosition> = HashMap::new();
+
+        for (&settlement_id, stockpile) in &merchant.stockpiles {
+            positions.entry(settlement_id).or_default().inventory +=
+                self.stock_value(settlement_id, &stockpile.goods);
+        }
+
+        let mut in_transit = 0.0;
+        for vessel_id in crate::determinism::sorted_vessel_ids(self.vessels.keys().copied()) {
+            let Some(vessel) = self.vessels.get(&vessel_id) else {
+                continue;
+            };
+            if vessel.owner != merchant_id {
+                continue;
+            }
+            match vessel.location {
+                VesselLocation::Docked(at) => {
+                    positions.entry(at).or_default().inventory +=
+                        self.stock_value(at, &vessel.cargo.goods);
+                }
+                VesselLocation::InTransit { to, .. } => {
+                    in_transit += self.stock_value(to, &vessel.cargo.goods);
+                }
+            }
+        }
+
+        for settlement_id in
+            crate::determinism::sorted_settlement_ids(self.settlements.keys().copied())
+        {
+            let settlement = &self.settlements[&settlement_id];
+            for key in crate::determinism::sorted_facility_keys(settlement.facilities.keys()) {
+                let facility = &settlement.facilities[key];
+                if facility.owner != merchant_id {
+                    continue;
+                }
+                let position = positions.entry(settlement_id).or_default();
+                position.facility_cash += facility.currency;
+                if let Some(def) = self.facility_def(facility.facility_type) {
+                    position.facility_book_value +=
+                        (def.construction_cost - facility.lifetime_income.amortization).max(0.0);
+                }
+            }
+        }
+
+        let mut settlements: Vec<(SettlementId, SettlementPosition)> =
+            positions.into_iter().collect();
+        settlements.sort_by_key(|(id, _)| id.0);
+
+        let (receivables, debts) = self.loan_positions(merchant_id);
+
+        Some(BalanceSheet {
+            cash: merchant.currency,
+            settlements,
+            in_transit,
+            receivables,
+            debts,
+        })
+    }
+
+    fn stock_value(&self, settlement_id: SettlementId, goods: &HashMap<GoodId, Quantity>) -> f64 {
+        let mut held: Vec<(GoodId, Quantity)> = goods.iter().map(|(g, q)| (*g, *q)).collect();
+        held.sort_by_key(|(g, _)| *g);
+        held.iter()
+            .map(|(g, q)| q * self.get_price(settlement_id, *g))
+            .sum()
+    }
+
+    /// Emit every merchant's balance sheet for the net worth time series.
+    #[cfg(feature = "instrument")]
+    pub(super) fn record_balance_sheets(&self) {
+        for merchant_id in crate::determinism::sorted_merchant_ids(self.merchants.keys().copied()) {
+            let Some(sheet) = self.balance_sheet(merchant_id) else {
+                continue;
+            };
+            let positions = sheet.settlements.iter().map(|(_, p)| p);
+            tracing::info!(
+                target: "balance_sheet",
+                tick = self.tick,
+                merchant_id = merchant_id.0,
+                cash = sheet.cash,
+                facility_cash = positions.clone().map(|p| p.facility_cash).sum::<f64>(),
+                inventory = positions.clone().map(|p| p.inventory).sum::<f64>(),
+                facility_book_value = positions.map(|p| p.facility_book_value).sum::<f64>(),
+                in_transit = sheet.in_transit,
+                receivables = sheet.receivables,
+                debts = sheet.debts,
+                net_worth = sheet.net_worth(),
+            );
+        }
+    }
+}
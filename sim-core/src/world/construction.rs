@@ -0,0 +1,277 @@
+use std::fmt;
+
+use super::*;
+use crate::geography::ResourceType;
+use crate::production::FacilityDef;
+
+/// Why a facility could not be built, demolished or funded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FacilityError {
+    UnknownSettlement(SettlementId),
+    UnknownMerchant(MerchantId),
+    UnknownFacility(FacilityHandle),
+    InsufficientFunds {
+        required: f64,
+        available: f64,
+    },
+    NoResourceSlot(ResourceType),
+    /// Treasury transfers must be finite and non-negative.
+    InvalidAmount(f64),
+}
+
+impl fmt::Display for FacilityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FacilityError::UnknownSettlement(id) => write!(f, "unknown settlement {}", id.0),
+            FacilityError::UnknownMerchant(id) => write!(f, "unknown merchant {}", id.0),
+            FacilityError::UnknownFacility(handle) => write!(
+                f,
+                "unknown facility {} at settlement {}",
+                facility_key_u64(handle.key),
+                handle.settlement.0
+            ),
+            FacilityError::InsufficientFunds {
+                required,
+                available,
+            } => write!(f, "needs {required} but only {available} is available"),
+            FacilityError::NoResourceSlot(resource) => {
+                write!(f, "no free {resource:?} slot at settlement")
+            }
+            FacilityError::InvalidAmount(amount) => write!(f, "invalid transfer amount {amount}"),
+        }
+    }
+}
+
+impl std::error::Error for FacilityError {}
+
+/// Cumulative currency paid for construction and refunded as salvage. Both
+/// cross the economy's boundary, so they enter the currency decomposition.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConstructionTotals {
+    pub spent: f64,
+    pub salvaged: f64,
+}
+
+impl World {
+    /// Construct a facility from its definition, charging the owner
+    /// `construction_cost` and claiming a free resource slot for primary
+    /// facilities. Nothing changes if any precondition fails.
+    pub fn build_facility(
+        &mut self,
+        def: &FacilityDef,
+        settlement_id: SettlementId,
+        owner_id: MerchantId,
+    ) -> Result<FacilityHandle, FacilityError> {
+        let available = self
+            .merchants
+            .get(&owner_id)
+            .ok_or(FacilityError::UnknownMerchant(owner_id))?
+            .currency;
+        let settlement = self
+            .settlements
+            .get(&settlement_id)
+            .ok_or(FacilityError::UnknownSettlement(settlement_id))?;
+        let slot_index = match def.required_resource {
+            Some(resource) => Some(
+                settlement
+                    .info
+                    .find_available_slot(resource)
+                    .ok_or(FacilityError::NoResourceSlot(resource))?,
+            ),
+            None => None,
+        };
+        if available < def.construction_cost {
+            return Err(FacilityError::InsufficientFunds {
+                required: def.construction_cost,
+                available,
+            });
+        }
+
+        let handle = self
+            .add_facility(def.facility_type, settlement_id, owner_id)
+            .expect("settlement and merchant checked above");
+        let settlement = self
+            .settlements
+            .get_mut(&settlement_id)
+            .expect("settlement checked above");
+        let facility = settlement
+            .facilities
+            .get_mut(handle.key)
+            .expect("facility just added");
+        facility.capacity = def.base_capacity;
+        if let Some(index) = slot_index {
+            facility.resource_slot_index = Some(index);
+            let claimed = settlement.info.claim_slot(index, handle.key);
+            debug_assert!(claimed, "slot was available");
+        }
+        self.merchants
+            .get_mut(&owner_id)
+            .expect("merchant checked above")
+            .currency -= def.construction_cost;
+        self.construction_totals.spent += def.construction_cost;
+        if let Some(ledger) = self.ledger.as_mut() {
+            ledger.transfer(
+                self.tick,
+                Phase::Actions,
+                Account::Merchant(owner_id),
+                Account::Construction,
+                None,
+                def.construction_cost,
+            );
+        }
+
+        Ok(handle)
+    }
+
+    /// Tear down a facility. The owner receives `salvage_fraction` of the
+    /// construction cost plus whatever is left in the facility treasury, the
+    /// resource slot is released, and its workers become unemployed until the
+    /// next labor phase reassigns them. A facility whose type has no
+    /// registered definition salvages nothing.
+    pub fn demolish_facility(&mut self, handle: FacilityHandle) -> Result<f64, FacilityError> {
+        let facility_type = self
+            .settlements
+            .get(&handle.settlement)
+            .ok_or(FacilityError::UnknownSettlement(handle.settlement))?
+            .facilities
+            .get(handle.key)
+            .ok_or(FacilityError::UnknownFacility(handle))?
+            .facility_type;
+        let salvage = self
+            .facility_def(facility_type)
+            .map_or(0.0, |def| def.construction_cost * def.salvage_fraction);
+        let settlement = self
+            .settlements
+            .get_mut(&handle.settlement)
+            .expect("settlement checked above");
+
+        let facility = settlement
+            .facilities
+            .remove(handle.key)
+            .expect("facility checked above");
+        settlement.facility_bid_states.remove(handle.key);
+        settlement.info.release_slot(handle.key);
+        if let Some(count) = settlement.owner_facility_counts.get_mut(&facility.owner) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                settlement.owner_facility_counts.remove(&facility.owner);
+            }
+        }
+        for pop in settlement.pops.values_mut() {
+            if pop.employed_at == Some(handle.key) {
+                pop.employed_at = None;
+                pop.employed_skill = None;
+            }
+        }
+
+        let refund = salvage + facility.currency;
+        if let Some(owner) = self.merchants.get_mut(&facility.owner) {
+            owner.owned_facilities.remove(&handle);
+            owner.currency += refund;
+        }
+        self.construction_totals.salvaged += salvage;
+        if let Some(ledger) = self.ledger.as_mut() {
+            let owner = Account::Merchant(facility.owner);
+            ledger.transfer(
+                self.tick,
+                Phase::Actions,
+                Account::Construction,
+                owner,
+                None,
+                salvage,
+            );
+            ledger.transfer(
+                self.tick,
+                Phase::Actions,
+                Account::Facility(handle),
+                owner,
+                None,
+                facility.currency,
+            );
+        }
+
+        Ok(refund)
+    }
+
+    /// Move `amount` from the owner's currency into the facility treasury.
+    pub fn deposit_to_facility(
+        &mut self,
+        handle: FacilityHandle,
+        amount: f64,
+    ) -> Result<(), FacilityError> {
+        if !amount.is_finite() || amount < 0.0 {
+            return Err(FacilityError::InvalidAmount(amount));
+        }
+        let owner_id = self
+            .facility(handle)
+            .ok_or(FacilityError::UnknownFacility(handle))?
+            .owner;
+        let owner = self
+            .merchants
+            .get_mut(&owner_id)
+            .ok_or(FacilityError::UnknownMerchant(owner_id))?;
+        if owner.currency < amount {
+            return Err(FacilityError::InsufficientFunds {
+                required: amount,
+                available: owner.currency,
+            });
+        }
+        owner.currency -= amount;
+        self.facility_mut(handle)
+            .expect("facility checked above")
+            .currency += amount;
+        if let Some(ledger) = self.ledger.as_mut() {
+            ledger.transfer(
+                self.tick,
+                Phase::Actions,
+                Account::Merchant(owner_id),
+                Account::Facility(handle),
+                None,
+                amount,
+            );
+        }
+        Ok(())
+    }
+
+    /// Move `amount` from the facility treasury back to its owner.
+    pub fn withdraw_from_facility(
+        &mut self,
+        handle: FacilityHandle,
+        amount: f64,
+    ) -> Result<(), FacilityError> {
+        if !amount.is_finite() || amount < 0.0 {
+            return Err(FacilityError::InvalidAmount(amount));
+        }
+        let facility = self
+            .facility(handle)
+            .ok_or(FacilityError::UnknownFacility(handle))?;
+        let owner_id = facility.owner;
+        if facility.currency < amount {
+            return Err(FacilityError::InsufficientFunds {
+                required: amount,
+                available: facility.currency,
+            });
+        }
+        if !self.merchants.contains_key(&owner_id) {
+            return Err(FacilityError::UnknownMerchant(owner_id));
+        }
+        self.facility_mut(handle)
+            .expect("facility checked above")
+            .currency -= amount;
+        self.merchants
+            .get_mut(&owner_id)
+            .expect("merchant checked above")
+            .currency += amount;
+        if let Some(ledger) = self.ledger.as_mut() {
+            ledger.transfer(
+                self.tick,
+                Phase::Actions,
+                Account::Facility(handle),
+                Account::Merchant(owner_id),
+                None,
+                amount,
+            );
+        }
+        Ok(())
+    }
+}
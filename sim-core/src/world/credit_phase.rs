@@ -0,0 +1,168 @@
+use super::*;
+
+impl World {
+    /// Lend `principal` from one merchant to another at `rate` interest per
+    /// tick, repaid in `term` installments starting next tick.
+    pub fn issue_loan(
+        &mut self,
+        lender_id: MerchantId,
+        borrower_id: MerchantId,
+        principal: f64,
+        rate: f64,
+        term: u64,
+    ) -> Result<LoanId, CreditError> {
+        if lender_id == borrower_id {
+            return Err(CreditError::SelfLoan(lender_id));
+        }
+        if !principal.is_finite()
+            || principal <= 0.0
+            || !rate.is_finite()
+            || rate < 0.0
+            || term == 0
+        {
+            return Err(CreditError::InvalidTerms);
+        }
+        if !self.merchants.contains_key(&borrower_id) {
+            return Err(CreditError::UnknownMerchant(borrower_id));
+        }
+        let lender = self
+            .merchants
+            .get_mut(&lender_id)
+            .ok_or(CreditError::UnknownMerchant(lender_id))?;
+        if lender.currency < principal {
+            return Err(CreditError::InsufficientFunds {
+                required: principal,
+                available: lender.currency,
+            });
+        }
+        lender.currency -= principal;
+        self.merchants
+            .get_mut(&borrower_id)
+            .expect("borrower checked above")
+            .currency += principal;
+
+        let id = LoanId::new(self.next_loan_id);
+        self.next_loan_id += 1;
+        self.loans.insert(
+            id,
+            Loan::new(id, lender_id, borrower_id, principal, rate, term, self.tick),
+        );
+        self.credit_totals.issued += principal;
+        if let Some(ledger) = self.ledger.as_mut() {
+            ledger.transfer(
+                self.tick,
+                Phase::Actions,
+                Account::Merchant(lender_id),
+                Account::Merchant(borrower_id),
+                None,
+                principal,
+            );
+        }
+        Ok(id)
+    }
+
+    pub fn loan(&self, id: LoanId) -> Option<&Loan> {
+        self.loans.get(&id)
+    }
+
+    /// Pay down a loan ahead of schedule from the borrower's purse, interest
+    /// first. Pays at most what is owed and what the borrower has; returns
+    /// the amount paid.
+    pub fn repay_loan(&mut self, id: LoanId, amount: f64) -> Result<f64, CreditError> {
+        if !amount.is_finite() || amount < 0.0 {
+            return Err(CreditError::InvalidAmount(amount));
+        }
+        if !self.loans.contains_key(&id) {
+            return Err(CreditError::UnknownLoan(id));
+        }
+        Ok(self.pay_loan(id, amount, Phase::Actions))
+    }
+
+    /// Interest accrues on every loan before installments are collected.
+    pub(super) fn accrue_loan_interest(&mut self) {
+        for id in crate::determinism::sorted_loan_ids(self.loans.keys().copied()) {
+            let interest = self.loans.get_mut(&id).expect("loan id from map").accrue();
+            self.credit_totals.interest_accrued += interest;
+        }
+    }
+
+    /// Collect the installment due on every loan. A borrower who cannot pay
+    /// in full pays what it has; the shortfall stays owed and falls due
+    /// again next tick.
+    pub(super) fn collect_loan_installments(&mut self) {
+        for id in crate::determinism::sorted_loan_ids(self.loans.keys().copied()) {
+            let loan = &self.loans[&id];
+            let due = loan.installment_due(self.tick);
+            #[cfg(feature = "instrument")]
+            let (lender, borrower) = (loan.lender, loan.borrower);
+            let paid = self.pay_loan(id, due, Phase::Credit);
+
+            #[cfg(feature = "instrument")]
+            tracing::info!(
+                target: "loan",
+                tick = self.tick,
+                loan_id = id.0,
+                lender_id = lender.0,
+                borrower_id = borrower.0,
+                due = due,
+                paid = paid,
+                balance = self.loans.get(&id).map_or(0.0, |l| l.balance()),
+            );
+            #[cfg(not(feature = "instrument"))]
+            let _ = paid;
+        }
+    }
+
+    /// Move up to `amount` from the borrower to the lender and retire the
+    /// loan once nothing is owed.
+    fn pay_loan(&mut self, id: LoanId, amount: f64, phase: Phase) -> f64 {
+        let Some(loan) = self.loans.get_mut(&id) else {
+            return 0.0;
+        };
+        let available = self
+            .merchants
+            .get(&loan.borrower)
+            .map_or(0.0, |m| m.currency.max(0.0));
+        let (interest, principal) = loan.apply_payment(amount.min(available));
+        let (lender, borrower) = (loan.lender, loan.borrower);
+        let paid = interest + principal;
+        if loan.is_repaid() {
+            self.loans.remove(&id);
+        }
+
+        if let Some(m) = self.merchants.get_mut(&borrower) {
+            m.currency -= paid;
+        }
+        if let Some(m) = self.merchants.get_mut(&lender) {
+            m.currency += paid;
+        }
+        self.credit_totals.interest_paid += interest;
+        self.credit_totals.principal_repaid += principal;
+        if let Some(ledger) = self.ledger.as_mut() {
+            ledger.transfer(
+                self.tick,
+                phase,
+                Account::Merchant(borrower),
+                Account::Merchant(lender),
+                None,
+                paid,
+            );
+        }
+        paid
+    }
+
+    /// What a merchant is owed and owes across its loans.
+    pub(super) fn loan_positions(&self, merchant_id: MerchantId) -> (f64, f64) {
+        let (mut receivables, mut debts) = (0.0, 0.0);
+        for id in crate::determinism::sorted_loan_ids(self.loans.keys().copied()) {
+            let loan = &self.loans[&id];
+            if loan.lender == merchant_id {
+                receivables += loan.balance();
+            }
+            if loan.borrower == merchant_id {
+                debts += loan.balance();
+            }
+        }
+        (receivables, debts)
+    }
+}
@@ -0,0 +1,77 @@
+use super::*;
+use crate::production::IncomeStatement;
+
+impl World {
+    pub(super) fn open_facility_books(&mut self) {
+        for settlement in self.settlements.values_mut() {
+            for facility in settlement.facilities.values_mut() {
+                facility.income = IncomeStatement::default();
+                facility.produced.clear();
+                facility.used.clear();
+            }
+        }
+    }
+
+    /// Charge this tick's amortization and fold the tick into each
+    /// facility's lifetime income. Amortization stops once the
+    /// construction cost is written off.
+    pub(super) fn close_facility_books(&mut self) {
+        let settlement_ids =
+            crate::determinism::sorted_settlement_ids(self.settlements.keys().copied());
+        for settlement_id in settlement_ids {
+            let Some(settlement) = self.settlements.get_mut(&settlement_id) else {
+                continue;
+            };
+            let facility_keys =
+                crate::determinism::sorted_facility_keys(settlement.facilities.keys());
+            for facility_key in facility_keys {
+                let Some(facility) = settlement.facilities.get_mut(facility_key) else {
+                    continue;
+                };
+                if let Some(def) = self
+                    .facility_defs
+                    .iter()
+                    .find(|d| d.facility_type == facility.facility_type)
+                {
+                    let remaining =
+                        (def.construction_cost - facility.lifetime_income.amortization).max(0.0);
+                    facility.income.amortization = def.amortization_per_tick().min(remaining);
+                }
+                let income = facility.income;
+                facility.lifetime_income += income;
+
+                #[cfg(feature = "instrument")]
+                tracing::info!(
+                    target: "facility_income",
+                    tick = self.tick,
+                    settlement_id = settlement_id.0,
+                    facility_id = facility_key_u64(facility_key),
+                    owner_id = facility.owner.0,
+                    revenue = income.revenue,
+                    wages = income.wages,
+                    input_costs = income.input_costs,
+                    depreciation = income.depreciation,
+                    amortization = income.amortization,
+                    profit = income.profit(),
+                );
+            }
+        }
+    }
+
+    /// The facility's income statement for the latest tick.
+    pub fn facility_income(&self, handle: FacilityHandle) -> Option<&IncomeStatement> {
+        self.facility(handle).map(|f| &f.income)
+    }
+
+    /// The facility's income statement since it was built.
+    pub fn facility_lifetime_income(&self, handle: FacilityHandle) -> Option<&IncomeStatement> {
+        self.facility(handle).map(|f| &f.lifetime_income)
+    }
+
+    /// Lifetime profit as a fraction of the facility's construction cost.
+    pub fn facility_return_on_capital(&self, handle: FacilityHandle) -> Option<f64> {
+        let facility = self.facility(handle)?;
+        let capital = self.facility_def(facility.facility_type)?.construction_cost;
+        (capital > 0.0).then(|| facility.lifetime_income.profit() / capital)
+    }
+}
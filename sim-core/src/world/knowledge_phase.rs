@@ -0,0 +1,176 @@
+use super::*;
+use crate::transport::TransportError;
+
+impl World {
+    /// What an observer standing in the settlement would see right now.
+    pub fn market_snapshot(&self, settlement_id: SettlementId) -> Option<MarketSnapshot> {
+        self.capture_market_snapshot(settlement_id, &self.merchants)
+    }
+
+    pub(super) fn capture_market_snapshot(
+        &self,
+        settlement_id: SettlementId,
+        merchants: &HashMap<MerchantId, MerchantAgent>,
+    ) -> Option<MarketSnapshot> {
+        let state = self.settlements.get(&settlement_id)?;
+
+        let mut stocks: HashMap<GoodId, Quantity> = HashMap::new();
+        for pop in state.pops.values() {
+            for (good, qty) in &pop.stocks {
+                *stocks.entry(*good).or_insert(0.0) += *qty;
+            }
+        }
+        for merchant_id in crate::determinism::sorted_merchant_ids(merchants.keys().copied()) {
+            if let Some(stockpile) = merchants[&merchant_id].stockpiles.get(&settlement_id) {
+                for (good, qty) in &stockpile.goods {
+                    *stocks.entry(*good).or_insert(0.0) += *qty;
+                }
+            }
+        }
+
+        Some(MarketSnapshot {
+            settlement: settlement_id,
+            observed_tick: self.tick,
+            pop_count: state.pops.len(),
+            prices: state.price_ema.clone(),
+            wages: state.wage_ema.clone(),
+            depth: state.market_depth.clone(),
+            stocks,
+        })
+    }
+
+    /// Give every merchant a fresh snapshot of each settlement where it
+    /// owns a facility.
+    pub(super) fn refresh_merchant_knowledge(
+        &self,
+        merchants: &mut HashMap<MerchantId, MerchantAgent>,
+    ) {
+        for settlement_id in
+            crate::determinism::sorted_settlement_ids(self.settlements.keys().copied())
+        {
+            let present = crate::determinism::sorted_merchant_ids(
+                self.settlements[&settlement_id]
+                    .owner_facility_counts
+                    .keys()
+                    .copied(),
+            );
+            if present.is_empty() {
+                continue;
+            }
+            let Some(snapshot) = self.capture_market_snapshot(settlement_id, merchants) else {
+                continue;
+            };
+            for merchant_id in present {
+                if let Some(merchant) = merchants.get_mut(&merchant_id) {
+                    merchant.knowledge.record(snapshot.clone());
+                }
+            }
+        }
+    }
+
+    /// Send a report on `from`'s market to the merchant over a direct land
+    /// route. The merchant learns it `distance` ticks later; the courier's
+    /// fee is one unit of the route's `transport_cost`. Returns the ticks
+    /// until delivery.
+    pub fn send_courier(
+        &mut self,
+        merchant_id: MerchantId,
+        from: SettlementId,
+        to: SettlementId,
+    ) -> Result<u32, TransportError> {
+        if !self.merchants.contains_key(&merchant_id) {
+            return Err(TransportError::UnknownMerchant(merchant_id));
+        }
+        for id in [from, to] {
+            if !self.settlements.contains_key(&id) {
+                return Err(TransportError::UnknownSettlement(id));
+            }
+        }
+        let route = self
+            .routes
+            .iter()
+            .find(|r| r.kind == RouteKind::Land && r.connects(from, to))
+            .ok_or(TransportError::NoRoute {
+                from,
+                to,
+                kind: RouteKind::Land,
+            })?;
+        let fee = route.transport_cost;
+        let ticks_remaining = route.distance.max(1);
+
+        let report = self
+            .market_snapshot(from)
+            .expect("settlement checked above");
+        let merchant = self
+            .merchants
+            .get_mut(&merchant_id)
+            .expect("merchant checked above");
+        if merchant.currency < fee {
+            return Err(TransportError::InsufficientFunds {
+                required: fee,
+                available: merchant.currency,
+            });
+        }
+        merchant.currency -= fee;
+        self.transport_totals.freight_paid += fee;
+        if let Some(ledger) = self.ledger.as_mut() {
+            ledger.transfer(
+                self.tick,
+                Phase::Actions,
+                Account::Merchant(merchant_id),
+                Account::Transport,
+                None,
+                fee,
+            );
+        }
+
+        self.couriers.push(Courier {
+            merchant: merchant_id,
+            from,
+            to,
+            ticks_remaining,
+            report,
+        });
+
+        #[cfg(feature = "instrument")]
+        tracing::info!(
+            target: "courier",
+            tick = self.tick,
+            merchant_id = merchant_id.0,
+            event = "send",
+            from_settlement = from.0,
+            to_settlement = to.0,
+            fee = fee,
+        );
+
+        Ok(ticks_remaining)
+    }
+
+    /// Advance couriers one tick and hand arrived reports to their merchants.
+    pub(super) fn run_courier_phase(&mut self, merchants: &mut HashMap<MerchantId, MerchantAgent>) {
+        let mut in_flight = Vec::with_capacity(self.couriers.len());
+        for mut courier in std::mem::take(&mut self.couriers) {
+            courier.ticks_remaining = courier.ticks_remaining.saturating_sub(1);
+            if courier.ticks_remaining > 0 {
+                in_flight.push(courier);
+                continue;
+            }
+
+            #[cfg(feature = "instrument")]
+            tracing::info!(
+                target: "courier",
+                tick = self.tick,
+                merchant_id = courier.merchant.0,
+                event = "arrive",
+                from_settlement = courier.from.0,
+                to_settlement = courier.to.0,
+                fee = 0.0,
+            );
+
+            if let Some(merchant) = merchants.get_mut(&courier.merchant) {
+                merchant.knowledge.record(courier.report);
+            }
+        }
+        self.couriers = in_flight;
+    }
+}
@@ -1,7 +1,11 @@
 use super::*;
 
 impl World {
-    fn gather_labor_skills(settlement: &SettlementState, recipes: &[Recipe]) -> Vec<SkillDef> {
+    fn gather_labor_skills(
+        settlement: &SettlementState,
+        recipes: &[Recipe],
+        registered: &[SkillDef],
+    ) -> Vec<SkillDef> {
         let mut skill_ids: HashSet<SkillId> = settlement.wage_ema.keys().copied().collect();
 
         for pop in settlement.pops.values() {
@@ -12,36 +16,55 @@ impl World {
             for recipe_id in &facility.recipe_priorities {
                 if let Some(recipe) = recipes.iter().find(|r| r.id == *recipe_id) {
                     skill_ids.extend(recipe.workers.keys().copied());
+                    if let Some(production_fn) = &recipe.production_fn {
+                        skill_ids.extend(production_fn.relevant_skills());
+                    }
                 }
             }
         }
 
+        // Registered ancestors join so specialists can fill parent slots
+        let registered: HashMap<SkillId, SkillDef> =
+            registered.iter().map(|s| (s.id, s.clone())).collect();
+        let present: Vec<SkillId> = skill_ids.iter().copied().collect();
+        for id in present {
+            if let Some(def) = registered.get(&id) {
+                skill_ids.extend(def.skill_chain(&registered).iter().map(|s| s.id));
+            }
+        }
+
         let mut ordered_skill_ids: Vec<SkillId> = skill_ids.into_iter().collect();
         ordered_skill_ids.sort_by_key(|s| s.0);
 
         ordered_skill_ids
             .into_iter()
-            .map(|id| SkillDef {
-                id,
-                name: String::new(),
-                parent: None,
+            .map(|id| {
+                registered
+                    .get(&id)
+                    .cloned()
+                    .unwrap_or_else(|| SkillDef::unregistered(id))
             })
             .collect()
     }
 
     /// Compute per-skill MVPs across all recipes a facility can run.
     ///
-    /// For each recipe, the per-worker value is `output_value / total_workers`.
-    /// Each skill gets the best (highest) per-worker value across all recipes
-    /// that use it. This lets facilities bid differently for high-value vs
-    /// low-value skills and ensures secondary recipes' labor needs are visible
-    /// to the labor market.
+    /// For each recipe, the per-worker value is `output_value / total_workers`,
+    /// or with a production function the value of the marginal product at
+    /// the crew production would give the recipe from the facility's current
+    /// workers and the owner's `stockpile`. Each skill gets the best
+    /// (highest) per-worker value across all recipes that use it. This lets
+    /// facilities bid differently for high-value vs low-value skills and
+    /// ensures secondary recipes' labor needs are visible to the labor market.
     fn facility_skill_mvps(
         settlement: &SettlementState,
+        facility_key: FacilityKey,
         facility: &Facility,
         recipes: &[Recipe],
+        stockpile: &Stockpile,
     ) -> HashMap<SkillId, Price> {
         let mut skill_mvps: HashMap<SkillId, Price> = HashMap::new();
+        let crews = allocate_recipes(facility_key, facility, recipes, stockpile).crews;
 
         for recipe_id in &facility.recipe_priorities {
             let Some(recipe) = recipes.iter().find(|r| r.id == *recipe_id) else {
@@ -51,11 +74,6 @@ impl World {
                 continue;
             }
 
-            let total_workers: u32 = recipe.workers.values().sum();
-            if total_workers == 0 {
-                continue;
-            }
-
             let output_value: Price = recipe
                 .outputs
                 .iter()
@@ -65,6 +83,23 @@ impl World {
                 })
                 .sum();
 
+            if let Some(production_fn) = &recipe.production_fn {
+                let mut skills = production_fn.relevant_skills();
+                skills.extend(recipe.workers.keys().copied());
+                let crew = crews.get(recipe_id).cloned().unwrap_or_default();
+                for skill in skills {
+                    let marginal = production_fn.marginal_product(&crew, skill);
+                    let mvp = output_value * marginal;
+                    let entry = skill_mvps.entry(skill).or_insert(0.0);
+                    *entry = entry.max(mvp);
+                }
+                continue;
+            }
+
+            let total_workers: u32 = recipe.workers.values().sum();
+            if total_workers == 0 {
+                continue;
+            }
             let per_worker = output_value / total_workers as f64;
 
             for &skill in recipe.workers.keys() {
@@ -98,10 +133,14 @@ impl World {
             .iter()
             .map(|(id, merchant)| (*id, merchant.currency))
             .collect();
+        let treasuries = self.facility_treasuries();
 
         let first_candidates = self.collect_candidate_assignments(&prepared);
-        let first_reservation =
-            Self::reserve_payable_assignments(first_candidates, &initial_owner_budgets);
+        let first_reservation = Self::reserve_payable_assignments(
+            first_candidates,
+            &initial_owner_budgets,
+            &treasuries,
+        );
 
         let mut final_prepared = prepared;
         let mut final_payable_by_settlement = first_reservation.payable_by_settlement;
@@ -129,6 +168,7 @@ impl World {
                 crate::determinism::sorted_settlement_ids(impacted_settlements.iter().copied());
 
             let mut reclear_owner_budgets = initial_owner_budgets.clone();
+            let mut reclear_treasuries = treasuries.clone();
             let payable_settlement_ids = crate::determinism::sorted_settlement_ids(
                 final_payable_by_settlement.keys().copied(),
             );
@@ -148,8 +188,13 @@ impl World {
                     else {
                         continue;
                     };
+                    let treasury = reclear_treasuries
+                        .entry((settlement_id, assignment.facility_id))
+                        .or_insert(0.0);
+                    let from_treasury = treasury.clamp(0.0, assignment.wage);
+                    *treasury -= from_treasury;
                     let entry = reclear_owner_budgets.entry(owner_id).or_insert(0.0);
-                    *entry = (*entry - assignment.wage).max(0.0);
+                    *entry = (*entry - (assignment.wage - from_treasury)).max(0.0);
                 }
             }
 
@@ -172,8 +217,11 @@ impl World {
                 impacted_prepared.insert(settlement_id, prepared_settlement);
             }
             let reclear_candidates = self.collect_candidate_assignments(&impacted_prepared);
-            let reclear_reservation =
-                Self::reserve_payable_assignments(reclear_candidates, &reclear_owner_budgets);
+            let reclear_reservation = Self::reserve_payable_assignments(
+                reclear_candidates,
+                &reclear_owner_budgets,
+                &reclear_treasuries,
+            );
             final_prepared.extend(impacted_prepared);
             for (settlement_id, assignments) in reclear_reservation.payable_by_settlement {
                 final_payable_by_settlement.insert(settlement_id, assignments);
@@ -242,22 +290,44 @@ impl World {
         candidates
     }
 
+    /// Treasury of every facility, keyed by settlement and facility.
+    fn facility_treasuries(&self) -> HashMap<(SettlementId, FacilityKey), f64> {
+        self.settlements
+            .iter()
+            .flat_map(|(settlement_id, settlement)| {
+                settlement
+                    .facilities
+                    .iter()
+                    .map(|(key, facility)| ((*settlement_id, key), facility.currency))
+            })
+            .collect()
+    }
+
+    /// Wages come out of the facility's treasury first and the owner's
+    /// currency after that.
     fn reserve_payable_assignments(
         candidates: Vec<CandidateLaborAssignment>,
         owner_budgets: &HashMap<MerchantId, f64>,
+        treasuries: &HashMap<(SettlementId, FacilityKey), f64>,
     ) -> LaborReservationResult {
         let mut owner_remaining = owner_budgets.clone();
+        let mut treasury_remaining = treasuries.clone();
         let mut payable_by_settlement: HashMap<SettlementId, Vec<Assignment>> = HashMap::new();
         let mut clipped_owners: HashSet<MerchantId> = HashSet::new();
 
         for candidate in candidates {
+            let treasury = treasury_remaining
+                .entry((candidate.settlement_id, candidate.assignment.facility_id))
+                .or_insert(0.0);
             let remaining = owner_remaining.entry(candidate.owner_id).or_insert(0.0);
-            if *remaining + 1e-9 < candidate.assignment.wage {
+            if *treasury + *remaining + 1e-9 < candidate.assignment.wage {
                 clipped_owners.insert(candidate.owner_id);
                 continue;
             }
 
-            *remaining -= candidate.assignment.wage;
+            let from_treasury = treasury.clamp(0.0, candidate.assignment.wage);
+            *treasury -= from_treasury;
+            *remaining -= candidate.assignment.wage - from_treasury;
             payable_by_settlement
                 .entry(candidate.settlement_id)
                 .or_default()
@@ -281,7 +351,7 @@ impl World {
 
         settlement.update_subsistence_queue();
 
-        let skills = Self::gather_labor_skills(settlement, recipes);
+        let skills = Self::gather_labor_skills(settlement, recipes, &self.skill_defs);
 
         let wage_seed = if settlement.wage_ema.is_empty() {
             1.0
@@ -310,7 +380,7 @@ impl World {
             let merchant_budget = owner_budget_overrides
                 .and_then(|budgets| budgets.get(&facility.owner).copied())
                 .unwrap_or(merchant_budget);
-            if merchant_budget <= 0.0 {
+            if merchant_budget + facility.currency <= 0.0 {
                 continue;
             }
 
@@ -327,8 +397,13 @@ impl World {
 
             let max_workers = facility.capacity.min(50);
 
+            let empty = Stockpile::new();
+            let stockpile = merchants
+                .get(&facility.owner)
+                .and_then(|m| m.stockpiles.get(&settlement_id))
+                .unwrap_or(&empty);
             let skill_mvps =
-                Self::facility_skill_mvps(settlement, facility, recipes);
+                Self::facility_skill_mvps(settlement, facility_key, facility, recipes, stockpile);
 
             let mut skill_mvp_pairs: Vec<_> = skill_mvps.iter().collect();
             skill_mvp_pairs.sort_by_key(|(s, _)| s.0);
@@ -406,11 +481,12 @@ impl World {
             .map(|(k, f)| {
                 (
                     k,
-                    owner_budget_overrides
-                        .and_then(|budgets| budgets.get(&f.owner).copied())
-                        .unwrap_or_else(|| {
-                            merchants.get(&f.owner).map(|m| m.currency).unwrap_or(0.0)
-                        }),
+                    f.currency
+                        + owner_budget_overrides
+                            .and_then(|budgets| budgets.get(&f.owner).copied())
+                            .unwrap_or_else(|| {
+                                merchants.get(&f.owner).map(|m| m.currency).unwrap_or(0.0)
+                            }),
                 )
             })
             .collect();
@@ -461,8 +537,12 @@ impl World {
                 settlement
                     .facilities
                     .get(assignment.facility_id)
-                    .and_then(|facility| merchants.get(&facility.owner))
-                    .map(|merchant| merchant.currency + 1e-9 >= assignment.wage)
+                    .and_then(|facility| {
+                        merchants
+                            .get(&facility.owner)
+                            .map(|merchant| facility.currency + merchant.currency)
+                    })
+                    .map(|funds| funds + 1e-9 >= assignment.wage)
                     .unwrap_or(false)
             }),
             "labor commit should only receive payable assignments"
@@ -551,17 +631,17 @@ impl World {
             .collect();
 
         for assignment in &assignments {
-            let Some(owner_id) = settlement
+            let Some((owner_id, treasury)) = settlement
                 .facilities
                 .get(assignment.facility_id)
-                .map(|f| f.owner)
+                .map(|f| (f.owner, f.currency))
             else {
                 continue;
             };
 
             let can_pay = merchants
                 .get(&owner_id)
-                .map(|m| m.currency + 1e-9 >= assignment.wage)
+                .map(|m| treasury + m.currency + 1e-9 >= assignment.wage)
                 .unwrap_or(false);
             if !can_pay {
                 continue;
@@ -571,8 +651,40 @@ impl World {
                 continue;
             };
 
+            // The treasury pays first; the owner covers the rest
+            let from_treasury = treasury.clamp(0.0, assignment.wage);
+            if let Some(facility) = settlement.facilities.get_mut(assignment.facility_id) {
+                facility.currency -= from_treasury;
+                facility.income.wages += assignment.wage;
+            }
             if let Some(merchant) = merchants.get_mut(&owner_id) {
-                merchant.currency -= assignment.wage;
+                merchant.currency -= assignment.wage - from_treasury;
+            }
+            if let Some(ledger) = self.ledger.as_mut() {
+                let facility = FacilityHandle {
+                    settlement: settlement_id,
+                    key: assignment.facility_id,
+                };
+                let pop = Account::Pop(PopHandle {
+                    settlement: settlement_id,
+                    key: pop_key,
+                });
+                ledger.transfer(
+                    self.tick,
+                    Phase::Labor,
+                    Account::Facility(facility),
+                    pop,
+                    None,
+                    from_treasury,
+                );
+                ledger.transfer(
+                    self.tick,
+                    Phase::Labor,
+                    Account::Merchant(owner_id),
+                    pop,
+                    None,
+                    assignment.wage - from_treasury,
+                );
             }
 
             if let Some(pop) = settlement.pops.get_mut(pop_key) {
@@ -1,4 +1,7 @@
 use super::*;
+use crate::labor::ordered_subsistence_yields;
+use crate::market::Side;
+use crate::types::AgentId;
 
 impl World {
     pub(super) fn run_market_phase_settlement(
@@ -7,12 +10,19 @@ impl World {
         good_profiles: &[GoodProfile],
         needs: &HashMap<String, crate::needs::Need>,
         merchants: &mut HashMap<MerchantId, MerchantAgent>,
+        merchant_orders: &HashMap<MerchantId, Vec<Order>>,
     ) {
         let Some(settlement) = self.settlements.get_mut(&settlement_id) else {
             return;
         };
 
-        if let Some(config) = &self.external_market {
+        // Inland settlements under a port-only config never see the ladders
+        let external_market = self
+            .external_market
+            .as_ref()
+            .filter(|config| config.reaches(settlement.info.role()));
+
+        if let Some(config) = external_market {
             for (&good, anchor) in &config.anchors {
                 let current = settlement
                     .depth_multipliers
@@ -37,11 +47,72 @@ impl World {
             .filter_map(|id| merchants.remove(id).map(|m| (*id, m)))
             .collect();
 
+        // Treasuries here join their owner's purse for the market and are
+        // settled back from the fills afterwards
+        let mut swept: HashMap<MerchantId, Vec<(FacilityKey, f64)>> = HashMap::new();
+        for (id, merchant) in &mut extracted_merchants {
+            let keys = crate::determinism::sorted_facility_keys(
+                settlement
+                    .facilities
+                    .iter()
+                    .filter(|(_, f)| f.owner == *id)
+                    .map(|(k, _)| k),
+            );
+            let balances: Vec<(FacilityKey, f64)> = keys
+                .into_iter()
+                .filter_map(|k| {
+                    let facility = settlement.facilities.get_mut(k)?;
+                    Some((k, std::mem::take(&mut facility.currency)))
+                })
+                .collect();
+            merchant.currency += balances.iter().map(|(_, b)| b).sum::<f64>();
+            if let Some(ledger) = self.ledger.as_mut() {
+                for (key, balance) in &balances {
+                    let facility = FacilityHandle {
+                        settlement: settlement_id,
+                        key: *key,
+                    };
+                    ledger.transfer(
+                        self.tick,
+                        Phase::Market,
+                        Account::Facility(facility),
+                        Account::Merchant(*id),
+                        None,
+                        *balance,
+                    );
+                }
+            }
+            swept.insert(*id, balances);
+        }
+
+        // The subsistence grain run_settlement_tick is about to hand out
+        let subsistence_yields = match &self.subsistence_reservation {
+            Some(config) => {
+                let unemployed: Vec<PopKey> =
+                    crate::determinism::sorted_pop_keys(settlement.pops.keys())
+                        .into_iter()
+                        .filter(|k| settlement.pops[*k].employed_at.is_none())
+                        .collect();
+                let yields = ordered_subsistence_yields(
+                    &settlement.subsistence_queue,
+                    &unemployed,
+                    config.q_max,
+                    config.carrying_capacity,
+                );
+                yields
+                    .into_iter()
+                    .filter(|(_, qty)| *qty > 0.0)
+                    .map(|(k, qty)| (k, config.grain_good, qty))
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+
         let mut pop_refs: Vec<(PopKey, &mut Pop)> = settlement.pops.iter_mut().collect();
         let mut merchant_refs: Vec<&mut MerchantAgent> =
             extracted_merchants.iter_mut().map(|(_, m)| m).collect();
 
-        let _result = run_settlement_tick(
+        let result = run_settlement_tick(
             self.tick,
             settlement_id,
             &mut pop_refs,
@@ -49,15 +120,173 @@ impl World {
             good_profiles,
             needs,
             &mut settlement.price_ema,
-            self.external_market.as_ref(),
+            external_market,
             Some(&mut self.outside_flow_totals),
             self.subsistence_reservation.as_ref(),
             &settlement.depth_multipliers,
             Some(&settlement.subsistence_queue),
+            Some(merchant_orders),
         );
+        settlement.last_fills = result.fills;
+        settlement.market_depth.clear();
+        for fill in &settlement.last_fills {
+            if matches!(fill.side, Side::Buy) {
+                *settlement.market_depth.entry(fill.good).or_insert(0.0) += fill.quantity;
+            }
+        }
+
+        for (_, good, qty) in &subsistence_yields {
+            *settlement.flows.subsistence.entry(*good).or_insert(0.0) += qty;
+        }
+        for pop in settlement.pops.values() {
+            for (good, qty) in &pop.consumed {
+                *settlement.flows.consumed.entry(*good).or_insert(0.0) += qty;
+            }
+        }
+
+        if let Some(ledger) = self.ledger.as_mut() {
+            let (tick, market) = (self.tick, Account::Market(settlement_id));
+            let pop_account = |key| {
+                Account::Pop(PopHandle {
+                    settlement: settlement_id,
+                    key,
+                })
+            };
+            for (pop_key, good, qty) in subsistence_yields {
+                ledger.transfer(
+                    tick,
+                    Phase::Market,
+                    Account::Commons(settlement_id),
+                    pop_account(pop_key),
+                    Some(good),
+                    qty,
+                );
+            }
+            for fill in &settlement.last_fills {
+                let agent = match fill.agent_id {
+                    AgentId::Pop(key) => pop_account(key),
+                    AgentId::Merchant(id) => Account::Merchant(id),
+                    AgentId::Outside(_) => Account::Outside(settlement_id),
+                };
+                let (buyer, seller) = match fill.side {
+                    Side::Buy => (agent, market),
+                    Side::Sell => (market, agent),
+                };
+                let value = fill.quantity * fill.price;
+                ledger.transfer(
+                    tick,
+                    Phase::Market,
+                    seller,
+                    buyer,
+                    Some(fill.good),
+                    fill.quantity,
+                );
+                ledger.transfer(tick, Phase::Market, buyer, seller, None, value);
+            }
+        }
+
+        for (id, merchant) in &mut extracted_merchants {
+            if let Some(balances) = swept.remove(id) {
+                let keys: Vec<FacilityKey> = balances.iter().map(|(k, _)| *k).collect();
+                settle_facility_treasuries(
+                    &mut settlement.facilities,
+                    merchant,
+                    balances,
+                    &settlement.last_fills,
+                );
+                if let Some(ledger) = self.ledger.as_mut() {
+                    for key in keys {
+                        let facility = FacilityHandle {
+                            settlement: settlement_id,
+                            key,
+                        };
+                        ledger.transfer(
+                            self.tick,
+                            Phase::Market,
+                            Account::Merchant(*id),
+                            Account::Facility(facility),
+                            None,
+                            settlement.facilities[key].currency,
+                        );
+                    }
+                }
+            }
+        }
 
         for (id, merchant) in extracted_merchants {
             merchants.insert(id, merchant);
         }
     }
 }
+
+/// Return swept treasuries to their facilities. Each of the owner's sales
+/// is credited to the facilities that produced the good this tick, in
+/// proportion to their output and up to it; each purchase is charged to the
+/// facilities that consumed the good, likewise up to what they used. The
+/// rest of a fill, such as goods delivered by vessel, resold, or bought
+/// ahead, stays with the owner. A treasury that would go negative is
+/// floored at zero with the owner covering it; if the owner's own purse
+/// ends up negative it draws on the treasuries in key order.
+fn settle_facility_treasuries(
+    facilities: &mut SlotMap<FacilityKey, Facility>,
+    merchant: &mut MerchantAgent,
+    mut balances: Vec<(FacilityKey, f64)>,
+    fills: &[Fill],
+) {
+    // Quantity of each good still to attribute, per facility
+    let mut unsold: Vec<HashMap<GoodId, Quantity>> = Vec::with_capacity(balances.len());
+    let mut unbought: Vec<HashMap<GoodId, Quantity>> = Vec::with_capacity(balances.len());
+    for (key, _) in &balances {
+        let facility = facilities.get(*key);
+        unsold.push(facility.map(|f| f.produced.clone()).unwrap_or_default());
+        unbought.push(facility.map(|f| f.used.clone()).unwrap_or_default());
+    }
+
+    let agent = AgentId::Merchant(merchant.id);
+    for fill in fills.iter().filter(|f| f.agent_id == agent) {
+        let buy = matches!(fill.side, Side::Buy);
+        let remaining = if buy { &mut unbought } else { &mut unsold };
+        let open: f64 = remaining
+            .iter()
+            .map(|r| r.get(&fill.good).copied().unwrap_or(0.0))
+            .sum();
+        if open <= 0.0 {
+            continue;
+        }
+        let attributed = fill.quantity.min(open);
+        for (i, left) in remaining.iter_mut().enumerate() {
+            let Some(left) = left.get_mut(&fill.good) else {
+                continue;
+            };
+            let qty = attributed * *left / open;
+            *left -= qty;
+            let value = qty * fill.price;
+            balances[i].1 += if buy { -value } else { value };
+            if let Some(facility) = facilities.get_mut(balances[i].0) {
+                if buy {
+                    facility.income.input_costs += value;
+                } else {
+                    facility.income.revenue += value;
+                }
+            }
+        }
+    }
+
+    for (_, balance) in &mut balances {
+        *balance = balance.max(0.0);
+        merchant.currency -= *balance;
+    }
+    for (_, balance) in &mut balances {
+        if merchant.currency >= 0.0 {
+            break;
+        }
+        let draw = balance.min(-merchant.currency);
+        *balance -= draw;
+        merchant.currency += draw;
+    }
+    for (key, balance) in balances {
+        if let Some(facility) = facilities.get_mut(key) {
+            facility.currency = balance;
+        }
+    }
+}
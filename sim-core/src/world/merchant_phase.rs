@@ -0,0 +1,218 @@
+use super::*;
+
+/// Controller output for one tick: orders for this tick's markets, and the
+/// structural actions to apply once the tick is done.
+pub(super) struct MerchantDecisions {
+    pub(super) orders: HashMap<SettlementId, HashMap<MerchantId, Vec<Order>>>,
+    pub(super) deferred: Vec<(MerchantId, MerchantAction)>,
+}
+
+impl World {
+    /// Ask every merchant's controller for its actions, in merchant id order.
+    pub(super) fn run_merchant_decisions(
+        &self,
+        merchants: &HashMap<MerchantId, MerchantAgent>,
+        recipes: &[Recipe],
+    ) -> MerchantDecisions {
+        let mut decisions = MerchantDecisions {
+            orders: HashMap::new(),
+            deferred: Vec::new(),
+        };
+        let default_controller = SupplyCurveController;
+
+        for merchant_id in crate::determinism::sorted_merchant_ids(merchants.keys().copied()) {
+            let merchant = &merchants[&merchant_id];
+            let observation = self.observe_merchant(merchant, recipes);
+            let controller: &dyn MerchantController = match self.controllers.get(&merchant_id) {
+                Some(controller) => controller.as_ref(),
+                None => &default_controller,
+            };
+
+            for action in controller.decide(&observation) {
+                match action {
+                    MerchantAction::Trade { settlement, orders } => decisions
+                        .orders
+                        .entry(settlement)
+                        .or_default()
+                        .entry(merchant_id)
+                        .or_default()
+                        .extend(orders),
+                    other => decisions.deferred.push((merchant_id, other)),
+                }
+            }
+        }
+
+        decisions
+    }
+
+    fn observe_merchant<'a>(
+        &'a self,
+        merchant: &'a MerchantAgent,
+        recipes: &'a [Recipe],
+    ) -> MerchantObservation<'a> {
+        let present = crate::determinism::sorted_settlement_ids(
+            self.settlements
+                .iter()
+                .filter(|(_, s)| s.owner_facility_counts.contains_key(&merchant.id))
+                .map(|(id, _)| *id),
+        );
+
+        let settlements = present
+            .into_iter()
+            .filter_map(|id| {
+                let state = &self.settlements[&id];
+                let snapshot = merchant.knowledge.get(id)?;
+                let facilities = crate::determinism::sorted_facility_keys(
+                    state
+                        .facilities
+                        .iter()
+                        .filter(|(_, f)| f.owner == merchant.id)
+                        .map(|(key, _)| key),
+                )
+                .into_iter()
+                .map(|key| {
+                    (
+                        FacilityHandle {
+                            settlement: id,
+                            key,
+                        },
+                        &state.facilities[key],
+                    )
+                })
+                .collect();
+
+                Some(SettlementObservation {
+                    id,
+                    name: &state.info.name,
+                    pop_count: snapshot.pop_count,
+                    price_ema: &snapshot.prices,
+                    wage_ema: &snapshot.wages,
+                    facilities,
+                })
+            })
+            .collect();
+
+        let vessels = crate::determinism::sorted_vessel_ids(
+            self.vessels
+                .values()
+                .filter(|v| v.owner == merchant.id)
+                .map(|v| v.id),
+        )
+        .into_iter()
+        .map(|id| &self.vessels[&id])
+        .collect();
+
+        MerchantObservation {
+            tick: self.tick,
+            merchant,
+            settlements,
+            vessels,
+            recipes,
+        }
+    }
+
+    /// Apply structural controller actions between ticks. Actions a merchant
+    /// is not entitled to (someone else's facility, unaffordable builds) are
+    /// dropped.
+    pub(super) fn apply_merchant_actions(&mut self, actions: Vec<(MerchantId, MerchantAction)>) {
+        for (merchant_id, action) in actions {
+            let applied = match &action {
+                MerchantAction::Trade { .. } => false,
+                MerchantAction::SetRecipePriorities {
+                    facility,
+                    priorities,
+                } => match self.facility_mut(*facility) {
+                    Some(f) if f.owner == merchant_id => {
+                        f.recipe_priorities = priorities.clone();
+                        true
+                    }
+                    _ => false,
+                },
+                MerchantAction::Build {
+                    facility_type,
+                    settlement,
+                } => match self.facility_def(*facility_type).cloned() {
+                    Some(def) => self.build_facility(&def, *settlement, merchant_id).is_ok(),
+                    None => false,
+                },
+                MerchantAction::Demolish { facility } => {
+                    self.facility(*facility)
+                        .is_some_and(|f| f.owner == merchant_id)
+                        && self.demolish_facility(*facility).is_ok()
+                }
+                MerchantAction::Deposit { facility, amount } => {
+                    self.facility(*facility)
+                        .is_some_and(|f| f.owner == merchant_id)
+                        && self.deposit_to_facility(*facility, *amount).is_ok()
+                }
+                MerchantAction::Withdraw { facility, amount } => {
+                    self.facility(*facility)
+                        .is_some_and(|f| f.owner == merchant_id)
+                        && self.withdraw_from_facility(*facility, *amount).is_ok()
+                }
+                MerchantAction::Dispatch { vessel, to, cargo } => {
+                    self.apply_dispatch(merchant_id, *vessel, *to, cargo)
+                }
+                MerchantAction::SendCourier { from, to } => {
+                    self.send_courier(merchant_id, *from, *to).is_ok()
+                }
+                MerchantAction::RepayLoan { loan, amount } => {
+                    self.loan(*loan).is_some_and(|l| l.borrower == merchant_id)
+                        && self.repay_loan(*loan, *amount).is_ok()
+                }
+            };
+
+            #[cfg(feature = "instrument")]
+            tracing::info!(
+                target: "merchant_action",
+                tick = self.tick,
+                merchant_id = merchant_id.0,
+                action = action_name(&action),
+                applied = applied,
+            );
+            #[cfg(not(feature = "instrument"))]
+            let _ = applied;
+        }
+    }
+
+    fn apply_dispatch(
+        &mut self,
+        merchant_id: MerchantId,
+        vessel: VesselId,
+        to: SettlementId,
+        cargo: &[(GoodId, Quantity)],
+    ) -> bool {
+        if self.vessel(vessel).is_none_or(|v| v.owner != merchant_id) {
+            return false;
+        }
+        let mut loaded = Vec::new();
+        for &(good, qty) in cargo {
+            match self.load_vessel(vessel, good, qty) {
+                Ok(q) => loaded.push((good, q)),
+                Err(_) => break,
+            }
+        }
+        if self.dispatch_vessel(vessel, to).is_ok() {
+            return true;
+        }
+        for (good, qty) in loaded {
+            let _ = self.unload_vessel(vessel, good, qty);
+        }
+        false
+    }
+}
+
+#[cfg(feature = "instrument")]
+fn action_name(action: &MerchantAction) -> &'static str {
+    match action {
+        MerchantAction::Trade { .. } => "trade",
+        MerchantAction::SetRecipePriorities { .. } => "set_recipe_priorities",
+        MerchantAction::Build { .. } => "build",
+        MerchantAction::Demolish { .. } => "demolish",
+        MerchantAction::Deposit { .. } => "deposit",
+        MerchantAction::Withdraw { .. } => "withdraw",
+        MerchantAction::Dispatch { .. } => "dispatch",
+        MerchantAction::SendCourier { .. } => "send_courier",
+        MerchantAction::RepayLoan { .. } => "repay_loan",
+    }
+}
@@ -0,0 +1,222 @@
+use rand::Rng;
+
+use super::mortality_phase::journal_handover;
+use super::*;
+use crate::migration::{Prospects, migration_probability};
+
+/// Settlement-wide averages a prospective migrant judges a place by.
+struct SettlementOutlook {
+    mean_income: f64,
+    mean_food_satisfaction: f64,
+}
+
+impl World {
+    /// Deliver migrants who have arrived, then let pops decide whether to
+    /// leave. Departing pops pay their fare up front and are out of the
+    /// labor market until they arrive.
+    pub(super) fn run_migration_phase(&mut self) {
+        let Some(config) = self.migration.clone() else {
+            return;
+        };
+
+        self.advance_migrants();
+
+        let settlement_ids =
+            crate::determinism::sorted_settlement_ids(self.settlements.keys().copied());
+        let outlooks: HashMap<SettlementId, SettlementOutlook> = settlement_ids
+            .iter()
+            .filter_map(|id| {
+                let pops = &self.settlements[id].pops;
+                if pops.is_empty() {
+                    return None;
+                }
+                let n = pops.len() as f64;
+                let keys = crate::determinism::sorted_pop_keys(pops.keys());
+                Some((
+                    *id,
+                    SettlementOutlook {
+                        mean_income: keys.iter().map(|k| pops[*k].income_ema).sum::<f64>() / n,
+                        mean_food_satisfaction: keys
+                            .iter()
+                            .map(|k| food_satisfaction(&pops[*k]))
+                            .sum::<f64>()
+                            / n,
+                    },
+                ))
+            })
+            .collect();
+
+        let graph = self.route_graph();
+        let mut rng = self.rng.clone();
+        let mut departures: Vec<(SettlementId, PopKey, SettlementId, u32)> = Vec::new();
+
+        for &from in &settlement_ids {
+            let destinations: Vec<(SettlementId, u32)> = graph
+                .reachable_within(from, config.max_distance)
+                .into_iter()
+                .filter(|(to, _)| *to != from && outlooks.contains_key(to))
+                .collect();
+            if destinations.is_empty() {
+                continue;
+            }
+
+            let home = &self.settlements[&from];
+            for pop_key in crate::determinism::sorted_pop_keys(home.pops.keys()) {
+                let pop = &home.pops[pop_key];
+                let here = Prospects::new(
+                    pop.income_ema,
+                    basket_cost(pop, &home.price_ema),
+                    food_satisfaction(pop),
+                );
+
+                let mut best: Option<(SettlementId, u32, f64)> = None;
+                for &(to, distance) in &destinations {
+                    if pop.currency < config.fare_per_distance * f64::from(distance) {
+                        continue;
+                    }
+                    let outlook = &outlooks[&to];
+                    let there = Prospects::new(
+                        outlook.mean_income,
+                        basket_cost(pop, &self.settlements[&to].price_ema),
+                        outlook.mean_food_satisfaction,
+                    );
+                    let gain = here.gain_to(&there);
+                    if best.is_none_or(|(_, _, g)| gain > g) {
+                        best = Some((to, distance, gain));
+                    }
+                }
+
+                let Some((to, distance, gain)) = best else {
+                    continue;
+                };
+                let p = migration_probability(gain, &config);
+                if p > 0.0 && rng.random::<f64>() < p {
+                    departures.push((from, pop_key, to, distance));
+                }
+            }
+        }
+
+        self.rng = rng;
+
+        let mut departed: HashMap<(SettlementId, SettlementId), (u32, f64)> = HashMap::new();
+        for (from, pop_key, to, distance) in departures {
+            let settlement = self.settlements.get_mut(&from).expect("id from map");
+            let Some(mut pop) = settlement.remove_pop(pop_key) else {
+                continue;
+            };
+            let fare = config.fare_per_distance * f64::from(distance);
+            pop.currency -= fare;
+            pop.employed_at = None;
+            pop.employed_skill = None;
+            self.transport_totals.fares_paid += fare;
+            if let Some(ledger) = self.ledger.as_mut() {
+                ledger.transfer(
+                    self.tick,
+                    Phase::Migration,
+                    Account::Pop(PopHandle {
+                        settlement: from,
+                        key: pop_key,
+                    }),
+                    Account::Transport,
+                    None,
+                    fare,
+                );
+            }
+            *self.migration_flows.entry((from, to)).or_insert(0) += 1;
+
+            let entry = departed.entry((from, to)).or_insert((0, 0.0));
+            entry.0 += 1;
+            entry.1 += pop.currency;
+
+            self.migrants.push(Migrant {
+                pop,
+                from,
+                from_key: pop_key,
+                to,
+                ticks_remaining: distance.max(1),
+            });
+        }
+
+        #[cfg(feature = "instrument")]
+        emit_migration_flows(self.tick, "depart", departed);
+        #[cfg(not(feature = "instrument"))]
+        let _ = departed;
+    }
+
+    /// Move migrants one tick along and settle those who have arrived.
+    fn advance_migrants(&mut self) {
+        let mut arrived: HashMap<(SettlementId, SettlementId), (u32, f64)> = HashMap::new();
+        let mut on_the_road = Vec::with_capacity(self.migrants.len());
+        for mut migrant in std::mem::take(&mut self.migrants) {
+            migrant.ticks_remaining = migrant.ticks_remaining.saturating_sub(1);
+            let Some(settlement) = self
+                .settlements
+                .get_mut(&migrant.to)
+                .filter(|_| migrant.ticks_remaining == 0)
+            else {
+                on_the_road.push(migrant);
+                continue;
+            };
+            let entry = arrived
+                .entry((migrant.from, migrant.to))
+                .or_insert((0, 0.0));
+            entry.0 += 1;
+            entry.1 += migrant.pop.currency;
+            let key = settlement.pops.insert(migrant.pop);
+            if let Some(ledger) = self.ledger.as_mut() {
+                let from = PopHandle {
+                    settlement: migrant.from,
+                    key: migrant.from_key,
+                };
+                let to = PopHandle {
+                    settlement: migrant.to,
+                    key,
+                };
+                let pop = &settlement.pops[key];
+                journal_handover(ledger, self.tick, Phase::Migration, from, to, pop, 1.0);
+            }
+        }
+        self.migrants = on_the_road;
+
+        #[cfg(feature = "instrument")]
+        emit_migration_flows(self.tick, "arrive", arrived);
+        #[cfg(not(feature = "instrument"))]
+        let _ = arrived;
+    }
+}
+
+fn food_satisfaction(pop: &Pop) -> f64 {
+    pop.need_satisfaction.get("food").copied().unwrap_or(0.0)
+}
+
+/// Cost of a pop's desired consumption at a settlement's prices.
+fn basket_cost(pop: &Pop, prices: &HashMap<GoodId, Price>) -> f64 {
+    let mut goods: Vec<GoodId> = pop.desired_consumption_ema.keys().copied().collect();
+    goods.sort_unstable();
+    goods
+        .into_iter()
+        .map(|good| pop.desired_consumption_ema[&good] * prices.get(&good).copied().unwrap_or(0.0))
+        .sum()
+}
+
+/// One event per origin/destination pair, in pair order.
+#[cfg(feature = "instrument")]
+fn emit_migration_flows(
+    tick: u64,
+    event: &'static str,
+    flows: HashMap<(SettlementId, SettlementId), (u32, f64)>,
+) {
+    let mut flows: Vec<_> = flows.into_iter().collect();
+    flows.sort_by_key(|((from, to), _)| (from.0, to.0));
+    for ((from, to), (pops, currency)) in flows {
+        tracing::info!(
+            target: "migration",
+            tick = tick,
+            event = event,
+            from_settlement = from.0,
+            to_settlement = to.0,
+            pops = pops,
+            currency = currency,
+        );
+    }
+}
@@ -1,11 +1,8 @@
 use super::*;
+use crate::labor::inherited_skills;
 
 impl World {
     pub(super) fn run_mortality_phase_settlement(&mut self, settlement_id: SettlementId) {
-        if self.tick <= self.mortality_grace_ticks {
-            return;
-        }
-
         let Some(settlement) = self.settlements.get_mut(&settlement_id) else {
             return;
         };
@@ -18,6 +15,16 @@ impl World {
             return;
         }
 
+        // Intake history accumulates through the grace period too
+        for pop in settlement.pops.values_mut() {
+            let food_satisfaction = pop.need_satisfaction.get("food").copied().unwrap_or(0.0);
+            pop.food_intake.observe(food_satisfaction, &self.demography);
+        }
+
+        if self.tick <= self.mortality_grace_ticks {
+            return;
+        }
+
         let mut rng = self.rng.clone();
 
         let pop_keys = crate::determinism::sorted_pop_keys(settlement.pops.keys());
@@ -28,7 +35,7 @@ impl World {
                 continue;
             };
             let food_satisfaction = pop.need_satisfaction.get("food").copied().unwrap_or(0.0);
-            let outcome = check_mortality(&mut rng, food_satisfaction);
+            let outcome = check_mortality(&mut rng, &pop.food_intake, &self.demography);
             outcomes.push((*pop_key, outcome, food_satisfaction));
         }
 
@@ -39,22 +46,31 @@ impl World {
                 MortalityOutcome::Grows => "grows",
                 MortalityOutcome::Survives => "survives",
             };
-            let death_prob = crate::mortality::death_probability(*food_satisfaction);
-            let growth_prob = crate::mortality::growth_probability(*food_satisfaction);
+            let intake = &settlement.pops[*pop_key].food_intake;
+            let death_prob = self.demography.death_hazard(intake);
+            let growth_prob = self.demography.growth_hazard(intake);
             tracing::info!(
                 target: "mortality",
                 tick = self.tick,
                 pop_id = pop_key_u64(*pop_key),
                 settlement_id = settlement_id.0,
                 food_satisfaction = *food_satisfaction,
+                intake_ema = intake.ema,
+                deficit_ticks = intake.deficit_ticks,
                 death_prob = death_prob,
                 growth_prob = growth_prob,
                 outcome = outcome_str,
             );
         }
 
+        let skill_defs: HashMap<SkillId, SkillDef> =
+            self.skill_defs.iter().map(|s| (s.id, s.clone())).collect();
         let mut dead_pops: Vec<PopKey> = Vec::new();
-        let mut children: Vec<Pop> = Vec::new();
+        let mut children: Vec<(PopKey, Pop)> = Vec::new();
+        let handle = |key| PopHandle {
+            settlement: settlement_id,
+            key,
+        };
 
         for (pop_key, outcome, _food_satisfaction) in outcomes {
             match outcome {
@@ -72,9 +88,15 @@ impl World {
                             }
                             *qty = child_share;
                         }
+                        if self.skill_learning.is_some() {
+                            child.skills = inherited_skills(&child.skills, &skill_defs);
+                            child.skill_experience.clear();
+                        }
                         child.employed_at = None;
                         child.employed_skill = None;
-                        children.push(child);
+                        child.food_intake.deficit_ticks = 0;
+                        child.food_intake.surplus_ticks = 0;
+                        children.push((pop_key, child));
                     }
                 }
                 MortalityOutcome::Survives => {}
@@ -82,26 +104,10 @@ impl World {
         }
 
         for pop_key in dead_pops {
-            let Some(pop) = settlement.pops.remove(pop_key) else {
+            let Some(pop) = settlement.remove_pop(pop_key) else {
                 continue;
             };
 
-            settlement.subsistence_queue.retain(|k| *k != pop_key);
-
-            if let Some(facility_key) = pop.employed_at {
-                debug_assert!(
-                    pop.employed_skill.is_some(),
-                    "pop has employed_at but no employed_skill"
-                );
-                if let Some(skill) = pop.employed_skill
-                    && let Some(facility) = settlement.facilities.get_mut(facility_key)
-                {
-                    if let Some(count) = facility.workers.get_mut(&skill) {
-                        *count = count.saturating_sub(1);
-                    }
-                }
-            }
-
             use rand::seq::SliceRandom;
             let mut heirs = crate::determinism::sorted_pop_keys(settlement.pops.keys());
             heirs.shuffle(&mut rng);
@@ -109,6 +115,10 @@ impl World {
             let n = heirs.len();
             if n > 0 {
                 let share = 1.0 / n as f64;
+                settlement.flows.inherited_currency += pop.currency;
+                for (good, qty) in &pop.stocks {
+                    *settlement.flows.inherited_goods.entry(*good).or_insert(0.0) += qty;
+                }
                 for heir_key in heirs {
                     if let Some(heir) = settlement.pops.get_mut(heir_key) {
                         heir.currency += pop.currency * share;
@@ -116,14 +126,69 @@ impl World {
                             *heir.stocks.entry(*good).or_insert(0.0) += qty * share;
                         }
                     }
+                    if let Some(ledger) = self.ledger.as_mut() {
+                        let (from, to) = (handle(pop_key), handle(heir_key));
+                        journal_handover(
+                            ledger,
+                            self.tick,
+                            Phase::Mortality,
+                            from,
+                            to,
+                            &pop,
+                            share,
+                        );
+                    }
+                }
+            } else {
+                settlement.flows.currency_lost_with_dead += pop.currency;
+                for (good, qty) in &pop.stocks {
+                    *settlement.flows.lost_with_dead.entry(*good).or_insert(0.0) += qty;
+                }
+                if let Some(ledger) = self.ledger.as_mut() {
+                    let mut stocks: Vec<(GoodId, Quantity)> =
+                        pop.stocks.iter().map(|(g, q)| (*g, *q)).collect();
+                    stocks.sort_by_key(|(good, _)| *good);
+                    let (from, to) = (
+                        Account::Pop(handle(pop_key)),
+                        Account::Unclaimed(settlement_id),
+                    );
+                    ledger.transfer(self.tick, Phase::Mortality, from, to, None, pop.currency);
+                    for (good, qty) in stocks {
+                        ledger.transfer(self.tick, Phase::Mortality, from, to, Some(good), qty);
+                    }
                 }
             }
         }
 
-        for child in children {
-            settlement.pops.insert(child);
+        for (parent_key, child) in children {
+            let child_key = settlement.pops.insert(child);
+            if let Some(ledger) = self.ledger.as_mut() {
+                let child = &settlement.pops[child_key];
+                let (from, to) = (handle(parent_key), handle(child_key));
+                journal_handover(ledger, self.tick, Phase::Mortality, from, to, child, 1.0);
+            }
         }
 
         self.rng = rng;
     }
 }
+
+/// Journal `share` of `pop`'s currency and stocks passing between pop
+/// accounts.
+pub(super) fn journal_handover(
+    ledger: &mut Ledger,
+    tick: u64,
+    phase: Phase,
+    from: PopHandle,
+    to: PopHandle,
+    pop: &Pop,
+    share: f64,
+) {
+    let (from, to) = (Account::Pop(from), Account::Pop(to));
+    ledger.transfer(tick, phase, from, to, None, pop.currency * share);
+    let mut stocks: Vec<(GoodId, Quantity)> = pop.stocks.iter().map(|(g, q)| (*g, *q)).collect();
+    stocks.sort_by_key(|(good, _)| *good);
+    for (good, qty) in stocks {
+        ledger.transfer(tick, phase, from, to, Some(good), qty * share);
+    }
+}
@@ -13,6 +13,8 @@ impl World {
         };
 
         let mut production_totals: HashMap<(MerchantId, GoodId), f64> = HashMap::new();
+        // Tools already held by an owner's earlier facilities this tick
+        let mut tools_in_use: HashMap<MerchantId, HashMap<GoodId, Quantity>> = HashMap::new();
         let facility_keys = crate::determinism::sorted_facility_keys(settlement.facilities.keys());
 
         for facility_key in facility_keys {
@@ -39,13 +41,44 @@ impl World {
             let Some(facility) = settlement.facilities.get(facility_key) else {
                 continue;
             };
-            let allocation = allocate_recipes(facility_key, facility, recipes, stockpile);
+            let in_use = tools_in_use.entry(owner_id).or_default();
+            let mut available = stockpile.clone();
+            for (good, qty) in in_use.iter() {
+                available.remove(*good, *qty);
+            }
+            let allocation = allocate_recipes(facility_key, facility, recipes, &available);
 
             let stockpile = merchant
                 .stockpiles
                 .get_mut(&settlement_id)
                 .expect("stockpile must exist");
             let result = execute_production(&allocation, recipes, stockpile, quality_multiplier);
+            // Wear comes out of the tools this facility held
+            for (good, held) in allocation.tools_held(recipes) {
+                let worn = result.tools_worn.get(&good).copied().unwrap_or(0.0);
+                *in_use.entry(good).or_insert(0.0) += held - worn;
+            }
+
+            let mut worn: Vec<(GoodId, f64)> =
+                result.tools_worn.iter().map(|(g, q)| (*g, *q)).collect();
+            worn.sort_by_key(|(g, _)| *g);
+            let depreciation: f64 = worn
+                .iter()
+                .map(|(g, q)| q * settlement.price_ema.get(g).copied().unwrap_or(1.0))
+                .sum();
+            if let Some(facility) = settlement.facilities.get_mut(facility_key) {
+                facility.income.depreciation += depreciation;
+                facility.produced = result.outputs_produced.clone();
+                facility.used = result.inputs_consumed.clone();
+            }
+
+            let flows = &mut settlement.flows;
+            for (good_id, qty) in &result.outputs_produced {
+                *flows.produced.entry(*good_id).or_insert(0.0) += qty;
+            }
+            for (good_id, qty) in result.inputs_consumed.iter().chain(&result.tools_worn) {
+                *flows.used_in_production.entry(*good_id).or_insert(0.0) += qty;
+            }
 
             for (&good_id, &qty) in &result.outputs_produced {
                 if qty > 0.0 {
@@ -0,0 +1,111 @@
+use super::*;
+use crate::labor::{advance_skills, training_target};
+
+impl World {
+    /// Let pops learn from this tick's work, pay employed masters for
+    /// training while out of work, and lose skills they no longer use.
+    pub(super) fn run_skill_phase_settlement(&mut self, settlement_id: SettlementId) {
+        let Some(config) = self.skill_learning.clone() else {
+            return;
+        };
+        let Some(settlement) = self.settlements.get_mut(&settlement_id) else {
+            return;
+        };
+        let defs: HashMap<SkillId, SkillDef> =
+            self.skill_defs.iter().map(|s| (s.id, s.clone())).collect();
+
+        let pop_keys = crate::determinism::sorted_pop_keys(settlement.pops.keys());
+        let mut masters: HashMap<SkillId, Vec<PopKey>> = HashMap::new();
+        for pop_key in &pop_keys {
+            let pop = &settlement.pops[*pop_key];
+            if let (Some(_), Some(skill)) = (pop.employed_at, pop.employed_skill) {
+                masters.entry(skill).or_default().push(*pop_key);
+            }
+        }
+
+        let mut fees: HashMap<SkillId, (u32, f64)> = HashMap::new();
+        for pop_key in &pop_keys {
+            let pop = &settlement.pops[*pop_key];
+            let training = if !pop.is_employed() && pop.currency >= config.training_fee {
+                training_target(pop, &defs, &settlement.wage_ema, |s| {
+                    masters.contains_key(&s)
+                })
+            } else {
+                None
+            };
+
+            let pop = &mut settlement.pops[*pop_key];
+            if let Some(skill) = training {
+                pop.currency -= config.training_fee;
+                let entry = fees.entry(skill).or_insert((0, 0.0));
+                entry.0 += 1;
+                entry.1 += config.training_fee;
+                if let Some(ledger) = self.ledger.as_mut() {
+                    let teachers = &masters[&skill];
+                    let share = config.training_fee / teachers.len() as f64;
+                    let pop_account = |key| {
+                        Account::Pop(PopHandle {
+                            settlement: settlement_id,
+                            key,
+                        })
+                    };
+                    for teacher in teachers {
+                        ledger.transfer(
+                            self.tick,
+                            Phase::Skills,
+                            pop_account(*pop_key),
+                            pop_account(*teacher),
+                            None,
+                            share,
+                        );
+                    }
+                }
+            }
+            let changes = advance_skills(pop, &defs, training, &config);
+
+            #[cfg(feature = "instrument")]
+            for (event, skills) in [
+                ("learned", &changes.learned),
+                ("forgotten", &changes.forgotten),
+            ] {
+                for skill in skills {
+                    tracing::info!(
+                        target: "skills",
+                        tick = self.tick,
+                        settlement_id = settlement_id.0,
+                        pop_id = pop_key_u64(*pop_key),
+                        skill_id = skill.0,
+                        event = event,
+                    );
+                }
+            }
+            #[cfg(not(feature = "instrument"))]
+            let _ = changes;
+        }
+
+        // Fees go to the masters of each skill, so training moves currency
+        // between pops rather than out of the economy
+        let mut trained: Vec<(SkillId, (u32, f64))> = fees.into_iter().collect();
+        trained.sort_by_key(|(skill, _)| skill.0);
+        for (skill, (trainees, total)) in trained {
+            let teachers = &masters[&skill];
+            let share = total / teachers.len() as f64;
+            for teacher in teachers {
+                settlement.pops[*teacher].currency += share;
+            }
+
+            #[cfg(feature = "instrument")]
+            tracing::info!(
+                target: "skills",
+                tick = self.tick,
+                settlement_id = settlement_id.0,
+                skill_id = skill.0,
+                event = "trained",
+                trainees = trainees,
+                fees = total,
+            );
+            #[cfg(not(feature = "instrument"))]
+            let _ = trainees;
+        }
+    }
+}
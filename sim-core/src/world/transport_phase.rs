@@ -0,0 +1,349 @@
+use super::*;
+use crate::transport::{
+    PortDependence, RouteIncident, TransportError, Vessel, VesselKind, VesselLocation,
+    per_tick_risk, roll_incident,
+};
+
+impl World {
+    pub fn add_vessel(
+        &mut self,
+        kind: VesselKind,
+        settlement_id: SettlementId,
+        owner_id: MerchantId,
+    ) -> Option<VesselId> {
+        if !self.merchants.contains_key(&owner_id) || !self.settlements.contains_key(&settlement_id)
+        {
+            return None;
+        }
+        let id = VesselId::new(self.next_vessel_id);
+        self.next_vessel_id += 1;
+        self.vessels
+            .insert(id, Vessel::new(id, kind, owner_id, settlement_id));
+        Some(id)
+    }
+
+    pub fn vessel(&self, id: VesselId) -> Option<&Vessel> {
+        self.vessels.get(&id)
+    }
+
+    pub fn vessel_mut(&mut self, id: VesselId) -> Option<&mut Vessel> {
+        self.vessels.get_mut(&id)
+    }
+
+    /// Move goods from the owner's stockpile at the vessel's berth into its
+    /// hold, limited by stock on hand and free capacity. Returns the quantity
+    /// actually loaded.
+    pub fn load_vessel(
+        &mut self,
+        id: VesselId,
+        good: GoodId,
+        quantity: Quantity,
+    ) -> Result<Quantity, TransportError> {
+        let vessel = self
+            .vessels
+            .get_mut(&id)
+            .ok_or(TransportError::UnknownVessel(id))?;
+        let at = vessel.docked_at().ok_or(TransportError::NotDocked(id))?;
+        let owner = self
+            .merchants
+            .get_mut(&vessel.owner)
+            .ok_or(TransportError::UnknownMerchant(vessel.owner))?;
+
+        let wanted = quantity.max(0.0).min(vessel.free_capacity());
+        let loaded = owner.stockpile_at(at).remove(good, wanted);
+        if loaded > 0.0 {
+            vessel.cargo.add(good, loaded);
+        }
+        Ok(loaded)
+    }
+
+    /// Move goods from the hold into the owner's stockpile at the berth.
+    pub fn unload_vessel(
+        &mut self,
+        id: VesselId,
+        good: GoodId,
+        quantity: Quantity,
+    ) -> Result<Quantity, TransportError> {
+        let vessel = self
+            .vessels
+            .get_mut(&id)
+            .ok_or(TransportError::UnknownVessel(id))?;
+        let at = vessel.docked_at().ok_or(TransportError::NotDocked(id))?;
+        let owner = self
+            .merchants
+            .get_mut(&vessel.owner)
+            .ok_or(TransportError::UnknownMerchant(vessel.owner))?;
+
+        let unloaded = vessel.cargo.remove(good, quantity.max(0.0));
+        if unloaded > 0.0 {
+            owner.stockpile_at(at).add(good, unloaded);
+        }
+        Ok(unloaded)
+    }
+
+    /// Send a docked vessel along a direct route of its kind. The owner pays
+    /// the route's `transport_cost` per unit of cargo up front; the cargo is
+    /// unloaded into the owner's stockpile on arrival.
+    pub fn dispatch_vessel(
+        &mut self,
+        id: VesselId,
+        to: SettlementId,
+    ) -> Result<(), TransportError> {
+        let vessel = self
+            .vessels
+            .get(&id)
+            .ok_or(TransportError::UnknownVessel(id))?;
+        let from = vessel.docked_at().ok_or(TransportError::NotDocked(id))?;
+        if !self.settlements.contains_key(&to) {
+            return Err(TransportError::UnknownSettlement(to));
+        }
+        let kind = vessel.kind.route_kind();
+        let route = self
+            .routes
+            .iter()
+            .find(|r| r.kind == kind && r.connects(from, to))
+            .ok_or(TransportError::NoRoute { from, to, kind })?;
+
+        let freight = route.transport_cost * vessel.cargo.total();
+        let ticks_remaining = vessel.transit_ticks(route.distance);
+        let news = self.market_snapshot(from);
+        let owner = self
+            .merchants
+            .get_mut(&vessel.owner)
+            .ok_or(TransportError::UnknownMerchant(vessel.owner))?;
+        if owner.currency < freight {
+            return Err(TransportError::InsufficientFunds {
+                required: freight,
+                available: owner.currency,
+            });
+        }
+        owner.currency -= freight;
+        self.transport_totals.freight_paid += freight;
+        if let Some(ledger) = self.ledger.as_mut() {
+            ledger.transfer(
+                self.tick,
+                Phase::Actions,
+                Account::Merchant(vessel.owner),
+                Account::Transport,
+                None,
+                freight,
+            );
+        }
+
+        let vessel = self.vessels.get_mut(&id).expect("vessel checked above");
+        vessel.location = VesselLocation::InTransit {
+            from,
+            to,
+            ticks_remaining,
+        };
+        vessel.news = news;
+
+        #[cfg(feature = "instrument")]
+        tracing::info!(
+            target: "vessel",
+            tick = self.tick,
+            vessel_id = id.0,
+            merchant_id = vessel.owner.0,
+            event = "depart",
+            from_settlement = from.0,
+            to_settlement = to.0,
+            cargo = vessel.cargo.total(),
+            freight = freight,
+        );
+
+        Ok(())
+    }
+
+    /// Advance vessels one tick: charge upkeep, roll route incidents for
+    /// those in transit, move them and unload arrivals into their owner's
+    /// stockpile. Arriving vessels bring their owner news of the port they
+    /// left and a fresh look at the one they reached. Couriers advance too.
+    pub(super) fn run_transport_phase(
+        &mut self,
+        merchants: &mut HashMap<MerchantId, MerchantAgent>,
+    ) {
+        let mut arrivals = Vec::new();
+        for id in crate::determinism::sorted_vessel_ids(self.vessels.keys().copied()) {
+            let vessel = self.vessels.get_mut(&id).expect("id from vessel map");
+            let Some(owner) = merchants.get_mut(&vessel.owner) else {
+                continue;
+            };
+
+            let upkeep = vessel.upkeep.min(owner.currency.max(0.0));
+            owner.currency -= upkeep;
+            self.transport_totals.upkeep_paid += upkeep;
+            if let Some(ledger) = self.ledger.as_mut() {
+                ledger.transfer(
+                    self.tick,
+                    Phase::Transport,
+                    Account::Merchant(vessel.owner),
+                    Account::Transport,
+                    None,
+                    upkeep,
+                );
+            }
+
+            let VesselLocation::InTransit { from, to, .. } = vessel.location else {
+                continue;
+            };
+
+            let route_kind = vessel.kind.route_kind();
+            let tick_risk = self
+                .routes
+                .iter()
+                .find(|r| r.kind == route_kind && r.connects(from, to))
+                .map(|r| per_tick_risk(r.risk, vessel.transit_ticks(r.distance)))
+                .unwrap_or(0.0);
+            if let Some(incident) = roll_incident(&mut self.rng, route_kind, tick_risk) {
+                let lost = apply_incident(vessel, incident);
+                let spoiled = matches!(incident, RouteIncident::Spoilage { .. });
+                let totals = &mut self.transport_totals;
+                for &(good, qty) in &lost {
+                    *totals.cargo_lost.entry(good).or_insert(0.0) += qty;
+                    if spoiled {
+                        *totals.cargo_spoiled.entry(good).or_insert(0.0) += qty;
+                    }
+                }
+
+                #[cfg(feature = "instrument")]
+                tracing::info!(
+                    target: "route_incident",
+                    tick = self.tick,
+                    vessel_id = id.0,
+                    merchant_id = vessel.owner.0,
+                    from_settlement = from.0,
+                    to_settlement = to.0,
+                    incident = incident.name(),
+                    quantity_lost = lost.iter().map(|(_, q)| q).sum::<f64>(),
+                    delay_ticks = match incident {
+                        RouteIncident::Delay { ticks } => ticks,
+                        _ => 0,
+                    },
+                );
+            }
+
+            let VesselLocation::InTransit {
+                ticks_remaining, ..
+            } = &mut vessel.location
+            else {
+                continue;
+            };
+            *ticks_remaining = ticks_remaining.saturating_sub(1);
+            if *ticks_remaining > 0 {
+                continue;
+            }
+
+            vessel.location = VesselLocation::Docked(to);
+            let from_port = self
+                .settlements
+                .get(&from)
+                .is_some_and(|s| s.info.role().has_outside_access());
+            let cargo = std::mem::take(&mut vessel.cargo);
+            let stockpile = owner.stockpile_at(to);
+            for good in sorted_goods(&cargo) {
+                let qty = cargo.get(good);
+                stockpile.add(good, qty);
+                self.transport_totals
+                    .record_delivery(to, good, qty, from_port);
+            }
+
+            #[cfg(feature = "instrument")]
+            tracing::info!(
+                target: "vessel",
+                tick = self.tick,
+                vessel_id = id.0,
+                merchant_id = vessel.owner.0,
+                event = "arrive",
+                from_settlement = from.0,
+                to_settlement = to.0,
+                cargo = cargo.total(),
+                freight = 0.0,
+            );
+
+            arrivals.push((vessel.owner, to, vessel.news.take()));
+        }
+
+        for (owner, at, news) in arrivals {
+            let fresh = self.capture_market_snapshot(at, merchants);
+            if let Some(merchant) = merchants.get_mut(&owner) {
+                for snapshot in news.into_iter().chain(fresh) {
+                    merchant.knowledge.record(snapshot);
+                }
+            }
+        }
+
+        self.run_courier_phase(merchants);
+    }
+
+    /// Port dependence of every settlement without outside market access,
+    /// sorted by id. Only direct port-to-settlement voyages count as port
+    /// flows; goods relayed through another inland settlement do not.
+    pub fn port_dependence(&self) -> Vec<PortDependence> {
+        let graph = self.route_graph();
+        let is_port = |id: &SettlementId| {
+            self.settlements
+                .get(id)
+                .is_some_and(|s| s.info.role().has_outside_access())
+        };
+        crate::determinism::sorted_settlement_ids(self.settlements.keys().copied())
+            .into_iter()
+            .filter(|id| !is_port(id))
+            .map(|id| {
+                let nearest_port = graph
+                    .reachable_within(id, u32::MAX)
+                    .into_iter()
+                    .find(|(other, _)| is_port(other));
+                let for_settlement = |flows: &HashMap<(SettlementId, GoodId), Quantity>| {
+                    flows
+                        .iter()
+                        .filter(|((s, _), _)| *s == id)
+                        .map(|((_, good), qty)| (*good, *qty))
+                        .collect()
+                };
+                PortDependence {
+                    settlement: id,
+                    nearest_port,
+                    delivered: for_settlement(&self.transport_totals.delivered),
+                    from_ports: for_settlement(&self.transport_totals.delivered_from_ports),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Apply an incident to a vessel in transit, returning the cargo destroyed.
+fn apply_incident(vessel: &mut Vessel, incident: RouteIncident) -> Vec<(GoodId, Quantity)> {
+    match incident {
+        RouteIncident::TotalLoss => {
+            let cargo = std::mem::take(&mut vessel.cargo);
+            sorted_goods(&cargo)
+                .into_iter()
+                .map(|good| (good, cargo.get(good)))
+                .filter(|(_, qty)| *qty > 0.0)
+                .collect()
+        }
+        RouteIncident::Spoilage { fraction } => sorted_goods(&vessel.cargo)
+            .into_iter()
+            .map(|good| {
+                let qty = vessel.cargo.get(good) * fraction;
+                (good, vessel.cargo.remove(good, qty))
+            })
+            .filter(|(_, qty)| *qty > 0.0)
+            .collect(),
+        RouteIncident::Delay { ticks } => {
+            if let VesselLocation::InTransit {
+                ticks_remaining, ..
+            } = &mut vessel.location
+            {
+                *ticks_remaining += ticks;
+            }
+            Vec::new()
+        }
+    }
+}
+
+fn sorted_goods(stockpile: &Stockpile) -> Vec<GoodId> {
+    let mut goods: Vec<GoodId> = stockpile.goods.keys().copied().collect();
+    goods.sort_unstable();
+    goods
+}
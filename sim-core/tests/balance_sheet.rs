@@ -0,0 +1,70 @@
+#[allow(dead_code)]
+mod common;
+
+use common::*;
+use sim_core::{FacilityType, MerchantId, VesselKind, VesselLocation, World, get_facility_def};
+
+#[test]
+fn balance_sheet_marks_holdings_to_local_prices() {
+    let mut world = World::with_seed(1);
+    let port = world.add_settlement("Port", (0.0, 0.0));
+    let town = world.add_settlement("Town", (1.0, 0.0));
+    let merchant = world.add_merchant();
+    world.get_merchant_mut(merchant).unwrap().currency = 300.0;
+    world
+        .settlements
+        .get_mut(&port)
+        .unwrap()
+        .price_ema
+        .insert(GRAIN, 2.0);
+    world
+        .settlements
+        .get_mut(&town)
+        .unwrap()
+        .price_ema
+        .insert(GRAIN, 5.0);
+
+    world
+        .get_merchant_mut(merchant)
+        .unwrap()
+        .stockpile_at(port)
+        .add(GRAIN, 10.0);
+    let farm = world
+        .add_facility(FacilityType::Farm, town, merchant)
+        .unwrap();
+    world.deposit_to_facility(farm, 50.0).unwrap();
+    world
+        .facility_mut(farm)
+        .unwrap()
+        .lifetime_income
+        .amortization = 20.0;
+
+    let vessel = world
+        .add_vessel(VesselKind::Caravan, port, merchant)
+        .unwrap();
+    {
+        let vessel = world.vessels.get_mut(&vessel).unwrap();
+        vessel.cargo.add(GRAIN, 4.0);
+        vessel.location = VesselLocation::InTransit {
+            from: port,
+            to: town,
+            ticks_remaining: 1,
+        };
+    }
+
+    let sheet = world.balance_sheet(merchant).unwrap();
+    let farm_cost = get_facility_def(FacilityType::Farm)
+        .unwrap()
+        .construction_cost;
+    assert_eq!(sheet.cash, 250.0);
+    assert_eq!(sheet.position(port).unwrap().inventory, 20.0);
+    let at_town = sheet.position(town).unwrap();
+    assert_eq!(at_town.facility_cash, 50.0);
+    assert_eq!(at_town.facility_book_value, farm_cost - 20.0);
+    assert_eq!(sheet.in_transit, 20.0);
+    assert_eq!(
+        sheet.net_worth(),
+        250.0 + 20.0 + 50.0 + farm_cost - 20.0 + 20.0
+    );
+    assert!(world.balance_sheet(MerchantId(99)).is_none());
+}
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use sim_core::{
+    World,
     labor::SkillId,
     needs::{Need, UtilityCurve},
     production::{FacilityType, Recipe, RecipeId},
@@ -186,3 +187,12 @@ pub fn make_grain_recipe(production_rate: f64) -> Recipe {
         .with_worker(LABORER, 1)
         .with_output(GRAIN, production_rate)
 }
+
+/// Run `ticks` ticks on the grain goods and food need with `recipes`.
+pub fn run_ticks(world: &mut World, ticks: usize, recipes: &[Recipe]) {
+    let good_profiles = make_grain_profile();
+    let needs = make_food_need(1.0);
+    for _ in 0..ticks {
+        world.run_tick(&good_profiles, &needs, recipes);
+    }
+}
@@ -0,0 +1,134 @@
+use sim_core::{
+    FacilityError, FacilityType, MerchantId, ResourceQuality, ResourceSlot, ResourceType,
+    SettlementId, World, get_facility_def,
+};
+
+#[allow(dead_code)]
+mod common;
+use common::*;
+
+fn world_with_land(slots: usize) -> (World, SettlementId, MerchantId) {
+    let mut world = World::with_seed(1);
+    let town = world.add_settlement("Town", (0.0, 0.0));
+    let info = world.get_settlement_mut(town).unwrap();
+    for _ in 0..slots {
+        info.resource_slots
+            .push(ResourceSlot::new(ResourceType::Land, ResourceQuality::Rich));
+    }
+    let merchant = world.add_merchant();
+    world.get_merchant_mut(merchant).unwrap().currency = 1_000.0;
+    (world, town, merchant)
+}
+
+#[test]
+fn build_charges_owner_and_claims_slot() {
+    let (mut world, town, merchant) = world_with_land(1);
+    let farm = get_facility_def(FacilityType::Farm).unwrap();
+
+    let handle = world.build_facility(&farm, town, merchant).unwrap();
+
+    assert_eq!(
+        world.get_merchant(merchant).unwrap().currency,
+        1_000.0 - farm.construction_cost
+    );
+    let facility = world.facility(handle).unwrap();
+    assert_eq!(facility.capacity, farm.base_capacity);
+    assert_eq!(facility.resource_slot_index, Some(0));
+    let info = world.get_settlement(town).unwrap();
+    assert_eq!(info.resource_slots[0].claimed_by, Some(handle.key));
+    assert_eq!(world.settlements[&town].owner_facility_counts[&merchant], 1);
+
+    // The only land slot is taken now.
+    assert_eq!(
+        world.build_facility(&farm, town, merchant),
+        Err(FacilityError::NoResourceSlot(ResourceType::Land))
+    );
+}
+
+#[test]
+fn build_fails_without_funds_and_changes_nothing() {
+    let (mut world, town, merchant) = world_with_land(1);
+    world.get_merchant_mut(merchant).unwrap().currency = 10.0;
+    let farm = get_facility_def(FacilityType::Farm).unwrap();
+
+    let err = world.build_facility(&farm, town, merchant).unwrap_err();
+    assert!(matches!(err, FacilityError::InsufficientFunds { .. }));
+    assert_eq!(world.get_merchant(merchant).unwrap().currency, 10.0);
+    assert!(world.settlements[&town].facilities.is_empty());
+    assert!(world.get_settlement(town).unwrap().resource_slots[0].is_available());
+}
+
+#[test]
+fn demolish_refunds_salvage_and_frees_workers() {
+    let (mut world, town, merchant) = world_with_land(1);
+    let farm = get_facility_def(FacilityType::Farm).unwrap();
+    let handle = world.build_facility(&farm, town, merchant).unwrap();
+
+    let pop = world.add_pop(town).unwrap();
+    {
+        let pop = world.pop_mut(pop).unwrap();
+        pop.skills.insert(LABORER);
+        pop.employed_at = Some(handle.key);
+        pop.employed_skill = Some(LABORER);
+    }
+    world.facility_mut(handle).unwrap().currency = 5.0;
+
+    let refund = world.demolish_facility(handle).unwrap();
+    assert_eq!(refund, farm.construction_cost * farm.salvage_fraction + 5.0);
+    assert_eq!(
+        world.get_merchant(merchant).unwrap().currency,
+        1_000.0 - farm.construction_cost + refund
+    );
+
+    let state = &world.settlements[&town];
+    assert!(state.facilities.is_empty());
+    assert!(state.facility_bid_states.is_empty());
+    assert!(!state.owner_facility_counts.contains_key(&merchant));
+    assert!(state.info.resource_slots[0].is_available());
+    assert!(
+        world
+            .get_merchant(merchant)
+            .unwrap()
+            .owned_facilities
+            .is_empty()
+    );
+    let pop = world.pop(pop).unwrap();
+    assert_eq!(pop.employed_at, None);
+    assert_eq!(pop.employed_skill, None);
+
+    assert_eq!(
+        world.demolish_facility(handle),
+        Err(FacilityError::UnknownFacility(handle))
+    );
+}
+
+#[test]
+fn owner_deposits_and_withdraws_treasury() {
+    let (mut world, town, merchant) = world_with_land(1);
+    let farm = get_facility_def(FacilityType::Farm).unwrap();
+    let handle = world.build_facility(&farm, town, merchant).unwrap();
+    let owner_cash = world.get_merchant(merchant).unwrap().currency;
+
+    world.deposit_to_facility(handle, 100.0).unwrap();
+    assert_eq!(world.facility(handle).unwrap().currency, 100.0);
+    assert_eq!(
+        world.get_merchant(merchant).unwrap().currency,
+        owner_cash - 100.0
+    );
+
+    assert!(matches!(
+        world.withdraw_from_facility(handle, 150.0),
+        Err(FacilityError::InsufficientFunds { .. })
+    ));
+    assert_eq!(
+        world.deposit_to_facility(handle, -1.0),
+        Err(FacilityError::InvalidAmount(-1.0))
+    );
+
+    world.withdraw_from_facility(handle, 40.0).unwrap();
+    assert_eq!(world.facility(handle).unwrap().currency, 60.0);
+    assert_eq!(
+        world.get_merchant(merchant).unwrap().currency,
+        owner_cash - 60.0
+    );
+}
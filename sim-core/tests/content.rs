@@ -0,0 +1,48 @@
+use sim_core::{ContentRegistry, FacilityType, World};
+
+const BASE_PACK: &str = include_str!("../content/base.json");
+
+#[test]
+fn base_pack_loads_and_drives_a_world() {
+    let registry = ContentRegistry::from_json(BASE_PACK).expect("base pack should validate");
+
+    let grain = registry.good_id("grain").expect("grain should be defined");
+    let laborer = registry
+        .skill_id("laborer")
+        .expect("laborer should be defined");
+    let farming = registry
+        .recipe("Grain Farming")
+        .expect("farming recipe should be defined");
+    assert_eq!(farming.outputs, vec![(grain, 1.0)]);
+    assert_eq!(registry.facility_defs.len(), 6);
+
+    let mut world = World::with_seed(3);
+    let town = world.add_settlement("Town", (0.0, 0.0));
+    let merchant = world.add_merchant();
+    let farm = world
+        .add_facility(FacilityType::Farm, town, merchant)
+        .expect("facility should be created");
+    world
+        .facility_mut(farm)
+        .expect("facility should exist")
+        .recipe_priorities = vec![farming.id];
+    for _ in 0..8 {
+        let handle = world.add_pop(town).expect("pop should be created");
+        let pop = world.pop_mut(handle).expect("pop should exist");
+        pop.skills.insert(laborer);
+        pop.stocks.insert(grain, 2.0);
+    }
+
+    for _ in 0..10 {
+        world.run_tick(&registry.good_profiles, &registry.needs, &registry.recipes);
+    }
+
+    let produced = world
+        .get_merchant(merchant)
+        .expect("merchant should exist")
+        .production_ema
+        .values()
+        .flat_map(|per_good| per_good.get(&grain))
+        .sum::<f64>();
+    assert!(produced > 0.0, "farm should produce grain from pack recipe");
+}
@@ -0,0 +1,140 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[allow(dead_code)]
+mod common;
+
+use common::*;
+use sim_core::{
+    FacilityType, MerchantAction, MerchantController, MerchantId, MerchantObservation, RecipeId,
+    ResourceQuality, ResourceSlot, ResourceType, SupplyCurveController, World,
+};
+
+fn build_world() -> (World, MerchantId) {
+    let mut world = World::with_seed(5);
+    let town = world.add_settlement("Town", (0.0, 0.0));
+    world
+        .get_settlement_mut(town)
+        .unwrap()
+        .resource_slots
+        .push(ResourceSlot::new(
+            ResourceType::Land,
+            ResourceQuality::Normal,
+        ));
+    let merchant = world.add_merchant();
+
+    let farm = world
+        .add_facility(FacilityType::Farm, town, merchant)
+        .unwrap();
+    {
+        let f = world.facility_mut(farm).unwrap();
+        f.capacity = 6;
+        f.recipe_priorities = vec![RecipeId::new(1)];
+    }
+    world
+        .settlements
+        .get_mut(&town)
+        .unwrap()
+        .facility_bid_states
+        .get_mut(farm.key)
+        .unwrap()
+        .bids
+        .insert(LABORER, 2.0);
+
+    for _ in 0..12 {
+        let handle = world.add_pop(town).unwrap();
+        let pop = world.pop_mut(handle).unwrap();
+        pop.skills.insert(LABORER);
+        pop.min_wage = 0.5;
+        pop.currency = 50.0;
+        pop.stocks.insert(GRAIN, 1.0);
+        pop.desired_consumption_ema.insert(GRAIN, 1.0);
+    }
+    (world, merchant)
+}
+
+/// Never trades; on the first tick it sees, builds a second farm and
+/// clears the recipes of the first.
+#[derive(Debug, Default)]
+struct Hoarder {
+    acted: AtomicBool,
+}
+
+impl MerchantController for Hoarder {
+    fn decide(&self, observation: &MerchantObservation<'_>) -> Vec<MerchantAction> {
+        if self.acted.swap(true, Ordering::Relaxed) {
+            return Vec::new();
+        }
+        let town = &observation.settlements[0];
+        vec![
+            MerchantAction::SetRecipePriorities {
+                facility: town.facilities[0].0,
+                priorities: Vec::new(),
+            },
+            MerchantAction::Build {
+                facility_type: FacilityType::Farm,
+                settlement: town.id,
+            },
+        ]
+    }
+}
+
+#[test]
+fn explicit_default_controller_matches_builtin_behavior() {
+    let (mut implicit, _) = build_world();
+    let (mut explicit, merchant) = build_world();
+    explicit.set_merchant_controller(merchant, Arc::new(SupplyCurveController));
+
+    run_ticks(&mut implicit, 20, &[make_grain_recipe(1.5)]);
+    run_ticks(&mut explicit, 20, &[make_grain_recipe(1.5)]);
+
+    assert_eq!(
+        implicit.get_merchant(merchant).unwrap().currency.to_bits(),
+        explicit.get_merchant(merchant).unwrap().currency.to_bits()
+    );
+}
+
+#[test]
+fn custom_controller_actions_are_applied() {
+    let (mut world, merchant) = build_world();
+    world.set_merchant_controller(merchant, Arc::new(Hoarder::default()));
+    let currency_before = world.get_merchant(merchant).unwrap().currency;
+
+    run_ticks(&mut world, 1, &[make_grain_recipe(1.5)]);
+
+    let m = world.get_merchant(merchant).unwrap();
+    assert_eq!(m.owned_facilities.len(), 2, "build should be applied");
+    let farm_cost = world
+        .facility_def(FacilityType::Farm)
+        .unwrap()
+        .construction_cost;
+    assert!(m.currency <= currency_before - farm_cost);
+
+    let cleared = m
+        .owned_facilities
+        .iter()
+        .filter(|h| world.facility(**h).unwrap().recipe_priorities.is_empty())
+        .count();
+    assert_eq!(
+        cleared, 2,
+        "old farm cleared, new farm starts without recipes"
+    );
+
+    // The build is applied before the tick closes, so its cost is in the
+    // tick's accounting.
+    assert_eq!(world.stock_flow_history.len(), 1);
+    let flow = &world.stock_flow_history[0];
+    assert_eq!(flow.construction_spent_delta, farm_cost);
+    assert!(flow.currency_residual.abs() < 1e-9);
+
+    // A controller that never sells keeps all its grain.
+    run_ticks(&mut world, 5, &[make_grain_recipe(1.5)]);
+    assert!(
+        world
+            .settlements
+            .values()
+            .flat_map(|s| &s.last_fills)
+            .all(|f| f.agent_id != sim_core::AgentId::Merchant(merchant)),
+        "hoarder should place no orders"
+    );
+}
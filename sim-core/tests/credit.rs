@@ -0,0 +1,114 @@
+#[allow(dead_code)]
+mod common;
+
+use common::*;
+use sim_core::{CreditError, LoanId, MerchantId, Phase, World};
+
+/// Two merchants with no presence anywhere, so loans are the only thing
+/// moving their currency.
+fn lender_and_borrower() -> (World, MerchantId, MerchantId) {
+    let mut world = World::with_seed(3);
+    world.add_settlement("Town", (0.0, 0.0));
+    let lender = world.add_merchant();
+    let borrower = world.add_merchant();
+    world.get_merchant_mut(lender).unwrap().currency = 1_000.0;
+    world.get_merchant_mut(borrower).unwrap().currency = 10.0;
+    (world, lender, borrower)
+}
+
+fn currency(world: &World, merchant: MerchantId) -> f64 {
+    world.get_merchant(merchant).unwrap().currency
+}
+
+#[test]
+fn loan_is_repaid_with_interest_over_its_term() {
+    let (mut world, lender, borrower) = lender_and_borrower();
+    world.set_ledger_enabled(true);
+    let before = world.balance_sheet(lender).unwrap().net_worth();
+
+    let loan = world.issue_loan(lender, borrower, 100.0, 0.01, 4).unwrap();
+    assert_eq!(currency(&world, borrower), 110.0);
+    let lender_sheet = world.balance_sheet(lender).unwrap();
+    assert_eq!(lender_sheet.receivables, 100.0);
+    assert_eq!(lender_sheet.net_worth(), before);
+    assert_eq!(world.balance_sheet(borrower).unwrap().debts, 100.0);
+
+    run_ticks(&mut world, 4, &[]);
+
+    assert!(world.loan(loan).is_none(), "loan should be retired");
+    // 1% on 100, 75, 50 and 25
+    assert!((currency(&world, lender) - 1_002.5).abs() < 1e-9);
+    assert!((currency(&world, borrower) - 7.5).abs() < 1e-9);
+    assert_eq!(world.balance_sheet(borrower).unwrap().debts, 0.0);
+
+    for flow in &world.stock_flow_history {
+        assert!(flow.currency_residual.abs() < 1e-9);
+        assert!(flow.loan_residual.abs() < 1e-9, "tick {}", flow.tick);
+    }
+    let repaid: f64 = world
+        .stock_flow_history
+        .iter()
+        .map(|f| f.loans_repaid_delta)
+        .sum();
+    assert!((repaid - 102.5).abs() < 1e-9);
+
+    let ledger = world.ledger.as_ref().unwrap();
+    let installments = ledger
+        .entries
+        .iter()
+        .filter(|e| e.phase == Phase::Credit)
+        .count();
+    assert_eq!(installments, 4);
+}
+
+#[test]
+fn unpaid_installments_stay_owed_until_the_borrower_can_pay() {
+    let (mut world, lender, borrower) = lender_and_borrower();
+    let loan = world.issue_loan(lender, borrower, 100.0, 0.1, 2).unwrap();
+    world.get_merchant_mut(borrower).unwrap().currency = 0.0;
+
+    run_ticks(&mut world, 3, &[]);
+    let overdue = world.loan(loan).expect("nothing was paid");
+    assert_eq!(overdue.outstanding, 100.0);
+    assert!((overdue.accrued_interest - 30.0).abs() < 1e-9);
+    for flow in &world.stock_flow_history {
+        assert!(flow.loan_residual.abs() < 1e-9, "tick {}", flow.tick);
+    }
+
+    world.get_merchant_mut(borrower).unwrap().currency = 50.0;
+    let paid = world.repay_loan(loan, 1_000.0).unwrap();
+    assert_eq!(paid, 50.0, "pays what it has, interest first");
+    assert!((world.loan(loan).unwrap().balance() - 80.0).abs() < 1e-9);
+
+    world.get_merchant_mut(borrower).unwrap().currency = 500.0;
+    run_ticks(&mut world, 1, &[]);
+    assert!(
+        world.loan(loan).is_none(),
+        "overdue balance falls due in full"
+    );
+}
+
+#[test]
+fn loans_need_valid_terms_and_a_funded_lender() {
+    let (mut world, lender, borrower) = lender_and_borrower();
+    assert_eq!(
+        world.issue_loan(lender, lender, 10.0, 0.0, 1),
+        Err(CreditError::SelfLoan(lender))
+    );
+    assert_eq!(
+        world.issue_loan(lender, borrower, 10.0, 0.0, 0),
+        Err(CreditError::InvalidTerms)
+    );
+    assert_eq!(
+        world.issue_loan(borrower, lender, 20.0, 0.0, 1),
+        Err(CreditError::InsufficientFunds {
+            required: 20.0,
+            available: 10.0
+        })
+    );
+    assert_eq!(
+        world.repay_loan(LoanId::new(7), 1.0),
+        Err(CreditError::UnknownLoan(LoanId::new(7)))
+    );
+    assert!(world.loans.is_empty());
+}
@@ -85,6 +85,7 @@ fn price_ceiling_with_import_anchor() {
         None,
         &HashMap::new(),
         None,
+        None,
     );
 
     let price = result
@@ -141,6 +142,7 @@ fn price_floor_with_export_anchor() {
         None,
         &HashMap::new(),
         None,
+        None,
     );
 
     let price = result
@@ -198,6 +200,7 @@ fn outside_depth_is_respected() {
         None,
         &HashMap::new(),
         None,
+        None,
     );
 
     let imported = flows
@@ -243,6 +246,7 @@ fn no_external_flow_when_disabled() {
         None,
         &HashMap::new(),
         None,
+        None,
     );
 
     let imported = flows
@@ -292,6 +296,7 @@ fn external_anchor_influences_price_ema_but_local_clear_dominates() {
         None,
         &HashMap::new(),
         None,
+        None,
     );
 
     let local_price = result
@@ -351,6 +356,7 @@ fn anchored_no_trade_tick_still_moves_ema_toward_world() {
         None,
         &HashMap::new(),
         None,
+        None,
     );
 
     assert!(
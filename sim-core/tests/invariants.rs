@@ -5,13 +5,24 @@ use common::*;
 use std::collections::HashMap;
 
 use sim_core::{
-    AnchoredGoodConfig, ExternalMarketConfig, GoodId, GoodProfile, Need, NeedContribution,
-    OutsideFlowTotals, Pop, PopKey, Price, Recipe, SettlementFriction, SettlementId,
-    SubsistenceReservationConfig, UtilityCurve, World, pop_key_from_u64,
+    Account, AgentId, AnchoredGoodConfig, ExternalMarketConfig, GoodId, GoodProfile, Need,
+    NeedContribution, OutsideFlowTotals, Phase, Pop, PopKey, Price, Recipe, SettlementFriction,
+    SettlementId, Side, SubsistenceReservationConfig, TickStockFlow, UtilityCurve, World,
+    pop_key_from_u64,
     production::{FacilityType, RecipeId},
     run_settlement_tick,
 };
 
+fn assert_goods_residual_near_zero(flow: &TickStockFlow) {
+    for (good, residual) in &flow.goods_residual {
+        assert!(
+            residual.abs() < 1e-6,
+            "good {good} residual {residual} at tick {}",
+            flow.tick
+        );
+    }
+}
+
 fn pk(id: u64) -> PopKey {
     pop_key_from_u64(id)
 }
@@ -44,7 +55,13 @@ fn total_currency(world: &World) -> f64 {
         .map(|p| p.currency)
         .sum();
     let merchant_currency: f64 = world.merchants.values().map(|m| m.currency).sum();
-    pop_currency + merchant_currency
+    let facility_currency: f64 = world
+        .settlements
+        .values()
+        .flat_map(|s| s.facilities.values())
+        .map(|f| f.currency)
+        .sum();
+    pop_currency + merchant_currency + facility_currency
 }
 
 #[test]
@@ -88,6 +105,9 @@ fn invariant_currency_conserved_under_population_growth() {
             "Currency should be conserved even with growth at tick {tick}: initial={initial_currency:.2}, current={current_currency:.2}, diff={diff:.6}"
         );
     }
+    for flow in &world.stock_flow_history {
+        assert_goods_residual_near_zero(flow);
+    }
 }
 
 #[test]
@@ -130,6 +150,7 @@ fn invariant_pop_cannot_sell_more_than_inventory() {
         None,
         &HashMap::new(),
         None,
+        None,
     );
 
     let remaining = seller.stocks.get(&GRAIN).copied().unwrap_or(0.0);
@@ -328,6 +349,7 @@ fn invariant_external_flow_matches_local_currency_delta() {
             None,
             &HashMap::new(),
             None,
+            None,
         );
         let post_currency = seller.currency + buyer.currency;
         let currency_delta = post_currency - pre_currency;
@@ -400,6 +422,7 @@ fn invariant_subsistence_allocates_more_to_earlier_pops() {
         Some(&subsistence),
         &HashMap::new(),
         Some(&queue),
+        None,
     );
 
     let a = pop_a.stocks.get(&GRAIN).copied().unwrap_or(0.0);
@@ -513,6 +536,7 @@ fn invariant_closed_economy_tick_residual_near_zero() {
             flow.tick,
             flow.currency_residual
         );
+        assert_goods_residual_near_zero(flow);
     }
 }
 
@@ -636,6 +660,7 @@ fn invariant_open_economy_grain_accounting_balanced() {
             "Negative exports at tick {}: {exports:.8}",
             flow.tick,
         );
+        assert_goods_residual_near_zero(flow);
     }
 
     // Verify the external anchor actually generated some trade
@@ -654,3 +679,278 @@ fn invariant_open_economy_grain_accounting_balanced() {
         "Open economy should have some external trade: imports={total_imports:.4}, exports={total_exports:.4}"
     );
 }
+
+#[test]
+fn invariant_facility_treasury_pays_wages_and_keeps_sales() {
+    let mut world = World::new();
+    world.mortality_grace_ticks = 1_000;
+    let settlement = world.add_settlement("TreasuryTown", (0.0, 0.0));
+    let merchant = world.add_merchant();
+    world.get_merchant_mut(merchant).unwrap().currency = 500.0;
+    let farm = world
+        .add_facility(FacilityType::Farm, settlement, merchant)
+        .unwrap();
+    {
+        let facility = world.facility_mut(farm).unwrap();
+        facility.capacity = 10;
+        facility.recipe_priorities = vec![RecipeId::new(1)];
+    }
+    world.deposit_to_facility(farm, 500.0).unwrap();
+    world.set_ledger_enabled(true);
+
+    for _ in 0..10 {
+        let pop_id = world.add_pop(settlement).unwrap();
+        let pop = world.pop_mut(pop_id).unwrap();
+        pop.skills.insert(LABORER);
+        pop.min_wage = 0.5;
+        pop.currency = 200.0;
+        pop.income_ema = 2.0;
+        pop.stocks.insert(GRAIN, 5.0);
+        pop.desired_consumption_ema.insert(GRAIN, 1.0);
+    }
+    let s = world.settlements.get_mut(&settlement).unwrap();
+    s.wage_ema.insert(LABORER, 1.0);
+    s.price_ema.insert(GRAIN, 1.0);
+
+    let good_profiles = make_grain_profile();
+    let needs = make_food_need(1.0);
+    let recipes = vec![make_grain_recipe(2.0)];
+    let initial_currency = total_currency(&world);
+    for _ in 0..15 {
+        world.run_tick(&good_profiles, &needs, &recipes);
+    }
+
+    let facility = world.facility(farm).unwrap();
+    let lifetime = &facility.lifetime_income;
+    assert!(lifetime.wages > 0.0);
+    assert!(lifetime.revenue > 0.0);
+    assert!(facility.total_workers() > 0);
+    assert!(
+        (facility.currency - (500.0 + lifetime.revenue - lifetime.wages - lifetime.input_costs))
+            .abs()
+            < 1e-6,
+        "treasury {} should be the deposit plus revenue less wages and inputs",
+        facility.currency
+    );
+    let ledger = world.ledger.as_ref().unwrap();
+    let wage_entries = || {
+        ledger
+            .entries
+            .iter()
+            .filter(|e| e.phase == Phase::Labor && e.good.is_none())
+    };
+    let paid_by_farm: f64 = wage_entries()
+        .filter(|e| e.credit == Account::Facility(farm))
+        .map(|e| e.amount)
+        .sum();
+    assert!((paid_by_farm - lifetime.wages).abs() < 1e-6);
+    assert!(
+        wage_entries().all(|e| e.credit != Account::Merchant(merchant)),
+        "wages should come out of the farm's treasury, not the owner's purse"
+    );
+    assert!((total_currency(&world) - initial_currency).abs() < 1e-6);
+    for flow in &world.stock_flow_history {
+        assert!(
+            flow.currency_residual.abs() < 1e-6,
+            "tick {} residual {}",
+            flow.tick,
+            flow.currency_residual
+        );
+    }
+}
+
+#[test]
+fn invariant_settlement_goods_balance_explained_by_flows() {
+    let mut world = World::new();
+    world.mortality_grace_ticks = 1_000;
+    let settlement = world.add_settlement("OpenTown", (0.0, 0.0));
+    let merchant = world.add_merchant();
+    let farm = world
+        .add_facility(FacilityType::Farm, settlement, merchant)
+        .unwrap();
+    {
+        let facility = world.facility_mut(farm).unwrap();
+        facility.capacity = 5;
+        facility.recipe_priorities = vec![RecipeId::new(1)];
+    }
+    {
+        let merchant_ref = world.get_merchant_mut(merchant).unwrap();
+        merchant_ref.currency = 10_000.0;
+        merchant_ref.stockpile_at(settlement).add(GRAIN, 500.0);
+    }
+    for _ in 0..10 {
+        let pop_id = world.add_pop(settlement).unwrap();
+        let pop = world.pop_mut(pop_id).unwrap();
+        pop.skills.insert(LABORER);
+        pop.min_wage = 0.5;
+        pop.currency = 500.0;
+        pop.stocks.insert(GRAIN, 50.0);
+        pop.desired_consumption_ema.insert(GRAIN, 1.0);
+    }
+    let s = world.settlements.get_mut(&settlement).unwrap();
+    s.wage_ema.insert(LABORER, 2.0);
+    s.price_ema.insert(GRAIN, 1.0);
+
+    let mut external = ExternalMarketConfig::default();
+    external.anchors.insert(
+        GRAIN,
+        AnchoredGoodConfig {
+            world_price: 10.0,
+            spread_bps: 500.0,
+            base_depth: 0.0,
+            depth_per_pop: 0.5,
+            tiers: 9,
+            tier_step_bps: 300.0,
+        },
+    );
+    world.set_external_market(external);
+    world.set_subsistence_reservation(SubsistenceReservationConfig::new(GRAIN, 1.0, 20, 1.0, 0.0));
+
+    let good_profiles = make_grain_profile();
+    let needs = make_food_need(1.0);
+    let recipes = vec![make_grain_recipe(2.0)];
+    for _ in 0..20 {
+        world.run_tick(&good_profiles, &needs, &recipes);
+    }
+
+    let get = |map: &HashMap<GoodId, f64>| map.get(&GRAIN).copied().unwrap_or(0.0);
+    let mut saw = (false, false, false);
+    for flow in &world.stock_flow_history {
+        assert_eq!(flow.settlements.len(), 1);
+        let local = &flow.settlements[0];
+        assert_eq!(local.settlement, settlement);
+        assert!((get(&local.imports_qty_delta) - get(&flow.imports_qty_delta)).abs() < 1e-9);
+        assert!((get(&local.exports_qty_delta) - get(&flow.exports_qty_delta)).abs() < 1e-9);
+        assert!(
+            (local.trade_balance() - flow.exports_value_delta + flow.imports_value_delta).abs()
+                < 1e-9
+        );
+
+        let flows = &local.flows;
+        let explained = get(&flows.produced) - get(&flows.used_in_production)
+            + get(&flows.subsistence)
+            - get(&flows.consumed)
+            + get(&local.imports_qty_delta)
+            - get(&local.exports_qty_delta);
+        let err = (local.goods_delta(GRAIN) - explained).abs();
+        assert!(err < 1e-6, "tick {} unexplained grain {err}", flow.tick);
+        assert_goods_residual_near_zero(flow);
+        saw.0 |= get(&flows.produced) > 0.0;
+        saw.1 |= get(&flows.subsistence) > 0.0;
+        saw.2 |= get(&flows.consumed) > 0.0;
+    }
+    assert_eq!(
+        saw,
+        (true, true, true),
+        "every flow term should be exercised"
+    );
+}
+
+#[test]
+fn invariant_goods_of_the_last_pop_to_die_are_written_off() {
+    const CLOTH: GoodId = 7;
+    let mut world = World::new();
+    world.mortality_grace_ticks = 0;
+    world.demography.max_death_hazard = 1.0;
+    world.demography.deficit_exponent = 0.0;
+    world.demography.deficit_onset_ticks = 1;
+    let settlement = world.add_settlement("LastTown", (0.0, 0.0));
+    let pop_id = world.add_pop(settlement).unwrap();
+    let pop = world.pop_mut(pop_id).unwrap();
+    pop.stocks.insert(CLOTH, 5.0);
+    pop.currency = 12.0;
+    // A crumb of grain so the pop's hunger is tracked at all
+    pop.stocks.insert(GRAIN, 0.01);
+    pop.desired_consumption_ema.insert(GRAIN, 1.0);
+
+    let good_profiles = make_grain_profile();
+    let needs = make_food_need(1.0);
+    for _ in 0..10 {
+        world.run_tick(&good_profiles, &needs, &Vec::<Recipe>::new());
+    }
+
+    assert_eq!(pop_count(&world), 0, "the starving pop should have died");
+    let written_off: f64 = world
+        .stock_flow_history
+        .iter()
+        .map(|f| f.goods_lost_with_dead.get(&CLOTH).copied().unwrap_or(0.0))
+        .sum();
+    assert_eq!(written_off, 5.0);
+    let currency_written_off: f64 = world
+        .stock_flow_history
+        .iter()
+        .map(|f| f.currency_lost_with_dead)
+        .sum();
+    assert_eq!(currency_written_off, 12.0);
+    for flow in &world.stock_flow_history {
+        assert_goods_residual_near_zero(flow);
+        assert!(
+            flow.currency_residual.abs() < 1e-9,
+            "tick {} residual {}",
+            flow.tick,
+            flow.currency_residual
+        );
+    }
+}
+
+#[test]
+fn invariant_facility_revenue_is_limited_to_its_own_output() {
+    let mut world = World::new();
+    world.mortality_grace_ticks = 1_000;
+    let settlement = world.add_settlement("ResaleTown", (0.0, 0.0));
+    let merchant = world.add_merchant();
+    {
+        // Grain shipped in from elsewhere, not grown here
+        let owner = world.get_merchant_mut(merchant).unwrap();
+        owner.currency = 100.0;
+        owner.stockpile_at(settlement).add(GRAIN, 50.0);
+    }
+    let farm = world
+        .add_facility(FacilityType::Farm, settlement, merchant)
+        .unwrap();
+    {
+        let facility = world.facility_mut(farm).unwrap();
+        facility.capacity = 2;
+        facility.recipe_priorities = vec![RecipeId::new(1)];
+    }
+
+    for _ in 0..10 {
+        let pop_id = world.add_pop(settlement).unwrap();
+        let pop = world.pop_mut(pop_id).unwrap();
+        pop.skills.insert(LABORER);
+        pop.min_wage = 0.0;
+        pop.currency = 200.0;
+        pop.desired_consumption_ema.insert(GRAIN, 1.0);
+    }
+    let s = world.settlements.get_mut(&settlement).unwrap();
+    s.wage_ema.insert(LABORER, 1.0);
+    s.price_ema.insert(GRAIN, 1.0);
+
+    let good_profiles = make_grain_profile();
+    let needs = make_food_need(1.0);
+    let recipes = vec![make_grain_recipe(1.0)];
+    let mut owner_sales = 0.0;
+    for _ in 0..10 {
+        world.run_tick(&good_profiles, &needs, &recipes);
+        let (mut sold, mut top_price) = (0.0, 0.0_f64);
+        for fill in &world.settlements[&settlement].last_fills {
+            if fill.agent_id == AgentId::Merchant(merchant) && matches!(fill.side, Side::Sell) {
+                sold += fill.quantity * fill.price;
+                top_price = top_price.max(fill.price);
+            }
+        }
+        let facility = world.facility(farm).unwrap();
+        let grown = facility.produced.get(&GRAIN).copied().unwrap_or(0.0);
+        assert!(
+            facility.income.revenue <= grown * top_price + 1e-9,
+            "revenue {} for {grown} grain grown",
+            facility.income.revenue
+        );
+        owner_sales += sold - facility.income.revenue;
+    }
+    assert!(world.facility(farm).unwrap().lifetime_income.revenue > 0.0);
+    assert!(
+        owner_sales > 0.0,
+        "sales of shipped grain stay with the owner"
+    );
+}
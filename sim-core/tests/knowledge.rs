@@ -0,0 +1,129 @@
+#[allow(dead_code)]
+mod common;
+
+use common::*;
+use sim_core::{
+    FacilityType, MerchantId, ResourceQuality, ResourceSlot, ResourceType, SettlementId,
+    VesselKind, World,
+};
+
+/// A merchant with a farm in Home and nothing in Away, three ticks down
+/// the road.
+fn home_and_away() -> (World, SettlementId, SettlementId, MerchantId) {
+    let mut world = World::with_seed(11);
+    let home = world.add_settlement("Home", (0.0, 0.0));
+    let away = world.add_settlement("Away", (3.0, 0.0));
+    world.add_route(home, away, 3);
+    world
+        .get_settlement_mut(home)
+        .unwrap()
+        .resource_slots
+        .push(ResourceSlot::new(
+            ResourceType::Land,
+            ResourceQuality::Normal,
+        ));
+    let merchant = world.add_merchant();
+    world
+        .add_facility(FacilityType::Farm, home, merchant)
+        .unwrap();
+    for id in [home, away] {
+        world
+            .settlements
+            .get_mut(&id)
+            .unwrap()
+            .price_ema
+            .insert(GRAIN, 10.0);
+    }
+    (world, home, away, merchant)
+}
+
+#[test]
+fn presence_keeps_knowledge_fresh_and_remote_markets_unknown() {
+    let (mut world, home, away, merchant) = home_and_away();
+    run_ticks(&mut world, 4, &[make_grain_recipe(1.0)]);
+
+    let knowledge = &world.get_merchant(merchant).unwrap().knowledge;
+    assert_eq!(knowledge.age(home, world.tick), Some(0));
+    assert_eq!(knowledge.get(home).unwrap().prices[&GRAIN], 10.0);
+    assert!(knowledge.get(away).is_none());
+}
+
+#[test]
+fn courier_report_arrives_after_travel_and_then_ages() {
+    let (mut world, home, away, merchant) = home_and_away();
+    run_ticks(&mut world, 1, &[make_grain_recipe(1.0)]);
+    world
+        .settlements
+        .get_mut(&away)
+        .unwrap()
+        .price_ema
+        .insert(GRAIN, 25.0);
+
+    let sent_at = world.tick;
+    assert_eq!(world.send_courier(merchant, away, home), Ok(3));
+    run_ticks(&mut world, 2, &[make_grain_recipe(1.0)]);
+    assert!(
+        world
+            .get_merchant(merchant)
+            .unwrap()
+            .knowledge
+            .get(away)
+            .is_none()
+    );
+
+    run_ticks(&mut world, 1, &[make_grain_recipe(1.0)]);
+    world
+        .settlements
+        .get_mut(&away)
+        .unwrap()
+        .price_ema
+        .insert(GRAIN, 40.0);
+    run_ticks(&mut world, 2, &[make_grain_recipe(1.0)]);
+
+    let report = world
+        .get_merchant(merchant)
+        .unwrap()
+        .knowledge
+        .get(away)
+        .unwrap();
+    assert_eq!(report.observed_tick, sent_at);
+    assert_eq!(report.prices[&GRAIN], 25.0, "later changes stay unseen");
+    assert_eq!(report.age(world.tick), 5);
+}
+
+#[test]
+fn vessel_brings_news_of_both_ends() {
+    // No facilities anywhere, so vessels are the merchant's only source
+    let mut world = World::with_seed(11);
+    let home = world.add_settlement("Home", (0.0, 0.0));
+    let away = world.add_settlement("Away", (3.0, 0.0));
+    world.add_route(home, away, 3);
+    let merchant = world.add_merchant();
+    world
+        .get_merchant_mut(merchant)
+        .unwrap()
+        .stockpile_at(home)
+        .add(GRAIN, 10.0);
+    let caravan = world
+        .add_vessel(VesselKind::Caravan, home, merchant)
+        .unwrap();
+    run_ticks(&mut world, 1, &[make_grain_recipe(1.0)]);
+    assert!(
+        world
+            .get_merchant(merchant)
+            .unwrap()
+            .knowledge
+            .snapshots
+            .is_empty()
+    );
+
+    world.load_vessel(caravan, GRAIN, 10.0).unwrap();
+    world.dispatch_vessel(caravan, away).unwrap();
+    let departed_at = world.tick;
+    run_ticks(&mut world, 3, &[make_grain_recipe(1.0)]);
+
+    let knowledge = &world.get_merchant(merchant).unwrap().knowledge;
+    assert_eq!(knowledge.get(home).unwrap().observed_tick, departed_at);
+    assert_eq!(knowledge.get(away).unwrap().observed_tick, world.tick);
+    assert_eq!(knowledge.get(away).unwrap().stocks[&GRAIN], 10.0);
+}
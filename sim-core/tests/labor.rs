@@ -0,0 +1,78 @@
+#[allow(dead_code)]
+mod common;
+
+use common::*;
+use sim_core::{ComplementaryProductionFn, FacilityType, Recipe, RecipeId, SettlementId, World};
+
+/// A farm whose first laborer gleans under a production function that
+/// gives nothing for a second, and whose second plows a fixed recipe.
+fn glean_and_plow() -> Vec<Recipe> {
+    vec![
+        Recipe::new(RecipeId::new(1), "Glean", vec![FacilityType::Farm])
+            .with_worker(LABORER, 1)
+            .with_output(GRAIN, 3.0)
+            .with_production_fn(ComplementaryProductionFn {
+                base_output: [(LABORER, 1.0)].into(),
+                complementarity_bonus: Default::default(),
+                max_optimal_capacity: [(LABORER, 1)].into(),
+                diminishing_rate: 1.0,
+            }),
+        Recipe::new(RecipeId::new(2), "Plow", vec![FacilityType::Farm])
+            .with_worker(LABORER, 1)
+            .with_output(GRAIN, 1.0),
+    ]
+}
+
+/// A farm that had two laborers last tick, and two laborers looking for work.
+fn farm_with_two_laborers() -> (World, SettlementId) {
+    let mut world = World::with_seed(4);
+    world.mortality_grace_ticks = 1_000;
+    let town = world.add_settlement("Town", (0.0, 0.0));
+    let merchant = world.add_merchant();
+    world.get_merchant_mut(merchant).unwrap().currency = 100.0;
+    let farm = world
+        .add_facility(FacilityType::Farm, town, merchant)
+        .unwrap();
+    {
+        let f = world.facility_mut(farm).unwrap();
+        f.capacity = 2;
+        f.recipe_priorities = vec![RecipeId::new(1), RecipeId::new(2)];
+        f.workers.insert(LABORER, 2);
+    }
+    {
+        let s = world.settlements.get_mut(&town).unwrap();
+        s.price_ema.insert(GRAIN, 1.0);
+        s.wage_ema.insert(LABORER, 2.5);
+    }
+    for _ in 0..2 {
+        let handle = world.add_pop(town).unwrap();
+        let pop = world.pop_mut(handle).unwrap();
+        pop.skills.insert(LABORER);
+        pop.min_wage = 2.0;
+        pop.stocks.insert(GRAIN, 10.0);
+        pop.desired_consumption_ema.insert(GRAIN, 1.0);
+    }
+    (world, town)
+}
+
+fn employed(world: &World, town: SettlementId) -> usize {
+    world.settlements[&town]
+        .pops
+        .values()
+        .filter(|p| p.employed_skill == Some(LABORER))
+        .count()
+}
+
+#[test]
+fn production_fn_mvp_counts_only_its_own_crew() {
+    // Two laborers on the farm: one gleans, one plows. Gleaning's marginal
+    // laborer is its first (worth 3), not the facility's second (worth 0),
+    // so the farm can outbid the pops' reservation wage.
+    let (mut world, town) = farm_with_two_laborers();
+    world.run_tick(
+        &make_grain_profile(),
+        &make_food_need(1.0),
+        &glean_and_plow(),
+    );
+    assert!(employed(&world, town) > 0);
+}
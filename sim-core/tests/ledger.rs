@@ -0,0 +1,113 @@
+#[allow(dead_code)]
+mod common;
+
+use common::*;
+use sim_core::{
+    Account, AnchoredGoodConfig, ExternalMarketConfig, FacilityType, Phase, RecipeId,
+    SettlementFriction, SubsistenceReservationConfig, VesselKind, World,
+};
+
+#[test]
+fn ledger_explains_every_tick_currency_delta() {
+    let mut world = World::with_seed(3);
+    world.mortality_grace_ticks = 1_000;
+    world.set_ledger_enabled(true);
+    let town = world.add_settlement("Town", (0.0, 0.0));
+    let merchant = world.add_merchant();
+    world.get_merchant_mut(merchant).unwrap().currency = 500.0;
+
+    let mut external = ExternalMarketConfig::default();
+    external.anchors.insert(
+        GRAIN,
+        AnchoredGoodConfig {
+            world_price: 2.0,
+            spread_bps: 500.0,
+            base_depth: 20.0,
+            depth_per_pop: 0.0,
+            tiers: 5,
+            tier_step_bps: 300.0,
+        },
+    );
+    external.frictions.insert(
+        town,
+        SettlementFriction {
+            enabled: true,
+            ..Default::default()
+        },
+    );
+    world.set_external_market(external);
+    world.set_subsistence_reservation(SubsistenceReservationConfig::new(GRAIN, 1.0, 4, 1.0, 0.0));
+
+    let farm = world
+        .add_facility(FacilityType::Farm, town, merchant)
+        .unwrap();
+    {
+        let facility = world.facility_mut(farm).unwrap();
+        facility.capacity = 4;
+        facility.recipe_priorities = vec![RecipeId::new(1)];
+    }
+    world.deposit_to_facility(farm, 100.0).unwrap();
+    world
+        .add_vessel(VesselKind::Caravan, town, merchant)
+        .unwrap();
+
+    for _ in 0..8 {
+        let pop_id = world.add_pop(town).unwrap();
+        let pop = world.pop_mut(pop_id).unwrap();
+        pop.skills.insert(LABORER);
+        pop.min_wage = 0.5;
+        pop.currency = 50.0;
+        pop.income_ema = 1.0;
+        pop.stocks.insert(GRAIN, 3.0);
+        pop.desired_consumption_ema.insert(GRAIN, 1.0);
+    }
+    let s = world.settlements.get_mut(&town).unwrap();
+    s.wage_ema.insert(LABORER, 1.0);
+    s.price_ema.insert(GRAIN, 2.0);
+
+    let good_profiles = make_grain_profile();
+    let needs = make_food_need(1.0);
+    let recipes = vec![make_grain_recipe(2.0)];
+    for _ in 0..12 {
+        world.run_tick(&good_profiles, &needs, &recipes);
+    }
+
+    let ledger = world.ledger.as_ref().unwrap();
+    for flow in &world.stock_flow_history {
+        let journaled: f64 = ledger
+            .external_inflow(flow.tick, None)
+            .iter()
+            .filter(|(phase, _)| *phase != Phase::Actions)
+            .map(|(_, inflow)| inflow)
+            .sum();
+        assert!(
+            (journaled - flow.currency_delta).abs() < 1e-6,
+            "tick {}: journal {journaled} vs delta {}",
+            flow.tick,
+            flow.currency_delta
+        );
+    }
+
+    let entries = &ledger.entries;
+    assert!(entries.iter().any(|e| e.phase == Phase::Labor));
+    assert!(
+        entries
+            .iter()
+            .any(|e| matches!(e.credit, Account::Commons(_)))
+    );
+    assert!(
+        entries
+            .iter()
+            .any(|e| matches!(e.credit, Account::Outside(_)))
+    );
+    assert!(entries.iter().any(|e| e.debit == Account::Transport));
+    assert!(
+        ledger.balance(Account::Market(town), None).abs() < 1e-6,
+        "the market's clearing account nets out"
+    );
+    assert!(
+        (ledger.balance(Account::Facility(farm), None) - world.facility(farm).unwrap().currency)
+            .abs()
+            < 1e-6
+    );
+}
@@ -0,0 +1,114 @@
+#[allow(dead_code)]
+mod common;
+
+use common::*;
+use sim_core::{Account, MigrationConfig, Phase, SettlementId, World};
+
+/// A hungry, poor village two ticks from a well-fed, well-paid town.
+/// Mortality is held off so only migration changes pop counts.
+fn village_and_town(pop_currency: f64) -> (World, SettlementId, SettlementId) {
+    let mut world = World::with_seed(21);
+    world.mortality_grace_ticks = 1_000;
+    let village = world.add_settlement("Village", (0.0, 0.0));
+    let town = world.add_settlement("Town", (2.0, 0.0));
+    world.add_route(village, town, 2);
+
+    for (id, income, grain) in [(village, 1.0, 0.0), (town, 10.0, 50.0)] {
+        world
+            .settlements
+            .get_mut(&id)
+            .unwrap()
+            .price_ema
+            .insert(GRAIN, 5.0);
+        for _ in 0..10 {
+            let handle = world.add_pop(id).unwrap();
+            let pop = world.pop_mut(handle).unwrap();
+            pop.currency = pop_currency;
+            pop.income_ema = income;
+            pop.stocks.insert(GRAIN, grain);
+            pop.desired_consumption_ema.insert(GRAIN, 1.0);
+        }
+    }
+    world.set_migration(MigrationConfig {
+        max_rate: 0.5,
+        fare_per_distance: 2.0,
+        ..Default::default()
+    });
+    (world, village, town)
+}
+
+fn pop_count(world: &World, id: SettlementId) -> usize {
+    world.settlements[&id].pops.len()
+}
+
+#[test]
+fn hungry_pops_move_towards_better_prospects() {
+    let (mut world, village, town) = village_and_town(100.0);
+    run_ticks(&mut world, 10, &[make_grain_recipe(1.0)]);
+
+    let left = world.migration_flows[&(village, town)];
+    assert!(left > 0);
+    assert!(!world.migration_flows.contains_key(&(town, village)));
+    assert_eq!(pop_count(&world, village), 10 - left as usize);
+    assert_eq!(
+        pop_count(&world, town) + world.migrants.len(),
+        10 + left as usize
+    );
+    assert_eq!(world.transport_totals.fares_paid, 4.0 * left as f64);
+
+    for settlement in world.settlements.values() {
+        for key in &settlement.subsistence_queue {
+            assert!(settlement.pops.contains_key(*key));
+        }
+    }
+    for flow in &world.stock_flow_history {
+        assert!(
+            flow.currency_residual.abs() < 1e-6,
+            "tick {} residual {}",
+            flow.tick,
+            flow.currency_residual
+        );
+        for (good, residual) in &flow.goods_residual {
+            assert!(residual.abs() < 1e-6, "tick {} good {good}", flow.tick);
+        }
+    }
+}
+
+#[test]
+fn pops_who_cannot_afford_the_fare_stay() {
+    let (mut world, village, _) = village_and_town(3.0);
+    run_ticks(&mut world, 10, &[make_grain_recipe(1.0)]);
+    assert!(world.migration_flows.is_empty());
+    assert_eq!(pop_count(&world, village), 10);
+}
+
+#[test]
+fn migrant_journal_accounts_follow_the_pop() {
+    let (mut world, village, town) = village_and_town(100.0);
+    world.set_ledger_enabled(true);
+    run_ticks(&mut world, 10, &[make_grain_recipe(1.0)]);
+
+    let ledger = world.ledger.as_ref().unwrap();
+    let (mut fares, mut arrivals) = (0, 0);
+    for entry in ledger
+        .entries
+        .iter()
+        .filter(|e| e.phase == Phase::Migration)
+    {
+        let Account::Pop(from) = entry.credit else {
+            panic!("migration entries start at a pop: {entry:?}");
+        };
+        assert_eq!(from.settlement, village, "fares and balances leave home");
+        match entry.debit {
+            Account::Transport => fares += 1,
+            Account::Pop(to) => {
+                assert_eq!(to.settlement, town);
+                assert!(world.pop(to).is_some(), "balance moves to the new handle");
+                arrivals += 1;
+            }
+            other => panic!("unexpected debit {other:?}"),
+        }
+    }
+    assert_eq!(fares, world.migration_flows[&(village, town)] as usize);
+    assert!(arrivals > 0);
+}
@@ -0,0 +1,150 @@
+#[allow(dead_code)]
+mod common;
+
+use common::*;
+use sim_core::{
+    AnchoredGoodConfig, ExternalMarketConfig, ResourceQuality, ResourceSlot, ResourceType,
+    SettlementFriction, SettlementId, SettlementRole, VesselKind, World,
+};
+
+/// A coastal port and an inland town two ticks up the road, both hungry
+/// and both with outside access enabled in their frictions.
+fn port_and_inland(port_only: bool) -> (World, SettlementId, SettlementId) {
+    let mut world = World::with_seed(5);
+    let port = world.add_settlement("Port", (0.0, 0.0));
+    let inland = world.add_settlement("Inland", (2.0, 0.0));
+    world.add_route(port, inland, 2);
+    world
+        .get_settlement_mut(port)
+        .unwrap()
+        .resource_slots
+        .push(ResourceSlot::new(
+            ResourceType::Coastal,
+            ResourceQuality::Normal,
+        ));
+
+    let mut config = ExternalMarketConfig {
+        port_only,
+        ..Default::default()
+    };
+    config.anchors.insert(
+        GRAIN,
+        AnchoredGoodConfig {
+            world_price: 10.0,
+            base_depth: 20.0,
+            ..Default::default()
+        },
+    );
+    for id in [port, inland] {
+        config.frictions.insert(
+            id,
+            SettlementFriction {
+                enabled: true,
+                ..Default::default()
+            },
+        );
+        world
+            .settlements
+            .get_mut(&id)
+            .unwrap()
+            .price_ema
+            .insert(GRAIN, 10.0);
+        for _ in 0..5 {
+            let handle = world.add_pop(id).unwrap();
+            let pop = world.pop_mut(handle).unwrap();
+            pop.currency = 100.0;
+            pop.desired_consumption_ema.insert(GRAIN, 1.0);
+        }
+    }
+    world.set_external_market(config);
+    (world, port, inland)
+}
+
+fn imports(world: &World, settlement: SettlementId) -> f64 {
+    world
+        .outside_flow_totals
+        .imports_qty
+        .get(&(settlement, GRAIN))
+        .copied()
+        .unwrap_or(0.0)
+}
+
+#[test]
+fn roles_derive_from_coastal_slots_unless_set() {
+    let (mut world, port, inland) = port_and_inland(true);
+    assert_eq!(
+        world.get_settlement(port).unwrap().role(),
+        SettlementRole::Port
+    );
+    assert_eq!(
+        world.get_settlement(inland).unwrap().role(),
+        SettlementRole::Inland
+    );
+
+    world.get_settlement_mut(inland).unwrap().explicit_role = Some(SettlementRole::RiverPort);
+    assert!(
+        world
+            .get_settlement(inland)
+            .unwrap()
+            .role()
+            .has_outside_access()
+    );
+}
+
+#[test]
+fn port_only_market_skips_inland_settlements() {
+    let (mut world, port, inland) = port_and_inland(true);
+    run_ticks(&mut world, 5, &[make_grain_recipe(1.0)]);
+    assert!(imports(&world, port) > 0.0);
+    assert_eq!(imports(&world, inland), 0.0);
+
+    // Same setup without the gate: the inland town imports directly
+    let (mut world, _, inland) = port_and_inland(false);
+    run_ticks(&mut world, 5, &[make_grain_recipe(1.0)]);
+    assert!(imports(&world, inland) > 0.0);
+}
+
+#[test]
+fn port_dependence_counts_direct_deliveries_from_ports() {
+    let mut world = World::with_seed(9);
+    let port = world.add_settlement("Port", (0.0, 0.0));
+    let town = world.add_settlement("Town", (2.0, 0.0));
+    let village = world.add_settlement("Village", (4.0, 0.0));
+    world.get_settlement_mut(port).unwrap().explicit_role = Some(SettlementRole::Port);
+    world.add_route(port, town, 2);
+    world.add_route(town, village, 2);
+
+    let merchant = world.add_merchant();
+    {
+        let m = world.get_merchant_mut(merchant).unwrap();
+        m.currency = 500.0;
+        m.stockpile_at(port).add(GRAIN, 40.0);
+    }
+    let caravan = world
+        .add_vessel(VesselKind::Caravan, port, merchant)
+        .unwrap();
+
+    world.load_vessel(caravan, GRAIN, 20.0).unwrap();
+    world.dispatch_vessel(caravan, town).unwrap();
+    run_ticks(&mut world, 2, &[make_grain_recipe(1.0)]);
+    world.load_vessel(caravan, GRAIN, 5.0).unwrap();
+    world.dispatch_vessel(caravan, village).unwrap();
+    run_ticks(&mut world, 2, &[make_grain_recipe(1.0)]);
+
+    let report = world.port_dependence();
+    assert_eq!(
+        report.iter().map(|d| d.settlement).collect::<Vec<_>>(),
+        vec![town, village]
+    );
+
+    let town_dep = &report[0];
+    assert_eq!(town_dep.nearest_port, Some((port, 2)));
+    assert_eq!(town_dep.delivered[&GRAIN], 20.0);
+    assert_eq!(town_dep.port_share(), 1.0);
+
+    // Relayed through the town, so none of it counts as a port flow
+    let village_dep = &report[1];
+    assert_eq!(village_dep.nearest_port, Some((port, 4)));
+    assert_eq!(village_dep.delivered[&GRAIN], 5.0);
+    assert_eq!(village_dep.good_port_share(GRAIN), 0.0);
+}
@@ -83,7 +83,7 @@ fn create_needs() -> HashMap<String, Need> {
 
 // === HELPER FUNCTIONS ===
 
-/// Calculate total currency across all pops and merchants
+/// Calculate total currency across all pops, merchants and facility treasuries
 fn total_currency(world: &World) -> f64 {
     let pop_currency: f64 = world
         .settlements
@@ -92,7 +92,13 @@ fn total_currency(world: &World) -> f64 {
         .map(|p| p.currency)
         .sum();
     let merchant_currency: f64 = world.merchants.values().map(|m| m.currency).sum();
-    pop_currency + merchant_currency
+    let facility_currency: f64 = world
+        .settlements
+        .values()
+        .flat_map(|s| s.facilities.values())
+        .map(|f| f.currency)
+        .sum();
+    pop_currency + merchant_currency + facility_currency
 }
 
 /// Calculate total stock of a good across all pops and merchants
@@ -0,0 +1,83 @@
+use sim_core::{Scenario, ScenarioError};
+
+const SCENARIO: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/scenarios/two_towns.json");
+
+#[test]
+fn sample_scenario_builds_and_runs() {
+    let scenario = Scenario::load(SCENARIO).expect("sample scenario should load");
+    let mut sim = scenario.build(None).expect("sample scenario should build");
+
+    let world = &sim.world;
+    assert_eq!(world.settlements.len(), 2);
+    assert_eq!(world.routes.len(), 1);
+    assert_eq!(world.merchants.len(), 1);
+    let pops: usize = world.settlements.values().map(|s| s.pops.len()).sum();
+    assert_eq!(pops, 60);
+    let facilities: usize = world.settlements.values().map(|s| s.facilities.len()).sum();
+    assert_eq!(facilities, 2);
+    assert!(world.external_market.is_some());
+    assert!(world.subsistence_reservation.is_some());
+
+    for _ in 0..20 {
+        sim.run_tick();
+    }
+    assert_eq!(sim.world.tick, 20);
+    assert_eq!(sim.world.stock_flow_history.len(), 20);
+}
+
+#[test]
+fn seed_override_is_deterministic() {
+    let scenario = Scenario::load(SCENARIO).unwrap();
+    let run = |seed| {
+        let mut sim = scenario.build(Some(seed)).unwrap();
+        for _ in 0..30 {
+            sim.run_tick();
+        }
+        let mut currencies: Vec<u64> = sim
+            .world
+            .merchants
+            .values()
+            .map(|m| m.currency.to_bits())
+            .collect();
+        currencies.sort();
+        currencies
+    };
+    assert_eq!(run(5), run(5));
+}
+
+#[test]
+fn unknown_names_are_rejected() {
+    let json = r#"{
+        "content": { "goods": [{ "name": "grain" }] },
+        "settlements": [
+            { "name": "Town", "prices": { "salt": 3.0 } }
+        ]
+    }"#;
+    let err = Scenario::from_json(json)
+        .unwrap()
+        .build(Some(1))
+        .err()
+        .unwrap();
+    assert!(
+        matches!(&err, ScenarioError::Unknown { kind: "good", name } if name == "salt"),
+        "unexpected error: {err}"
+    );
+
+    let json = r#"{
+        "content": { "goods": [{ "name": "grain" }] },
+        "settlements": [{ "name": "Town" }],
+        "routes": [{ "from": "Town", "to": "Nowhere", "distance": 2 }]
+    }"#;
+    let err = Scenario::from_json(json)
+        .unwrap()
+        .build(Some(1))
+        .err()
+        .unwrap();
+    assert!(matches!(
+        err,
+        ScenarioError::Unknown {
+            kind: "settlement",
+            ..
+        }
+    ));
+}
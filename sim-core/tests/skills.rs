@@ -0,0 +1,152 @@
+#[allow(dead_code)]
+mod common;
+
+use common::*;
+use sim_core::{FacilityType, Recipe, RecipeId, SkillDef, SkillId, SkillLearningConfig, World};
+
+const SMITH: SkillId = SkillId(2);
+
+/// One farm wanting a laborer and one pop who only knows smithing.
+fn smith_and_farm(registered: bool) -> World {
+    let mut world = World::new();
+    let settlement = world.add_settlement("Solo", (0.0, 0.0));
+    let merchant = world.add_merchant();
+    world
+        .get_merchant_mut(merchant)
+        .expect("merchant should exist")
+        .currency = 1_000.0;
+
+    let facility = world
+        .add_facility(FacilityType::Farm, settlement, merchant)
+        .expect("facility should be created");
+    {
+        let f = world.facility_mut(facility).expect("facility should exist");
+        f.capacity = 1;
+        f.recipe_priorities = vec![RecipeId::new(1)];
+    }
+    world
+        .settlements
+        .get_mut(&settlement)
+        .expect("settlement should exist")
+        .wage_ema
+        .insert(LABORER, 1.0);
+
+    let handle = world.add_pop(settlement).expect("pop should be created");
+    let pop = world.pop_mut(handle).expect("pop should exist");
+    pop.skills.insert(SMITH);
+    pop.min_wage = 0.0;
+    pop.desired_consumption_ema.insert(GRAIN, 1.0);
+
+    if registered {
+        world.set_skill_defs(skill_defs());
+    }
+    world
+}
+
+fn skill_defs() -> Vec<SkillDef> {
+    vec![
+        SkillDef {
+            id: LABORER,
+            name: "laborer".to_string(),
+            parent: None,
+            reservation_wage: 0.0,
+        },
+        SkillDef {
+            id: SMITH,
+            name: "smith".to_string(),
+            parent: Some(LABORER),
+            reservation_wage: 0.0,
+        },
+    ]
+}
+
+fn employed_skills(world: &World) -> Vec<Option<SkillId>> {
+    world
+        .settlements
+        .values()
+        .flat_map(|s| s.pops.values())
+        .map(|p| p.employed_skill)
+        .collect()
+}
+
+#[test]
+fn registered_specialist_fills_parent_slot() {
+    let good_profiles = make_grain_profile();
+    let needs = make_food_need(1.0);
+    let recipes = vec![make_grain_recipe(1.0)];
+
+    let mut unregistered = smith_and_farm(false);
+    unregistered.run_tick(&good_profiles, &needs, &recipes);
+    assert_eq!(employed_skills(&unregistered), vec![None]);
+
+    let mut registered = smith_and_farm(true);
+    registered.run_tick(&good_profiles, &needs, &recipes);
+    assert_eq!(employed_skills(&registered), vec![Some(LABORER)]);
+}
+
+#[test]
+fn unemployed_laborer_pays_a_master_to_learn_smithing() {
+    let mut world = World::new();
+    world.mortality_grace_ticks = 1_000;
+    world.set_skill_defs(skill_defs());
+    world.set_skill_learning(SkillLearningConfig::default());
+    let settlement = world.add_settlement("Solo", (0.0, 0.0));
+    let merchant = world.add_merchant();
+    world
+        .get_merchant_mut(merchant)
+        .expect("merchant should exist")
+        .currency = 1_000.0;
+
+    let facility = world
+        .add_facility(FacilityType::Farm, settlement, merchant)
+        .expect("facility should be created");
+    {
+        let f = world.facility_mut(facility).expect("facility should exist");
+        f.capacity = 1;
+        f.recipe_priorities = vec![RecipeId::new(1)];
+    }
+    world
+        .settlements
+        .get_mut(&settlement)
+        .expect("settlement should exist")
+        .wage_ema
+        .insert(SMITH, 20.0);
+
+    let mut handles = Vec::new();
+    for skills in [vec![LABORER, SMITH], vec![LABORER]] {
+        let handle = world.add_pop(settlement).expect("pop should be created");
+        let pop = world.pop_mut(handle).expect("pop should exist");
+        pop.skills.extend(skills);
+        pop.min_wage = 0.0;
+        pop.income_ema = 0.0;
+        pop.currency = 100.0;
+        pop.stocks.insert(GRAIN, 20.0);
+        pop.desired_consumption_ema.insert(GRAIN, 1.0);
+        handles.push(handle);
+    }
+
+    let good_profiles = make_grain_profile();
+    let needs = make_food_need(1.0);
+    let recipes = vec![
+        Recipe::new(RecipeId::new(1), "Smithing", vec![FacilityType::Farm])
+            .with_capacity_cost(1)
+            .with_worker(SMITH, 1)
+            .with_output(GRAIN, 1.0),
+    ];
+    for _ in 0..12 {
+        world.run_tick(&good_profiles, &needs, &recipes);
+    }
+
+    let master = world.pop(handles[0]).expect("master should exist");
+    let apprentice = world.pop(handles[1]).expect("apprentice should exist");
+    assert_eq!(master.employed_skill, Some(SMITH));
+    assert!(apprentice.skills.contains(&SMITH));
+    for flow in &world.stock_flow_history {
+        assert!(
+            flow.currency_residual.abs() < 1e-6,
+            "tick {} residual {}",
+            flow.tick,
+            flow.currency_residual
+        );
+    }
+}
@@ -0,0 +1,160 @@
+#[allow(dead_code)]
+mod common;
+
+use common::*;
+use sim_core::{
+    FacilityType, RecipeId, SNAPSHOT_FORMAT_VERSION, SnapshotError, SubsistenceReservationConfig,
+    World, pop_key_u64,
+};
+
+fn build_world() -> World {
+    let mut world = World::with_seed(11);
+
+    let settlement = world.add_settlement("Harbor", (0.0, 0.0));
+    let merchant = world.add_merchant();
+    // Subsistence keeps a population alive while crowding drives deaths and
+    // births, so pop slots are freed and reused across the snapshot point.
+    world.set_subsistence_reservation(SubsistenceReservationConfig::new(
+        GRAIN, 1.5, 20, 10.0, 0.10,
+    ));
+
+    let facility = world
+        .add_facility(FacilityType::Farm, settlement, merchant)
+        .expect("facility should be created");
+    {
+        let f = world.facility_mut(facility).expect("facility should exist");
+        f.capacity = 8;
+        f.recipe_priorities = vec![RecipeId::new(1)];
+    }
+    world
+        .settlements
+        .get_mut(&settlement)
+        .expect("settlement should exist")
+        .facility_bid_states
+        .get_mut(facility.key)
+        .expect("bid state should exist")
+        .bids
+        .insert(LABORER, 2.0);
+
+    for i in 0..30 {
+        let handle = world.add_pop(settlement).expect("pop should be created");
+        let pop = world.pop_mut(handle).expect("pop should exist");
+        pop.skills.insert(LABORER);
+        pop.min_wage = 0.5;
+        pop.currency = 20.0 + i as f64;
+        pop.stocks.insert(GRAIN, 1.0);
+        pop.desired_consumption_ema.insert(GRAIN, 1.0);
+    }
+    world
+}
+
+/// Order-independent digest of the state a resumed run must reproduce.
+#[derive(Debug, PartialEq)]
+struct Digest {
+    tick: u64,
+    pops: Vec<(u64, u64, Option<u64>)>,
+    merchant_currency: Vec<u64>,
+    history: Vec<(u64, u64)>,
+}
+
+fn digest(world: &World) -> Digest {
+    let mut pops: Vec<(u64, u64, Option<u64>)> = world
+        .settlements
+        .values()
+        .flat_map(|s| {
+            s.pops.iter().map(|(k, p)| {
+                (
+                    pop_key_u64(k),
+                    p.currency.to_bits(),
+                    p.stocks.get(&GRAIN).map(|q| q.to_bits()),
+                )
+            })
+        })
+        .collect();
+    pops.sort();
+    let mut merchant_currency: Vec<u64> = world
+        .merchants
+        .values()
+        .map(|m| m.currency.to_bits())
+        .collect();
+    merchant_currency.sort();
+    Digest {
+        tick: world.tick,
+        pops,
+        merchant_currency,
+        history: world
+            .stock_flow_history
+            .iter()
+            .map(|f| (f.tick, f.currency_after.to_bits()))
+            .collect(),
+    }
+}
+
+#[test]
+fn resumed_run_matches_continuous_run() {
+    let mut continuous = build_world();
+    run_ticks(&mut continuous, 25, &[make_grain_recipe(1.5)]);
+
+    let json = continuous
+        .to_snapshot_json()
+        .expect("snapshot should serialize");
+    let mut resumed = World::from_snapshot_json(&json).expect("snapshot should load");
+    assert_eq!(digest(&resumed), digest(&continuous));
+
+    run_ticks(&mut continuous, 40, &[make_grain_recipe(1.5)]);
+    run_ticks(&mut resumed, 40, &[make_grain_recipe(1.5)]);
+
+    let pop_count: usize = continuous.settlements.values().map(|s| s.pops.len()).sum();
+    assert!(pop_count > 0, "scenario should keep some pops alive");
+    assert_eq!(
+        digest(&resumed),
+        digest(&continuous),
+        "resumed run should continue bit-identically"
+    );
+}
+
+#[test]
+fn resumed_run_keeps_merchant_market_knowledge() {
+    let mut continuous = build_world();
+    run_ticks(&mut continuous, 10, &[make_grain_recipe(1.5)]);
+    let json = continuous
+        .to_snapshot_json()
+        .expect("snapshot should serialize");
+    let mut resumed = World::from_snapshot_json(&json).expect("snapshot should load");
+
+    run_ticks(&mut continuous, 1, &[make_grain_recipe(1.5)]);
+    run_ticks(&mut resumed, 1, &[make_grain_recipe(1.5)]);
+
+    let knowledge = |world: &World| {
+        let merchant = world.merchants.values().next().expect("merchant exists");
+        let settlement = *world.settlements.keys().next().expect("settlement exists");
+        merchant.knowledge.get(settlement).cloned()
+    };
+    let expected = knowledge(&continuous).expect("owner observes its market");
+    assert!(!expected.depth.is_empty(), "market should have traded");
+    assert_eq!(knowledge(&resumed), Some(expected));
+}
+
+#[test]
+fn snapshot_round_trips_through_file() {
+    let mut world = build_world();
+    run_ticks(&mut world, 5, &[make_grain_recipe(1.5)]);
+
+    let path = std::env::temp_dir().join(format!("sim_core_snapshot_{}.json", std::process::id()));
+    world.save_snapshot(&path).expect("snapshot should save");
+    let loaded = World::load_snapshot(&path).expect("snapshot should load");
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(digest(&loaded), digest(&world));
+}
+
+#[test]
+fn snapshot_rejects_unknown_version() {
+    let world = build_world();
+    let json = world.to_snapshot_json().expect("snapshot should serialize");
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    value["version"] = serde_json::json!(SNAPSHOT_FORMAT_VERSION + 1);
+
+    let err = World::from_snapshot_json(&value.to_string()).unwrap_err();
+    assert!(matches!(err, SnapshotError::UnsupportedVersion { .. }));
+}
@@ -0,0 +1,75 @@
+#[allow(dead_code)]
+mod common;
+
+use common::*;
+use sim_core::{FacilityType, GoodId, Recipe, RecipeId, SettlementId, World};
+
+const TOOLS: GoodId = 9;
+
+/// One-worker farms owned by the same merchant, with `tools` in its
+/// stockpile and two laborers per farm.
+fn farm_town(farms: usize, tools: f64) -> (World, SettlementId) {
+    let mut world = World::with_seed(2);
+    world.mortality_grace_ticks = 1_000;
+    let town = world.add_settlement("Town", (0.0, 0.0));
+    let merchant = world.add_merchant();
+    {
+        let owner = world.get_merchant_mut(merchant).unwrap();
+        owner.currency = 1_000.0;
+        owner.stockpile_at(town).add(TOOLS, tools);
+    }
+    for _ in 0..farms {
+        let farm = world
+            .add_facility(FacilityType::Farm, town, merchant)
+            .unwrap();
+        let f = world.facility_mut(farm).unwrap();
+        f.capacity = 1;
+        f.recipe_priorities = vec![RecipeId::new(1)];
+    }
+    world
+        .settlements
+        .get_mut(&town)
+        .unwrap()
+        .wage_ema
+        .insert(LABORER, 1.0);
+    for _ in 0..2 * farms {
+        let handle = world.add_pop(town).unwrap();
+        let pop = world.pop_mut(handle).unwrap();
+        pop.skills.insert(LABORER);
+        pop.min_wage = 0.0;
+        pop.stocks.insert(GRAIN, 20.0);
+        pop.desired_consumption_ema.insert(GRAIN, 1.0);
+    }
+    (world, town)
+}
+
+/// Grain produced at `town` in one tick.
+fn grain_produced(mut world: World, town: SettlementId, recipe: Recipe) -> f64 {
+    world.run_tick(&make_grain_profile(), &make_food_need(1.0), &[recipe]);
+    world.settlements[&town]
+        .flows
+        .produced
+        .get(&GRAIN)
+        .copied()
+        .unwrap_or(0.0)
+}
+
+#[test]
+fn boosters_raise_farm_output_in_a_full_tick() {
+    let recipe = make_grain_recipe(1.0).with_booster(TOOLS, 0.5, 0.02, 0.8);
+
+    let (bare, town) = farm_town(2, 0.0);
+    let (equipped, _) = farm_town(2, 1.0);
+    assert!((grain_produced(bare, town, recipe.clone()) - 1.6).abs() < 1e-9);
+    assert!((grain_produced(equipped, town, recipe) - 2.0).abs() < 1e-9);
+}
+
+#[test]
+fn one_set_of_tools_equips_one_facility() {
+    let recipe = make_grain_recipe(1.0).with_tool(TOOLS, 1.0, 0.0);
+
+    let (one_set, town) = farm_town(2, 1.0);
+    let (two_sets, _) = farm_town(2, 2.0);
+    assert_eq!(grain_produced(one_set, town, recipe.clone()), 1.0);
+    assert_eq!(grain_produced(two_sets, town, recipe), 2.0);
+}
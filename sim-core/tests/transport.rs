@@ -0,0 +1,199 @@
+#[allow(dead_code)]
+mod common;
+
+use common::*;
+use std::collections::HashMap;
+
+use sim_core::{
+    GoodId, MerchantId, RouteKind, SettlementId, TransportError, VesselId, VesselKind,
+    VesselLocation, World,
+};
+
+/// Two ports joined by a three-tick sea lane, no pops, so the only goods
+/// movement is the merchant's cargo.
+fn two_ports() -> (World, SettlementId, SettlementId, MerchantId, VesselId) {
+    let mut world = World::with_seed(3);
+    let a = world.add_settlement("Port A", (0.0, 0.0));
+    let b = world.add_settlement("Port B", (3.0, 0.0));
+    world.add_sea_route(a, b, 3);
+    world.routes[0].risk = 0.0;
+    let merchant = world.add_merchant();
+    world
+        .get_merchant_mut(merchant)
+        .unwrap()
+        .stockpile_at(a)
+        .add(GRAIN, 50.0);
+    let ship = world.add_vessel(VesselKind::Ship, a, merchant).unwrap();
+    {
+        let ship = world.vessel_mut(ship).unwrap();
+        ship.speed = 1;
+        ship.capacity = 30.0;
+        ship.upkeep = 1.0;
+    }
+    (world, a, b, merchant, ship)
+}
+
+#[test]
+fn cargo_arrives_after_route_distance() {
+    let (mut world, a, b, merchant, ship) = two_ports();
+
+    assert_eq!(world.load_vessel(ship, GRAIN, 40.0), Ok(30.0));
+    world.dispatch_vessel(ship, b).unwrap();
+    assert_eq!(
+        world.load_vessel(ship, GRAIN, 1.0),
+        Err(TransportError::NotDocked(ship))
+    );
+
+    run_ticks(&mut world, 2, &[make_grain_recipe(1.0)]);
+    assert!(matches!(
+        world.vessel(ship).unwrap().location,
+        VesselLocation::InTransit {
+            ticks_remaining: 1,
+            ..
+        }
+    ));
+    assert!(
+        !world
+            .get_merchant(merchant)
+            .unwrap()
+            .stockpiles
+            .contains_key(&b)
+    );
+
+    run_ticks(&mut world, 1, &[make_grain_recipe(1.0)]);
+    let vessel = world.vessel(ship).unwrap();
+    assert_eq!(vessel.location, VesselLocation::Docked(b));
+    assert!(vessel.cargo.is_empty());
+    let m = world.get_merchant(merchant).unwrap();
+    assert_eq!(m.stockpiles[&a].get(GRAIN), 20.0);
+    assert_eq!(m.stockpiles[&b].get(GRAIN), 30.0);
+}
+
+#[test]
+fn in_transit_cargo_is_conserved_and_costs_are_explained() {
+    let (mut world, _a, b, merchant, ship) = two_ports();
+    let currency_before = world.get_merchant(merchant).unwrap().currency;
+
+    world.load_vessel(ship, GRAIN, 30.0).unwrap();
+    world.dispatch_vessel(ship, b).unwrap();
+    let freight = world.transport_totals.freight_paid;
+    assert_eq!(freight, 30.0, "default route cost is 1 per unit");
+
+    run_ticks(&mut world, 4, &[make_grain_recipe(1.0)]);
+
+    for flow in &world.stock_flow_history {
+        let grain_delta = flow.goods_delta.get(&GRAIN).copied().unwrap_or(0.0);
+        assert!(
+            grain_delta.abs() < 1e-9,
+            "tick {}: grain moved, not created",
+            flow.tick
+        );
+        assert!(
+            flow.currency_residual.abs() < 1e-9,
+            "tick {}: upkeep should be explained, residual {}",
+            flow.tick,
+            flow.currency_residual
+        );
+        assert_eq!(flow.transport_paid_delta, 1.0);
+    }
+    assert_eq!(
+        world.get_merchant(merchant).unwrap().currency,
+        currency_before - freight - 4.0
+    );
+}
+
+#[test]
+fn vessels_only_use_routes_of_their_kind() {
+    let (mut world, a, b, merchant, _ship) = two_ports();
+    let caravan = world.add_vessel(VesselKind::Caravan, a, merchant).unwrap();
+    assert!(matches!(
+        world.dispatch_vessel(caravan, b),
+        Err(TransportError::NoRoute { .. })
+    ));
+
+    world.add_route(a, b, 5);
+    world.dispatch_vessel(caravan, b).unwrap();
+    assert!(matches!(
+        world.vessel(caravan).unwrap().location,
+        VesselLocation::InTransit {
+            ticks_remaining: 5,
+            ..
+        }
+    ));
+}
+
+/// Sail `trips` legs between the ports, carrying a full hold of grain on
+/// every A→B leg.
+fn run_voyages(risk: f64, seed: u64, trips: usize) -> World {
+    let (mut world, a, b, merchant, ship) = two_ports();
+    world.set_random_seed(seed);
+    world.routes[0].risk = risk;
+    world
+        .get_merchant_mut(merchant)
+        .unwrap()
+        .stockpile_at(a)
+        .add(GRAIN, 30.0 * trips as f64);
+    for _ in 0..trips {
+        let ship_at = world.vessel(ship).unwrap().docked_at().unwrap();
+        if ship_at == a {
+            world.load_vessel(ship, GRAIN, 30.0).unwrap();
+            world.dispatch_vessel(ship, b).unwrap();
+        } else {
+            world.dispatch_vessel(ship, a).unwrap();
+        }
+        while world.vessel(ship).unwrap().docked_at().is_none() {
+            run_ticks(&mut world, 1, &[make_grain_recipe(1.0)]);
+        }
+    }
+    world
+}
+
+#[test]
+fn incidents_destroy_goods_explicitly_and_deterministically() {
+    let world = run_voyages(1.0, 9, 20);
+
+    let mut destroyed = 0.0;
+    for flow in &world.stock_flow_history {
+        let lost = flow.goods_destroyed.get(&GRAIN).copied().unwrap_or(0.0);
+        let delta = flow.goods_delta.get(&GRAIN).copied().unwrap_or(0.0);
+        assert!(
+            (delta + lost).abs() < 1e-9,
+            "tick {}: grain delta {delta} not explained by {lost} destroyed",
+            flow.tick
+        );
+        let grain = |terms: &HashMap<GoodId, f64>| terms.get(&GRAIN).copied().unwrap_or(0.0);
+        let split = grain(&flow.goods_spoiled) + grain(&flow.goods_lost_in_transit);
+        assert!((split - lost).abs() < 1e-9, "tick {}", flow.tick);
+        let residual = grain(&flow.goods_residual);
+        assert!(residual.abs() < 1e-9, "tick {}: {residual}", flow.tick);
+        destroyed += lost;
+    }
+    assert!(
+        destroyed > 0.0,
+        "certain incidents should destroy some cargo"
+    );
+    assert_eq!(world.transport_totals.cargo_lost[&GRAIN], destroyed);
+
+    let again = run_voyages(1.0, 9, 20);
+    assert_eq!(again.tick, world.tick);
+    assert_eq!(
+        again.transport_totals.cargo_lost[&GRAIN].to_bits(),
+        destroyed.to_bits()
+    );
+}
+
+#[test]
+fn sea_lanes_are_risky_by_default() {
+    let mut world = World::with_seed(1);
+    let a = world.add_settlement("A", (0.0, 0.0));
+    let b = world.add_settlement("B", (1.0, 0.0));
+    world.add_route(a, b, 2);
+    world.add_sea_route(a, b, 2);
+    assert_eq!(world.routes[0].kind, RouteKind::Land);
+    assert_eq!(world.routes[0].risk, 0.0);
+    assert_eq!(world.routes[1].kind, RouteKind::Sea);
+    assert!(world.routes[1].risk > 0.1);
+
+    let calm = run_voyages(0.0, 4, 10);
+    assert!(calm.transport_totals.cargo_lost.is_empty());
+}
@@ -0,0 +1,148 @@
+//! `WasmWorld` facade tests.
+//!
+//! Run under node with `wasm-pack test --node sim-core` (or
+//! `cargo test --target wasm32-unknown-unknown` with
+//! `wasm-bindgen-test-runner` installed). The same tests also run natively as
+//! plain `#[test]`s.
+
+use sim_core::wasm::{
+    GoodDef, GoodQuantity, NeedContributionDef, NeedDef, PopDef, RecipeDef, UtilityCurveDef,
+    WasmWorld, WorkerRequirement,
+};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const GRAIN: u32 = 1;
+const LABORER: u32 = 1;
+
+fn build_world() -> WasmWorld {
+    let mut world = WasmWorld::new(7);
+    world.add_need(NeedDef {
+        id: "food".to_string(),
+        utility_curve: UtilityCurveDef::Subsistence {
+            requirement: 1.0,
+            steepness: 5.0,
+        },
+    });
+    world.add_good(GoodDef {
+        good: GRAIN,
+        contributions: vec![NeedContributionDef {
+            need_id: "food".to_string(),
+            efficiency: 1.0,
+        }],
+    });
+    world
+        .add_recipe(RecipeDef {
+            id: 1,
+            name: "Grain Farming".to_string(),
+            facility_types: vec!["Farm".to_string()],
+            capacity_cost: 1,
+            workers: vec![WorkerRequirement {
+                skill: LABORER,
+                count: 1,
+            }],
+            inputs: Vec::new(),
+            outputs: vec![GoodQuantity {
+                good: GRAIN,
+                quantity: 2.0,
+            }],
+        })
+        .unwrap();
+
+    let town = world.add_settlement("Town", 0.0, 0.0);
+    let merchant = world.add_merchant(500.0);
+    world
+        .add_facility("Farm", town, merchant, 10, vec![1])
+        .unwrap();
+    world
+        .add_pops(
+            town,
+            10,
+            PopDef {
+                currency: 50.0,
+                skills: vec![LABORER],
+                min_wage: 0.5,
+                stocks: vec![GoodQuantity {
+                    good: GRAIN,
+                    quantity: 2.0,
+                }],
+            },
+        )
+        .unwrap();
+    world
+}
+
+#[wasm_bindgen_test(unsupported = test)]
+fn run_tick_produces_read_models() {
+    let mut world = build_world();
+    world.run_ticks(5);
+
+    let view = world.view();
+    assert_eq!(view.tick, 5);
+    assert_eq!(world.tick(), 5);
+
+    assert_eq!(view.settlements.len(), 1);
+    let town = &view.settlements[0];
+    assert_eq!(town.name, "Town");
+    assert!(town.pop_count > 0);
+    assert_eq!(town.facility_count, 1);
+    assert!(town.prices.iter().any(|p| p.good == GRAIN && p.price > 0.0));
+
+    assert_eq!(view.merchants.len(), 1);
+    assert_eq!(view.merchants[0].facility_count, 1);
+    assert!(view.merchants[0].currency.is_finite());
+
+    assert!(
+        view.fills
+            .iter()
+            .all(|f| f.settlement == town.id && f.quantity > 0.0),
+        "fills should belong to the only settlement"
+    );
+}
+
+#[wasm_bindgen_test(unsupported = test)]
+fn snapshot_round_trip_through_facade() {
+    let mut world = build_world();
+    world.run_ticks(3);
+    let json = world.save_snapshot().unwrap();
+
+    let mut restored = build_world();
+    restored.load_snapshot(&json).unwrap();
+    assert_eq!(restored.tick(), 3);
+
+    world.run_ticks(3);
+    restored.run_ticks(3);
+    assert_eq!(
+        world.view().merchants[0].currency.to_bits(),
+        restored.view().merchants[0].currency.to_bits()
+    );
+}
+
+#[wasm_bindgen_test(unsupported = test)]
+fn content_pack_replaces_definitions() {
+    let mut world = WasmWorld::new(1);
+    world
+        .load_content(include_str!("../content/base.json"))
+        .unwrap();
+    let town = world.add_settlement("Town", 0.0, 0.0);
+    let merchant = world.add_merchant(500.0);
+    world
+        .add_facility("Farm", town, merchant, 4, vec![1])
+        .unwrap();
+    world
+        .add_pops(
+            town,
+            4,
+            PopDef {
+                currency: 10.0,
+                skills: vec![LABORER],
+                min_wage: 0.5,
+                stocks: vec![GoodQuantity {
+                    good: GRAIN,
+                    quantity: 1.0,
+                }],
+            },
+        )
+        .unwrap();
+    world.run_ticks(3);
+    assert_eq!(world.view().tick, 3);
+}